@@ -0,0 +1,285 @@
+use crate::{Camera, Canvas, Material, Matrix, Motion, RenderSettings, Shape, SingleColorPattern, Tuples, World};
+use crate::render::render_with_settings;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// how values change between two keyframes
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    Smooth, // eases out of every keyframe and into the next one
+}
+
+// values that can be blended, f is 0 at a and 1 at b
+pub trait Interpolate: Clone {
+    fn interpolate(a: &Self, b: &Self, f: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(a: &f64, b: &f64, f: f64) -> f64 {
+        a + (b - a) * f
+    }
+}
+
+impl Interpolate for Tuples {
+    fn interpolate(a: &Tuples, b: &Tuples, f: f64) -> Tuples {
+        a.clone().add(&b.clone().subtract(a).scale(f))
+    }
+}
+
+// rotations are interpolated along the shortest arc, like the transforms of a moving shape
+impl Interpolate for Matrix {
+    fn interpolate(a: &Matrix, b: &Matrix, f: f64) -> Matrix {
+        Motion::new(a, b).transform_at(f)
+    }
+}
+
+// a value that changes over time. Before the first and after the last keyframe the value
+// stays at that keyframe
+#[derive(Debug, Clone)]
+pub struct Track<T> {
+    keys: Vec<(f64, T)>,
+    interpolation: Interpolation,
+}
+
+impl<T: Interpolate> Track<T> {
+    pub fn new(interpolation: Interpolation) -> Track<T> {
+        Track { keys: vec![], interpolation }
+    }
+
+    pub fn with_key(mut self, time: f64, value: T) -> Track<T> {
+        let i = self.keys.partition_point(|(t, _)| *t <= time);
+        self.keys.insert(i, (time, value));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn value_at(&self, time: f64) -> T {
+        if self.keys.is_empty() {
+            panic!("Track.value_at: the track has no keyframes");
+        }
+        let next = self.keys.partition_point(|(t, _)| *t <= time);
+        if next == 0 {
+            return self.keys[0].1.clone();
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1].1.clone();
+        }
+        let ((t0, a), (t1, b)) = (&self.keys[next - 1], &self.keys[next]);
+        let mut f = (time - t0) / (t1 - t0);
+        if self.interpolation == Interpolation::Smooth {
+            f = f * f * (3.0 - 2.0 * f);
+        }
+        T::interpolate(a, b, f)
+    }
+}
+
+// material values with a single number that can be animated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaterialValue {
+    Ambient,
+    Diffuse,
+    Specular,
+    Shininess,
+    Reflective,
+    Transparency,
+    RefractiveIndex,
+}
+
+impl MaterialValue {
+    pub fn set(&self, material: &mut Material, value: f64) {
+        match self {
+            MaterialValue::Ambient => material.ambient = value,
+            MaterialValue::Diffuse => material.diffuse = value,
+            MaterialValue::Specular => material.specular = value,
+            MaterialValue::Shininess => material.shininess = value,
+            MaterialValue::Reflective => material.reflective = value,
+            MaterialValue::Transparency => material.transparency = value,
+            MaterialValue::RefractiveIndex => material.refractive_index = value,
+        }
+    }
+}
+
+// something in the scene that follows a track
+#[derive(Debug, Clone)]
+pub enum Channel {
+    CameraTransform(Track<Matrix>),
+    CameraPath { from: Track<Tuples>, to: Track<Tuples>, up: Tuples }, // camera at from looking at to
+    CameraOrbit { center: Tuples, from: Tuples, up: Tuples, angle: Track<f64> }, // from turned around the y axis through center, looking at center
+    ObjectTransform(Arc<RwLock<dyn Shape>>, Track<Matrix>),
+    LightPosition(usize, Track<Tuples>), // index into the lights of the world
+    MaterialColor(Arc<RwLock<dyn Shape>>, Track<Tuples>), // replaces the pattern with a single color
+    Material(Arc<RwLock<dyn Shape>>, MaterialValue, Track<f64>),
+}
+
+impl Channel {
+    // a track without keyframes has no value at any time
+    fn has_keyframes(&self) -> bool {
+        match self {
+            Channel::CameraTransform(track) | Channel::ObjectTransform(_, track) => !track.is_empty(),
+            Channel::CameraPath { from, to, .. } => !from.is_empty() && !to.is_empty(),
+            Channel::CameraOrbit { angle, .. } | Channel::Material(_, _, angle) => !angle.is_empty(),
+            Channel::LightPosition(_, track) | Channel::MaterialColor(_, track) => !track.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AnimationError {
+    pub message: String,
+}
+
+impl fmt::Display for AnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AnimationError {}
+
+// frames spread over the time from 0 to duration, every frame sets all channels to their
+// value at the time of the frame before it is rendered
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    pub frames: usize,
+    pub duration: f64,
+    pub looping: bool, // the frame after the last one would be the first again, so the last frame stops short of duration
+    channels: Vec<Channel>,
+}
+
+impl Animation {
+    pub fn new(frames: usize, duration: f64) -> Animation {
+        Animation { frames, duration, looping: false, channels: vec![] }
+    }
+
+    // the camera circles once around center, starting at from
+    pub fn turntable(frames: usize, center: &Tuples, from: &Tuples, up: &Tuples) -> Animation {
+        let mut animation = Animation::new(frames, 1.0);
+        animation.looping = true;
+        let angle = Track::new(Interpolation::Linear).with_key(0.0, 0.0).with_key(1.0, 2.0 * std::f64::consts::PI);
+        animation.channels.push(Channel::CameraOrbit { center: *center, from: *from, up: *up, angle });
+        animation
+    }
+
+    // the camera moves through the waypoints in duration, always looking at the next one
+    // or past the last one in the direction it came from
+    pub fn fly_through(frames: usize, duration: f64, waypoints: &[Tuples], up: &Tuples, interpolation: Interpolation) -> Result<Animation, AnimationError> {
+        if waypoints.len() < 2 {
+            return Err(AnimationError { message: format!("Animation.fly_through: at least two waypoints are needed, got {}", waypoints.len()) });
+        }
+        let step = duration / (waypoints.len() - 1) as f64;
+        let mut from = Track::new(interpolation);
+        let mut to = Track::new(interpolation);
+        for (i, p) in waypoints.iter().enumerate() {
+            from = from.with_key(i as f64 * step, *p);
+            let target = match waypoints.get(i + 1) {
+                Some(next) => *next,
+                None => p.clone().add(&p.clone().subtract(&waypoints[i - 1])),
+            };
+            to = to.with_key(i as f64 * step, target);
+        }
+        let mut animation = Animation::new(frames, duration);
+        animation.channels.push(Channel::CameraPath { from, to, up: *up });
+        Ok(animation)
+    }
+
+    // the world is the one the animation is applied to, channels have to refer to things in it
+    pub fn add(&mut self, channel: Channel, world: &World) -> Result<(), AnimationError> {
+        if !channel.has_keyframes() {
+            return Err(AnimationError { message: "Animation.add: every track of the channel needs at least one keyframe".to_string() });
+        }
+        if let Channel::LightPosition(i, _) = channel {
+            if i >= world.get_lights().len() {
+                return Err(AnimationError { message: format!("Animation.add: light {} doesn't exist, the world has {} lights", i, world.get_lights().len()) });
+            }
+        }
+        self.channels.push(channel);
+        Ok(())
+    }
+
+    pub fn channels(&self) -> &Vec<Channel> {
+        &self.channels
+    }
+
+    pub fn time_of_frame(&self, frame: usize) -> f64 {
+        let steps = if self.looping { self.frames } else { self.frames.saturating_sub(1) };
+        if steps == 0 {
+            return 0.0;
+        }
+        self.duration * frame as f64 / steps as f64
+    }
+
+    // puts camera and world into their state at the given time
+    pub fn apply(&self, camera: &mut Camera, world: &mut World, time: f64) {
+        for channel in self.channels.iter() {
+            match channel {
+                Channel::CameraTransform(track) => camera.transform = track.value_at(time),
+                Channel::CameraPath { from, to, up } => {
+                    camera.transform = Matrix::view_transform(&from.value_at(time), &to.value_at(time), up);
+                },
+                Channel::CameraOrbit { center, from, up, angle } => {
+                    let offset = Matrix::rotate_y(angle.value_at(time)) * from.clone().subtract(center);
+                    let position = center.clone().add(&offset);
+                    camera.transform = Matrix::view_transform(&position, center, up);
+                },
+                Channel::ObjectTransform(shape, track) => {
+                    shape.write().unwrap().set_transform(&track.value_at(time));
+                },
+                Channel::LightPosition(i, track) => {
+                    // lights removed after the channel was added stay where they are
+                    if let Some(light) = world.get_mut_lights().get_mut(*i) {
+                        light.set_position(&track.value_at(time));
+                    }
+                },
+                Channel::MaterialColor(shape, track) => {
+                    shape.write().unwrap().get_mut_material().pattern = SingleColorPattern::new(track.value_at(time));
+                },
+                Channel::Material(shape, value, track) => {
+                    value.set(shape.write().unwrap().get_mut_material(), track.value_at(time));
+                },
+            }
+        }
+    }
+
+    pub fn render_frame(&self, camera: &mut Camera, world: &mut World, settings: &RenderSettings, frame: usize) -> Canvas {
+        self.apply(camera, world, self.time_of_frame(frame));
+        render_with_settings(camera, world, settings)
+    }
+
+    // renders the frames in the range to ppm files named by frame_path, returns the names
+    pub fn render_frames(&self, camera: &mut Camera, world: &mut World, settings: &RenderSettings, frames: Range<usize>, path: &str) -> Result<Vec<String>, std::io::Error> {
+        let mut written = vec![];
+        for frame in frames {
+            let canvas = self.render_frame(camera, world, settings, frame);
+            let name = Animation::frame_path(path, frame);
+            fs::write(&name, canvas.canvas_to_ppm())?;
+            written.push(name);
+        }
+        Ok(written)
+    }
+
+    // the first run of # in path is replaced by the frame number padded to that many digits,
+    // without # four digits are added in front of the extension
+    pub fn frame_path(path: &str, frame: usize) -> String {
+        if let Some(start) = path.find('#') {
+            let width = path[start..].chars().take_while(|c| *c == '#').count();
+            return format!("{}{:0width$}{}", &path[..start], frame, &path[start + width..], width = width);
+        }
+        let file = Path::new(path);
+        match (file.file_stem(), file.extension()) {
+            (Some(stem), Some(extension)) => {
+                let name = format!("{}_{:04}.{}", stem.to_string_lossy(), frame, extension.to_string_lossy());
+                file.with_file_name(name).to_string_lossy().into_owned()
+            },
+            _ => format!("{}_{:04}", path, frame),
+        }
+    }
+}
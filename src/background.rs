@@ -0,0 +1,83 @@
+use crate::Tuples;
+use crate::Canvas;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+// what a ray sees when it leaves the scene without hitting anything
+pub trait Background: Debug + Send + Sync {
+    // direction is normalized and in world space
+    fn color_at(&self, direction: &Tuples) -> Tuples;
+}
+
+#[derive(Debug, Clone)]
+pub struct SolidBackground {
+    color: Tuples,
+}
+
+impl SolidBackground {
+    pub fn new(color: Tuples) -> SolidBackground {
+        SolidBackground { color }
+    }
+}
+
+impl Background for SolidBackground {
+    fn color_at(&self, _direction: &Tuples) -> Tuples {
+        self.color
+    }
+}
+
+// blends from bottom (looking straight down) to top (looking straight up)
+#[derive(Debug, Clone)]
+pub struct GradientBackground {
+    bottom: Tuples,
+    top: Tuples,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: Tuples, top: Tuples) -> GradientBackground {
+        GradientBackground { bottom, top }
+    }
+}
+
+impl Background for GradientBackground {
+    fn color_at(&self, direction: &Tuples) -> Tuples {
+        let t = 0.5 * (direction.y + 1.0);
+        self.bottom.clone().scale(1.0 - t).add(&self.top.clone().scale(t))
+    }
+}
+
+// latitude/longitude image around the scene, the center of the image is in direction -z
+// and the top row straight up
+#[derive(Debug, Clone)]
+pub struct ImageBackground {
+    image: Canvas,
+}
+
+impl ImageBackground {
+    pub fn new(image: Canvas) -> ImageBackground {
+        ImageBackground { image }
+    }
+
+    pub fn image(&self) -> &Canvas {
+        &self.image
+    }
+
+    // u grows towards +x, v from top to bottom, both in 0..1
+    pub fn direction_to_uv(direction: &Tuples) -> (f64, f64) {
+        let u = 0.5 + direction.x.atan2(-direction.z) / (2.0 * PI);
+        let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+        (u, v)
+    }
+
+    pub fn uv_to_direction(u: f64, v: f64) -> Tuples {
+        let (phi, theta) = ((u - 0.5) * 2.0 * PI, v * PI);
+        Tuples::vector(theta.sin() * phi.sin(), theta.cos(), -theta.sin() * phi.cos())
+    }
+}
+
+impl Background for ImageBackground {
+    fn color_at(&self, direction: &Tuples) -> Tuples {
+        let (u, v) = ImageBackground::direction_to_uv(direction);
+        self.image.sample_wrapped(u, v)
+    }
+}
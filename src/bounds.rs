@@ -0,0 +1,153 @@
+use crate::Tuples;
+use crate::Matrix;
+use crate::Ray;
+use crate::utils::EPSILON;
+
+// axis aligned bounding box, components may be infinite for unbounded shapes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Tuples,
+    pub max: Tuples,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Bounds::empty()
+    }
+}
+
+impl Bounds {
+    pub fn new(min: Tuples, max: Tuples) -> Bounds {
+        Bounds { min, max }
+    }
+
+    // contains nothing, adding a point or a box to it yields that point or box
+    pub fn empty() -> Bounds {
+        Bounds::new(
+            Tuples::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Tuples::point(-f64::INFINITY, -f64::INFINITY, -f64::INFINITY),
+        )
+    }
+
+    pub fn infinite() -> Bounds {
+        Bounds::new(
+            Tuples::point(-f64::INFINITY, -f64::INFINITY, -f64::INFINITY),
+            Tuples::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min.get_at_idx(i) > self.max.get_at_idx(i))
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        !self.is_empty() && (0..3).any(|i| !self.min.get_at_idx(i).is_finite() || !self.max.get_at_idx(i).is_finite())
+    }
+
+    pub fn add_point(&mut self, p: &Tuples) {
+        for i in 0..3 {
+            self.min.set_at_idx(i, self.min.get_at_idx(i).min(p.get_at_idx(i)));
+            self.max.set_at_idx(i, self.max.get_at_idx(i).max(p.get_at_idx(i)));
+        }
+    }
+
+    pub fn add_bounds(&mut self, b: &Bounds) {
+        if b.is_empty() {
+            return;
+        }
+        self.add_point(&b.min);
+        self.add_point(&b.max);
+    }
+
+    pub fn merge(a: &Bounds, b: &Bounds) -> Bounds {
+        let mut out = *a;
+        out.add_bounds(b);
+        out
+    }
+
+    pub fn contains_point(&self, p: &Tuples) -> bool {
+        (0..3).all(|i| self.min.get_at_idx(i) <= p.get_at_idx(i) && p.get_at_idx(i) <= self.max.get_at_idx(i))
+    }
+
+    pub fn contains_bounds(&self, b: &Bounds) -> bool {
+        self.contains_point(&b.min) && self.contains_point(&b.max)
+    }
+
+    pub fn centroid(&self) -> Tuples {
+        Tuples::point(
+            (self.min.x + self.max.x) * 0.5,
+            (self.min.y + self.max.y) * 0.5,
+            (self.min.z + self.max.z) * 0.5,
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let dx = self.max.x - self.min.x;
+        let dy = self.max.y - self.min.y;
+        let dz = self.max.z - self.min.z;
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    // transforms all 8 corners and fits a new box around them, done per matrix entry
+    // so that infinite components don't turn into NaN (0 * inf)
+    pub fn transform(b: &Bounds, m: &Matrix) -> Bounds {
+        if b.is_empty() {
+            return *b;
+        }
+        let mut out = Bounds::new(
+            Tuples::point(m.get(0, 3), m.get(1, 3), m.get(2, 3)),
+            Tuples::point(m.get(0, 3), m.get(1, 3), m.get(2, 3)),
+        );
+        for row in 0..3 {
+            for col in 0..3 {
+                let factor = m.get(row, col);
+                if factor == 0.0 {
+                    continue;
+                }
+                let a = factor * b.min.get_at_idx(col);
+                let c = factor * b.max.get_at_idx(col);
+                out.min.set_at_idx(row, out.min.get_at_idx(row) + a.min(c));
+                out.max.set_at_idx(row, out.max.get_at_idx(row) + a.max(c));
+            }
+        }
+        out
+    }
+
+    // slab test along the whole line, negative t included, intersections behind the
+    // origin are needed to track refraction containers
+    pub fn intersects(&self, r: &Ray) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let mut t_min = -f64::INFINITY;
+        let mut t_max = f64::INFINITY;
+        for i in 0..3 {
+            let origin = r.origin().get_at_idx(i);
+            let direction = r.direction().get_at_idx(i);
+            let min = self.min.get_at_idx(i) - EPSILON;
+            let max = self.max.get_at_idx(i) + EPSILON;
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return false;
+                }
+                continue;
+            }
+            let t0 = (min - origin) / direction;
+            let t1 = (max - origin) / direction;
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+            if t_min > t_max {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn is_equal(&self, other: &Bounds) -> bool {
+        let eq = |a: f64, b: f64| a == b || crate::utils::is_equal_f64(a, b);
+        (0..3).all(|i| eq(self.min.get_at_idx(i), other.min.get_at_idx(i)) && eq(self.max.get_at_idx(i), other.max.get_at_idx(i)))
+    }
+}
@@ -0,0 +1,161 @@
+use crate::Shape;
+use crate::Ray;
+use crate::Tuples;
+use crate::Bounds;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// relative to the cost of intersecting a single object
+const TRAVERSAL_COST: f64 = 0.125;
+const MAX_LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone)]
+enum BvhNode {
+    Leaf { bounds: Bounds, objects: Vec<usize> },
+    Split { bounds: Bounds, left: Box<BvhNode>, right: Box<BvhNode> },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BuildItem {
+    index: usize,
+    bounds: Bounds,
+    centroid: Tuples,
+}
+
+// bounding volume hierarchy over a list of objects owned by a world or a group,
+// nodes refer to the objects by their index in that list
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    root: Option<BvhNode>,
+    unbounded: Vec<usize>, // planes, infinite cylinders, ... are tested against every ray
+    bounds: Bounds,
+}
+
+impl Bvh {
+    pub fn build(objects: &[Arc<RwLock<dyn Shape>>]) -> Bvh {
+        let mut bvh = Bvh::default();
+        let mut items = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            let bounds = <dyn Shape>::parent_space_bounds(object);
+            bvh.bounds.add_bounds(&bounds);
+            if bounds.is_infinite() || bounds.is_empty() {
+                // empty groups stay in here so that children added later are not missed
+                bvh.unbounded.push(index);
+            } else {
+                items.push(BuildItem { index, bounds, centroid: bounds.centroid() });
+            }
+        }
+        if !items.is_empty() {
+            bvh.root = Some(Bvh::build_node(&mut items));
+        }
+        bvh
+    }
+
+    // surface area heuristic: try every split position along every axis and keep the
+    // cheapest, unless a leaf is cheaper
+    fn build_node(items: &mut [BuildItem]) -> BvhNode {
+        let mut bounds = Bounds::empty();
+        for item in items.iter() {
+            bounds.add_bounds(&item.bounds);
+        }
+        let count = items.len();
+        if count == 1 {
+            return BvhNode::Leaf { bounds, objects: vec![items[0].index] };
+        }
+
+        let area = if bounds.surface_area() > 0.0 { bounds.surface_area() } else { 1.0 };
+        let mut best: Option<(usize, usize, f64)> = None; // axis, split position, cost
+        for axis in 0..3 {
+            Bvh::sort_by_axis(items, axis);
+            let mut right_areas = vec![0.0; count];
+            let mut right = Bounds::empty();
+            for i in (1..count).rev() {
+                right.add_bounds(&items[i].bounds);
+                right_areas[i] = right.surface_area();
+            }
+            let mut left = Bounds::empty();
+            for i in 1..count {
+                left.add_bounds(&items[i - 1].bounds);
+                let cost = TRAVERSAL_COST +
+                    (left.surface_area() * i as f64 + right_areas[i] * (count - i) as f64) / area;
+                if best.is_none_or(|(_, _, c)| cost < c) {
+                    best = Some((axis, i, cost));
+                }
+            }
+        }
+
+        let (axis, split, cost) = best.unwrap();
+        if count <= MAX_LEAF_SIZE && count as f64 <= cost {
+            return BvhNode::Leaf { bounds, objects: items.iter().map(|i| i.index).collect() };
+        }
+        Bvh::sort_by_axis(items, axis);
+        let (left_items, right_items) = items.split_at_mut(split);
+        BvhNode::Split {
+            bounds,
+            left: Box::new(Bvh::build_node(left_items)),
+            right: Box::new(Bvh::build_node(right_items)),
+        }
+    }
+
+    fn sort_by_axis(items: &mut [BuildItem], axis: usize) {
+        items.sort_by(|a, b| a.centroid.get_at_idx(axis).partial_cmp(&b.centroid.get_at_idx(axis)).unwrap());
+    }
+
+    // r must be in the space of the objects' parent, i.e. the space the bvh was built in
+    // calls f with the index of every object whose bounds the ray might hit
+    pub fn visit(&self, r: &Ray, mut f: impl FnMut(usize)) {
+        for index in self.unbounded.iter() {
+            f(*index);
+        }
+        if let Some(root) = &self.root {
+            Bvh::visit_node(root, r, &mut f);
+        }
+    }
+
+    fn visit_node(node: &BvhNode, r: &Ray, f: &mut impl FnMut(usize)) {
+        match node {
+            BvhNode::Leaf { bounds, objects: indices } => {
+                if bounds.intersects(r) {
+                    for index in indices.iter() {
+                        f(*index);
+                    }
+                }
+            },
+            BvhNode::Split { bounds, left, right } => {
+                if bounds.intersects(r) {
+                    Bvh::visit_node(left, r, f);
+                    Bvh::visit_node(right, r, f);
+                }
+            },
+        }
+    }
+
+    // bounds of all objects, infinite if any of them is unbounded
+    pub fn bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+
+    pub fn depth(&self) -> usize {
+        fn node_depth(node: &BvhNode) -> usize {
+            match node {
+                BvhNode::Leaf { .. } => 1,
+                BvhNode::Split { left, right, .. } => 1 + node_depth(left).max(node_depth(right)),
+            }
+        }
+        self.root.as_ref().map_or(0, node_depth)
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        fn node_leaves(node: &BvhNode) -> usize {
+            match node {
+                BvhNode::Leaf { .. } => 1,
+                BvhNode::Split { left, right, .. } => node_leaves(left) + node_leaves(right),
+            }
+        }
+        self.root.as_ref().map_or(0, node_leaves)
+    }
+
+    pub fn unbounded_count(&self) -> usize {
+        self.unbounded.len()
+    }
+}
@@ -1,4 +1,50 @@
 use crate::{Matrix, Ray, Tuples};
+use std::f64::consts::PI;
+
+// shape of the opening of the lens, out of focus highlights (bokeh) take this shape
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Aperture {
+    #[default]
+    Circle,
+    Polygon(usize), // regular polygon with this many sides and a corner at the top, like the blades of a diaphragm
+}
+
+impl Aperture {
+    // a point on the aperture with a radius of 1, uniformly distributed for u and v uniform in 0..1
+    pub fn sample(&self, u: f64, v: f64) -> (f64, f64) {
+        match *self {
+            Aperture::Circle => {
+                let radius = u.sqrt();
+                let phi = 2.0 * PI * v;
+                (radius * phi.cos(), radius * phi.sin())
+            },
+            Aperture::Polygon(sides) => {
+                // one of the equally large triangles between the center and an edge, then a
+                // point inside of it
+                let sides = sides.max(3);
+                let scaled = u * sides as f64;
+                let edge = (scaled as usize).min(sides - 1);
+                let corner = |i: usize| {
+                    let angle = PI / 2.0 + 2.0 * PI * i as f64 / sides as f64;
+                    (angle.cos(), angle.sin())
+                };
+                let (a, b) = (corner(edge), corner(edge + 1));
+                let distance = (scaled - edge as f64).sqrt();
+                (distance * (a.0 * (1.0 - v) + b.0 * v), distance * (a.1 * (1.0 - v) + b.1 * v))
+            },
+        }
+    }
+}
+
+// how directions around the camera are laid out on the image
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Projection {
+    #[default]
+    Perspective, // a pinhole, fov spans the longer side of the image
+    Orthographic { size: f64 }, // parallel rays, size is the extent of the view along the longer side in world units
+    Fisheye, // equidistant, the angle to the view direction grows with the distance from the center and reaches fov / 2 at the middle of the longer edges
+    Equirectangular, // the whole sphere around the camera, longitude across and latitude down the image
+}
 
 #[derive(Debug, Default)]
 pub struct Camera {
@@ -9,6 +55,12 @@ pub struct Camera {
     pub pixel_size: f64,
     pub half_width: f64,
     pub half_height: f64,
+    pub projection: Projection,
+    pub aperture_radius: f64, // 0 for a pinhole camera where everything is in focus, only used for the perspective projection
+    pub focal_distance: f64, // distance from the camera to the plane that is in focus
+    pub aperture: Aperture,
+    pub shutter_open: f64, // rays are spread over the time from open to close, moving shapes blur
+    pub shutter_close: f64,
 }
 
 impl Camera {
@@ -19,20 +71,79 @@ impl Camera {
         let half_height = if aspect_ratio >= 1.0 { half_view / aspect_ratio } else { half_view };
         let pixel_size = half_width * 2.0 / h_size as f64;
         Camera {
-            h_size, v_size, fov, transform: Matrix::new(4), pixel_size, half_width, half_height
+            h_size, v_size, fov, transform: Matrix::new(4), pixel_size, half_width, half_height,
+            projection: Projection::Perspective, aperture_radius: 0.0, focal_distance: 1.0, aperture: Aperture::Circle,
+            shutter_open: 0.0, shutter_close: 0.0,
         }
     }
 
     pub fn ray_for_pixel(c: &Camera, x: usize, y: usize) -> Ray {
-        let x_offset = (x as f64 + 0.5) * c.pixel_size;
-        let y_offset = (y as f64 + 0.5) * c.pixel_size;
-        
-        let world_x = c.half_width  - x_offset;
-        let world_y = c.half_height - y_offset;
-
-        let mut pixel = Matrix::inverse(&c.transform).unwrap() * Tuples::point(world_x, world_y, -1.0);
-        let origin = Matrix::inverse(&c.transform).unwrap() * Tuples::point(0.0,0.0,0.0);
-        let direction = pixel.subtract(&origin).normalize();
+        Camera::ray_for_pixel_offset(c, x, y, 0.5, 0.5)
+    }
+
+    // dx and dy are in 0..1, measured from the top left corner of the pixel
+    pub fn ray_for_pixel_offset(c: &Camera, x: usize, y: usize, dx: f64, dy: f64) -> Ray {
+        let (origin, direction) = Camera::camera_space_ray(c, x, y, dx, dy);
+        let inverse = Matrix::inverse(&c.transform).unwrap();
+        let mut direction = &inverse * &direction;
+        Ray::new(&inverse * &origin, direction.normalize())
+    }
+
+    // moment in the shutter interval for u in 0..1
+    pub fn shutter_time(c: &Camera, u: f64) -> f64 {
+        c.shutter_open + (c.shutter_close - c.shutter_open) * u
+    }
+
+    // position on the image relative to its center, -1..1 along the longer side. Positive
+    // towards the left and the top like the image plane of the perspective projection
+    fn image_position(c: &Camera, x: usize, y: usize, dx: f64, dy: f64) -> (f64, f64) {
+        let half = c.h_size.max(c.v_size) as f64 / 2.0;
+        ((c.h_size as f64 / 2.0 - (x as f64 + dx)) / half, (c.v_size as f64 / 2.0 - (y as f64 + dy)) / half)
+    }
+
+    // origin and direction before the camera transform, looking towards -z
+    fn camera_space_ray(c: &Camera, x: usize, y: usize, dx: f64, dy: f64) -> (Tuples, Tuples) {
+        let origin = Tuples::point(0.0, 0.0, 0.0);
+        match c.projection {
+            Projection::Perspective => {
+                // through the image plane at z = -1
+                let world_x = c.half_width - (x as f64 + dx) * c.pixel_size;
+                let world_y = c.half_height - (y as f64 + dy) * c.pixel_size;
+                (origin, Tuples::vector(world_x, world_y, -1.0))
+            },
+            Projection::Orthographic { size } => {
+                let (px, py) = Camera::image_position(c, x, y, dx, dy);
+                (Tuples::point(px * size / 2.0, py * size / 2.0, 0.0), Tuples::vector(0.0, 0.0, -1.0))
+            },
+            Projection::Fisheye => {
+                let (px, py) = Camera::image_position(c, x, y, dx, dy);
+                let theta = px.hypot(py) * c.fov / 2.0;
+                let phi = py.atan2(px);
+                (origin, Tuples::vector(theta.sin() * phi.cos(), theta.sin() * phi.sin(), -theta.cos()))
+            },
+            Projection::Equirectangular => {
+                // the middle of the image looks straight ahead
+                let longitude = (0.5 - (x as f64 + dx) / c.h_size as f64) * 2.0 * PI;
+                let latitude = (0.5 - (y as f64 + dy) / c.v_size as f64) * PI;
+                (origin, Tuples::vector(longitude.sin() * latitude.cos(), latitude.sin(), -longitude.cos() * latitude.cos()))
+            },
+        }
+    }
+
+    // thin lens: the ray starts at a point on the lens picked by lens_u and lens_v in 0..1 and
+    // goes through the point of the focal plane that the pinhole ray through the pixel hits
+    pub fn ray_for_pixel_lens(c: &Camera, x: usize, y: usize, dx: f64, dy: f64, lens_u: f64, lens_v: f64) -> Ray {
+        if c.aperture_radius <= 0.0 || c.projection != Projection::Perspective {
+            return Camera::ray_for_pixel_offset(c, x, y, dx, dy);
+        }
+        let (_, pinhole) = Camera::camera_space_ray(c, x, y, dx, dy);
+        let (lens_x, lens_y) = c.aperture.sample(lens_u, lens_v);
+
+        let inverse = Matrix::inverse(&c.transform).unwrap();
+        let f = c.focal_distance;
+        let mut focus = &inverse * &Tuples::point(pinhole.x * f, pinhole.y * f, -f);
+        let origin = &inverse * &Tuples::point(lens_x * c.aperture_radius, lens_y * c.aperture_radius, 0.0);
+        let direction = focus.subtract(&origin).normalize();
         Ray::new(origin, direction)
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,20 @@
 use crate::Tuples;
 use crate::utils::color_to_256;
+use std::fmt;
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasError {
+    pub message: String,
+}
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CanvasError {}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Canvas {
@@ -109,4 +124,163 @@ impl Canvas {
         }
         output.join("")
     }
-}
\ No newline at end of file
+}
+
+// Reading
+impl Canvas {
+    pub fn canvas_from_ppm_file(path: &str) -> Result<Canvas, CanvasError> {
+        let content = fs::read_to_string(path).map_err(|e| CanvasError { message: format!("failed to read {path}: {e}") })?;
+        Canvas::canvas_from_ppm(&content)
+    }
+
+    // plain (P3) ppm, comments start with # and run to the end of the line
+    pub fn canvas_from_ppm(input: &str) -> Result<Canvas, CanvasError> {
+        let mut tokens = input.lines()
+            .map(|line| line.split('#').next().unwrap())
+            .flat_map(|line| line.split_whitespace());
+        let error = |message: &str| CanvasError { message: message.to_string() };
+
+        if tokens.next() != Some("P3") {
+            return Err(error("not a plain ppm file, expected P3"));
+        }
+        let mut header = [0usize; 3];
+        for value in header.iter_mut() {
+            let token = tokens.next().ok_or_else(|| error("incomplete header"))?;
+            *value = token.parse::<usize>().map_err(|_| error(&format!("invalid header value {token}")))?;
+        }
+        let [width, height, max_value] = header;
+        if max_value == 0 {
+            return Err(error("maximum color value must not be 0"));
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        let scale = max_value as f64;
+        for y in 0..height {
+            for x in 0..width {
+                let mut rgb = [0.0; 3];
+                for c in rgb.iter_mut() {
+                    let token = tokens.next().ok_or_else(|| error("not enough pixel data"))?;
+                    *c = token.parse::<usize>().map_err(|_| error(&format!("invalid color value {token}")))? as f64 / scale;
+                }
+                canvas.write_pixel(x, y, &Tuples::color(rgb[0], rgb[1], rgb[2]));
+            }
+        }
+        Ok(canvas)
+    }
+
+    pub fn canvas_from_hdr_file(path: &str) -> Result<Canvas, CanvasError> {
+        let content = fs::read(path).map_err(|e| CanvasError { message: format!("failed to read {path}: {e}") })?;
+        Canvas::canvas_from_hdr(&content)
+    }
+
+    // Radiance rgbe (.hdr) with flat or run length encoded scanlines, colors stay unclamped
+    pub fn canvas_from_hdr(input: &[u8]) -> Result<Canvas, CanvasError> {
+        let error = |message: &str| CanvasError { message: message.to_string() };
+        let mut pos = 0;
+        let next_line = |pos: &mut usize| -> Option<String> {
+            let start = *pos;
+            let end = start + input[start..].iter().position(|b| *b == b'\n')?;
+            *pos = end + 1;
+            Some(String::from_utf8_lossy(&input[start..end]).trim_end().to_string())
+        };
+
+        let magic = next_line(&mut pos).ok_or_else(|| error("missing header"))?;
+        if magic != "#?RADIANCE" && magic != "#?RGBE" {
+            return Err(error("not a radiance hdr file"));
+        }
+        loop {
+            let line = next_line(&mut pos).ok_or_else(|| error("incomplete header"))?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(format) = line.strip_prefix("FORMAT=") {
+                if format != "32-bit_rle_rgbe" {
+                    return Err(error(&format!("unsupported format {format}")));
+                }
+            }
+        }
+        let resolution = next_line(&mut pos).ok_or_else(|| error("missing resolution"))?;
+        let parts: Vec<&str> = resolution.split_whitespace().collect();
+        if parts.len() != 4 || parts[0] != "-Y" || parts[2] != "+X" {
+            return Err(error(&format!("unsupported resolution line {resolution}")));
+        }
+        let height = parts[1].parse::<usize>().map_err(|_| error("invalid height"))?;
+        let width = parts[3].parse::<usize>().map_err(|_| error("invalid width"))?;
+
+        let mut canvas = Canvas::new(width, height);
+        let mut scanline = vec![[0u8; 4]; width];
+        for y in 0..height {
+            pos = Canvas::read_hdr_scanline(input, pos, &mut scanline).ok_or_else(|| error("not enough pixel data"))?;
+            for (x, rgbe) in scanline.iter().enumerate() {
+                canvas.write_pixel(x, y, &Canvas::rgbe_to_color(rgbe));
+            }
+        }
+        Ok(canvas)
+    }
+
+    // returns the position after the scanline
+    fn read_hdr_scanline(input: &[u8], mut pos: usize, scanline: &mut [[u8; 4]]) -> Option<usize> {
+        let width = scanline.len();
+        let header = input.get(pos..pos + 4)?;
+        let is_rle = (8..0x8000).contains(&width) && header[0] == 2 && header[1] == 2 &&
+            ((header[2] as usize) << 8 | header[3] as usize) == width;
+        if !is_rle {
+            for pixel in scanline.iter_mut() {
+                pixel.copy_from_slice(input.get(pos..pos + 4)?);
+                pos += 4;
+            }
+            return Some(pos);
+        }
+
+        // every channel is stored on its own as runs of equal bytes and literal bytes
+        pos += 4;
+        for channel in 0..4 {
+            let mut x = 0;
+            while x < width {
+                let count = *input.get(pos)? as usize;
+                pos += 1;
+                if count > 128 {
+                    let value = *input.get(pos)?;
+                    pos += 1;
+                    for pixel in scanline.get_mut(x..x + count - 128)? {
+                        pixel[channel] = value;
+                    }
+                    x += count - 128;
+                } else {
+                    if count == 0 {
+                        return None;
+                    }
+                    let values = input.get(pos..pos + count)?;
+                    for (pixel, value) in scanline.get_mut(x..x + count)?.iter_mut().zip(values) {
+                        pixel[channel] = *value;
+                    }
+                    pos += count;
+                    x += count;
+                }
+            }
+        }
+        Some(pos)
+    }
+
+    fn rgbe_to_color(rgbe: &[u8; 4]) -> Tuples {
+        if rgbe[3] == 0 {
+            return Tuples::color(0.0, 0.0, 0.0);
+        }
+        let f = 2f64.powi(rgbe[3] as i32 - (128 + 8));
+        Tuples::color(rgbe[0] as f64 * f, rgbe[1] as f64 * f, rgbe[2] as f64 * f)
+    }
+
+    // bilinear lookup, u and v in 0..1 from the left and top edge, u wraps around
+    pub fn sample_wrapped(&self, u: f64, v: f64) -> Tuples {
+        let x = u * self.width as f64 - 0.5;
+        let y = (v * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let wrap = |x: f64| x.rem_euclid(self.width as f64) as usize;
+        let (x0, x1) = (wrap(x0), wrap(x0 + 1.0));
+        let (y0, y1) = (y0 as usize, (y0 as usize + 1).min(self.height - 1));
+        let top = self.pixel_at(x0, y0).clone().scale(1.0 - fx).add(&self.pixel_at(x1, y0).clone().scale(fx));
+        let bottom = self.pixel_at(x0, y1).clone().scale(1.0 - fx).add(&self.pixel_at(x1, y1).clone().scale(fx));
+        top.clone().scale(1.0 - fy).add(&bottom.clone().scale(fy))
+    }
+}
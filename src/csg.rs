@@ -0,0 +1,186 @@
+use crate::Shape;
+use crate::shape;
+use crate::Ray;
+use crate::Bounds;
+use crate::Tuples;
+use crate::Matrix;
+use crate::Material;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
+use crate::Intersection;
+use crate::IntersectionList;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOperation {
+    Union, Intersection, Difference
+}
+
+#[derive(Debug, Clone)]
+pub struct Csg {
+    material: Material,
+    transform: Matrix,
+    transform_inverse: Matrix,
+    cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
+    pub operation: CsgOperation,
+    left: Arc<RwLock<dyn Shape>>,
+    right: Arc<RwLock<dyn Shape>>,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Arc<RwLock<dyn Shape>>, right: Arc<RwLock<dyn Shape>>) -> Arc<RwLock<Csg>> {
+        let csg = Arc::new(
+            RwLock::new(
+                Csg {
+                    material: Material::material(),
+                    transform: Matrix::new(4),
+                    transform_inverse: Matrix::new(4),
+                    cast_shadows: true,
+                    motion: None,
+                    parent: None,
+                    operation,
+                    left: Arc::clone(&left),
+                    right: Arc::clone(&right),
+                }
+            )
+        );
+        let parent: Arc<RwLock<dyn Shape>> = csg.clone();
+        left.write().unwrap().set_parent(&parent);
+        right.write().unwrap().set_parent(&parent);
+        csg
+    }
+
+    pub fn left(&self) -> &Arc<RwLock<dyn Shape>> {
+        &self.left
+    }
+
+    pub fn right(&self) -> &Arc<RwLock<dyn Shape>> {
+        &self.right
+    }
+
+    // lhit: the left shape was hit, in_l/in_r: the hit is inside the left/right shape
+    pub fn intersection_allowed(operation: CsgOperation, lhit: bool, in_l: bool, in_r: bool) -> bool {
+        match operation {
+            CsgOperation::Union => (lhit && !in_r) || (!lhit && !in_l),
+            CsgOperation::Intersection => (lhit && in_r) || (!lhit && in_l),
+            CsgOperation::Difference => (lhit && !in_r) || (!lhit && in_l),
+        }
+    }
+
+    // expects xs to be sorted, keeps only the intersections on the surface of the combined shape
+    pub fn filter_intersections(&self, xs: &IntersectionList) -> IntersectionList {
+        let filtered = Csg::filter(self.operation, xs.xs().clone(), |i| <dyn Shape>::includes(&self.left, i.object()));
+        IntersectionList::intersections_from_vec(filtered)
+    }
+
+    // lhit tells if an intersection is with the left shape
+    pub fn filter(operation: CsgOperation, xs: Vec<Intersection>, lhit: impl Fn(&Intersection) -> bool) -> Vec<Intersection> {
+        let mut in_l = false;
+        let mut in_r = false;
+        let mut result: Vec<Intersection> = vec![];
+        for i in xs {
+            let lhit = lhit(&i);
+            if Csg::intersection_allowed(operation, lhit, in_l, in_r) {
+                result.push(i);
+            }
+            if lhit {
+                in_l = !in_l;
+            } else {
+                in_r = !in_r;
+            }
+        }
+        result
+    }
+}
+
+impl Shape for Csg {
+    fn intersect_local(&self, _: &Ray) -> Vec<f64> {
+        panic!("Csg.intersect_local: the intersections belong to the children, use intersections_local");
+    }
+
+    fn intersections_local(&self, r: &Ray, s: &Arc<RwLock<dyn Shape>>) -> IntersectionList {
+        let mut xs = vec![];
+        let mut scope = FreezeScope::inside(self.get_parent());
+        FrozenShape::intersect_local(&self.freeze(s, &mut scope), r, &mut xs);
+        IntersectionList::intersections_from_vec(xs)
+    }
+
+    fn has_descendant(&self, s: &Arc<RwLock<dyn Shape>>) -> bool {
+        <dyn Shape>::includes(&self.left, s) || <dyn Shape>::includes(&self.right, s)
+    }
+
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.csg(s, self)
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
+        self.transform = transform.clone();
+        self.transform_inverse = Matrix::inverse(transform).unwrap();
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        shape::changed();
+        self.material = material.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
+        &mut self.material
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_transform_inverse(&self) -> &Matrix {
+        &self.transform_inverse
+    }
+
+    fn normal_at_local(&self, _: &Tuples) -> Tuples {
+        panic!("Csg.normal_at_local: a csg has no surface of its own, use the normal of the child that was hit");
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::merge(&<dyn Shape>::parent_space_bounds(&self.left), &<dyn Shape>::parent_space_bounds(&self.right))
+    }
+
+    fn get_type(&self) -> &str {
+        "Csg"
+    }
+
+    fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
+        self.cast_shadows = b;
+    }
+
+    fn cast_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+        self.motion = Some(motion.clone());
+    }
+
+    fn get_motion(&self) -> Option<&Motion> {
+        self.motion.as_ref()
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
+}
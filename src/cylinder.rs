@@ -1,11 +1,16 @@
 use crate::Shape;
+use crate::shape;
 use crate::Ray;
+use crate::Bounds;
 use crate::Tuples;
 use crate::Matrix;
 use crate::Material;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
 use core::f64;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
 
 #[derive(Debug, Clone)]
 pub struct Cylinder {
@@ -13,24 +18,28 @@ pub struct Cylinder {
     transform: Matrix,
     transform_inverse: Matrix,
     cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
     pub y_min: f64,
     pub y_max: f64,
     pub closed: bool,
 }
 
 impl Cylinder {
-    pub fn new() -> Rc<RefCell<Cylinder>> {
+    pub fn new() -> Arc<RwLock<Cylinder>> {
         Cylinder::new_limited(-f64::INFINITY, f64::INFINITY, false)
     }
 
-    pub fn new_limited(min: f64, max: f64, closed: bool) -> Rc<RefCell<Cylinder>> {
-        Rc::new(
-            RefCell::new(
+    pub fn new_limited(min: f64, max: f64, closed: bool) -> Arc<RwLock<Cylinder>> {
+        Arc::new(
+            RwLock::new(
                 Cylinder { 
                     material: Material::material(), 
                     transform: Matrix::new(4), 
                     transform_inverse: Matrix::new(4), 
                     cast_shadows: true,
+                    motion: None,
+                    parent: None,
                     y_min: min,
                     y_max: max,
                     closed
@@ -106,12 +115,18 @@ impl Shape for Cylinder {
         result
     }
 
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.primitive(s, Box::new(self.clone()))
+    }
+
     fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
         self.transform = transform.clone();
         self.transform_inverse = Matrix::inverse(transform).unwrap();
     }
 
     fn set_material(&mut self, material: &Material) {
+        shape::changed();
         self.material = material.clone();
     }
 
@@ -120,6 +135,7 @@ impl Shape for Cylinder {
     }
 
     fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
         &mut self.material
     }
 
@@ -144,15 +160,39 @@ impl Shape for Cylinder {
         Tuples::vector(p_object_space.x, 0.0, p_object_space.z)
     }
 
+    fn bounds(&self) -> Bounds {
+        // infinite along y unless truncated
+        Bounds::new(Tuples::point(-1.0, self.y_min, -1.0), Tuples::point(1.0, self.y_max, 1.0))
+    }
+
     fn get_type(&self) -> &str {
         "Cylinder"
     }
 
     fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
         self.cast_shadows = b;
     }
 
     fn cast_shadows(&self) -> bool {
         self.cast_shadows
     }
+
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+        self.motion = Some(motion.clone());
+    }
+
+    fn get_motion(&self) -> Option<&Motion> {
+        self.motion.as_ref()
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,271 @@
+use crate::Shape;
+use crate::shape;
+use crate::Ray;
+use crate::Tuples;
+use crate::Matrix;
+use crate::Motion;
+use crate::Bvh;
+use crate::CsgOperation;
+use crate::Csg;
+use crate::Intersection;
+use crate::IntersectionList;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// the transform of a shape relative to its parent
+#[derive(Debug, Clone)]
+struct Placement {
+    inverse: Matrix,
+    motion: Option<Motion>,
+}
+
+impl Placement {
+    fn of(shape: &dyn Shape) -> Placement {
+        Placement { inverse: shape.get_transform_inverse().clone(), motion: shape.get_motion().cloned() }
+    }
+
+    fn ray_to_local(&self, r: &Ray) -> Ray {
+        match &self.motion {
+            Some(motion) => Ray::transform(r, &motion.inverse_at(r.time())),
+            None => Ray::transform(r, &self.inverse),
+        }
+    }
+
+    fn point_to_local(&self, p: &Tuples, time: f64) -> Tuples {
+        match &self.motion {
+            Some(motion) => motion.inverse_at(time) * p,
+            None => &self.inverse * p,
+        }
+    }
+
+    fn normal_to_parent(&self, n: &Tuples, time: f64) -> Tuples {
+        let mut n = match &self.motion {
+            Some(motion) => Matrix::transpose(&motion.inverse_at(time)) * n,
+            None => Matrix::transpose(&self.inverse) * n,
+        };
+        n.w = 0.0;
+        n.normalize()
+    }
+}
+
+#[derive(Debug)]
+enum ToObject {
+    Fixed { inverse: Matrix, normal: Matrix }, // world to object space and its transpose for normals
+    Moving(Vec<Placement>), // from the outermost ancestor down to the shape itself
+}
+
+#[derive(Debug)]
+enum Kind {
+    Primitive(Box<dyn Shape>),
+    Group { children: Vec<Arc<FrozenShape>>, bvh: Box<Bvh> },
+    Csg { operation: CsgOperation, left: Arc<FrozenShape>, right: Arc<FrozenShape> },
+}
+
+// a copy of a shape and everything below it that rays are traced against without locking,
+// so that rendering threads don't wait for each other
+#[derive(Debug)]
+pub struct FrozenShape {
+    shape: Arc<RwLock<dyn Shape>>, // the shape this is a copy of, intersections refer to it
+    placement: Placement,
+    to_object: ToObject,
+    medium: Arc<RwLock<dyn Shape>>, // the outermost csg the shape is part of, or the shape itself
+    priority: u32,
+    transparent: bool,
+    cast_shadows: bool,
+    ids: Range<usize>, // the shape and its descendants, numbered depth first
+    kind: Kind,
+}
+
+impl FrozenShape {
+    // appends the intersections with a ray given in the space of the parent
+    pub fn intersect(node: &Arc<FrozenShape>, r: &Ray, xs: &mut Vec<Intersection>) {
+        FrozenShape::intersect_local(node, &node.placement.ray_to_local(r), xs);
+    }
+
+    // like intersect for a ray that is already in the space of the shape
+    pub fn intersect_local(node: &Arc<FrozenShape>, r: &Ray, xs: &mut Vec<Intersection>) {
+        match &node.kind {
+            Kind::Primitive(shape) => {
+                xs.extend(shape.intersect_local_uv(r).into_iter().map(|(t, u, v)| Intersection::new_frozen(t, node, u, v)));
+            },
+            Kind::Group { children, bvh } => {
+                bvh.visit(r, |index| FrozenShape::intersect(&children[index], r, xs));
+            },
+            Kind::Csg { operation, left, right } => {
+                let mut children_xs = vec![];
+                FrozenShape::intersect(left, r, &mut children_xs);
+                FrozenShape::intersect(right, r, &mut children_xs);
+                children_xs.sort_by(|a, b| a.t().partial_cmp(&b.t()).unwrap());
+                xs.extend(Csg::filter(*operation, children_xs, |i| i.frozen().is_some_and(|hit| left.includes(hit))));
+            },
+        }
+    }
+
+    // true if other is this shape or one of its descendants
+    pub fn includes(&self, other: &FrozenShape) -> bool {
+        self.ids.contains(&other.ids.start)
+    }
+
+    // u and v are the barycentric coordinates of the hit, only used by smooth triangles
+    pub fn normal_at(&self, p: &Tuples, u: f64, v: f64, time: f64) -> Tuples {
+        let shape = match &self.kind {
+            Kind::Primitive(shape) => shape,
+            _ => panic!("FrozenShape.normal_at: composite shapes have no surface of their own"),
+        };
+        match &self.to_object {
+            ToObject::Fixed { inverse, normal } => {
+                let n_local = shape.normal_at_local_uv(&(inverse * p), u, v);
+                let mut n = normal * &n_local;
+                n.w = 0.0;
+                n.normalize()
+            },
+            ToObject::Moving(placements) => {
+                let p_local = placements.iter().fold(*p, |p, placement| placement.point_to_local(&p, time));
+                let n_local = shape.normal_at_local_uv(&p_local, u, v);
+                placements.iter().rev().fold(n_local, |n, placement| placement.normal_to_parent(&n, time))
+            },
+        }
+    }
+
+    pub fn shape(&self) -> &Arc<RwLock<dyn Shape>> {
+        &self.shape
+    }
+
+    pub fn medium(&self) -> &Arc<RwLock<dyn Shape>> {
+        &self.medium
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn cast_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+}
+
+// what a shape takes over from the composite shapes around it while it is frozen
+#[derive(Debug, Default)]
+pub struct FreezeScope {
+    next_id: usize,
+    ancestors: Vec<Placement>,
+    medium: Option<Arc<RwLock<dyn Shape>>>,
+}
+
+impl FreezeScope {
+    // scope of a shape whose parent is the given one, nothing is taken over from outside of it
+    pub fn inside(parent: Option<Arc<RwLock<dyn Shape>>>) -> FreezeScope {
+        let mut scope = FreezeScope::default();
+        let mut current = parent;
+        while let Some(ancestor) = current {
+            let shape = ancestor.read().unwrap();
+            scope.ancestors.insert(0, Placement::of(&*shape));
+            if shape.get_type() == "Csg" {
+                scope.medium = Some(Arc::clone(&ancestor));
+            }
+            current = shape.get_parent();
+        }
+        scope
+    }
+
+    pub fn primitive(&mut self, s: &Arc<RwLock<dyn Shape>>, copy: Box<dyn Shape>) -> Arc<FrozenShape> {
+        let first = self.next_id;
+        self.next_id += 1;
+        let node = self.node(s, copy.as_ref(), first..self.next_id);
+        Arc::new(node(Kind::Primitive(copy)))
+    }
+
+    pub fn group(&mut self, s: &Arc<RwLock<dyn Shape>>, group: &dyn Shape, children: &[Arc<RwLock<dyn Shape>>]) -> Arc<FrozenShape> {
+        let first = self.next_id;
+        self.next_id += 1;
+        self.ancestors.push(Placement::of(group));
+        let frozen_children = children.iter().map(|c| c.read().unwrap().freeze(c, self)).collect();
+        self.ancestors.pop();
+        let node = self.node(s, group, first..self.next_id);
+        Arc::new(node(Kind::Group { children: frozen_children, bvh: Box::new(Bvh::build(children)) }))
+    }
+
+    pub fn csg(&mut self, s: &Arc<RwLock<dyn Shape>>, csg: &Csg) -> Arc<FrozenShape> {
+        let first = self.next_id;
+        self.next_id += 1;
+        let outermost = self.medium.is_none();
+        if outermost {
+            self.medium = Some(Arc::clone(s));
+        }
+        self.ancestors.push(Placement::of(csg));
+        let left = csg.left().read().unwrap().freeze(csg.left(), self);
+        let right = csg.right().read().unwrap().freeze(csg.right(), self);
+        self.ancestors.pop();
+        if outermost {
+            self.medium = None;
+        }
+        let node = self.node(s, csg, first..self.next_id);
+        Arc::new(node(Kind::Csg { operation: csg.operation, left, right }))
+    }
+
+    // everything but the kind of the frozen shape
+    fn node(&self, s: &Arc<RwLock<dyn Shape>>, shape: &dyn Shape, ids: Range<usize>) -> impl FnOnce(Kind) -> FrozenShape {
+        let placement = Placement::of(shape);
+        let moving = placement.motion.is_some() || self.ancestors.iter().any(|a| a.motion.is_some());
+        let to_object = if moving {
+            let mut placements = self.ancestors.clone();
+            placements.push(placement.clone());
+            ToObject::Moving(placements)
+        } else {
+            let inverse = self.ancestors.iter().fold(Matrix::new(4), |m, a| &a.inverse * &m);
+            let inverse = &placement.inverse * &inverse;
+            ToObject::Fixed { normal: Matrix::transpose(&inverse), inverse }
+        };
+        let shape_handle = Arc::clone(s);
+        let medium = self.medium.clone().unwrap_or_else(|| Arc::clone(s));
+        let priority = shape.get_material().medium_priority;
+        let transparent = shape.get_material().transparency > 0.0;
+        let cast_shadows = shape.cast_shadows();
+        move |kind| FrozenShape { shape: shape_handle, placement, to_object, medium, priority, transparent, cast_shadows, ids, kind }
+    }
+}
+
+// the objects of a world frozen for rendering, with a bvh over them
+#[derive(Debug)]
+pub struct FrozenScene {
+    objects: Vec<Arc<FrozenShape>>,
+    bvh: Bvh,
+    generation: u64, // shape::generation() when the objects were copied
+}
+
+impl FrozenScene {
+    pub fn freeze(objects: &[Arc<RwLock<dyn Shape>>]) -> FrozenScene {
+        // taken first, changes made while copying make the scene outdated right away
+        let generation = shape::generation();
+        let mut scope = FreezeScope::default();
+        let frozen = objects.iter().map(|o| o.read().unwrap().freeze(o, &mut scope)).collect();
+        FrozenScene { objects: frozen, bvh: Bvh::build(objects), generation }
+    }
+
+    pub fn intersect(&self, r: &Ray) -> IntersectionList {
+        let mut xs = vec![];
+        self.bvh.visit(r, |index| FrozenShape::intersect(&self.objects[index], r, &mut xs));
+        IntersectionList::intersections_from_vec(xs)
+    }
+
+    // false once any shape changed after the scene was frozen
+    pub fn is_current(&self) -> bool {
+        self.generation == shape::generation()
+    }
+
+    // index of the object of the world the frozen shape belongs to
+    pub fn object_index(&self, node: &FrozenShape) -> Option<usize> {
+        // ids grow from one object to the next
+        let index = self.objects.partition_point(|o| o.ids.end <= node.ids.start);
+        self.objects.get(index).filter(|o| o.includes(node)).map(|_| index)
+    }
+
+    pub fn bvh(&self) -> &Bvh {
+        &self.bvh
+    }
+}
@@ -0,0 +1,162 @@
+use crate::Shape;
+use crate::shape;
+use crate::Ray;
+use crate::Bounds;
+use crate::Bvh;
+use crate::Tuples;
+use crate::Matrix;
+use crate::Material;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
+use crate::IntersectionList;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct Group {
+    material: Material,
+    transform: Matrix,
+    transform_inverse: Matrix,
+    cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
+    children: Vec<Arc<RwLock<dyn Shape>>>,
+}
+
+impl Group {
+    pub fn new() -> Arc<RwLock<Group>> {
+        Arc::new(
+            RwLock::new(
+                Group {
+                    material: Material::material(),
+                    transform: Matrix::new(4),
+                    transform_inverse: Matrix::new(4),
+                    cast_shadows: true,
+                    motion: None,
+                    parent: None,
+                    children: vec![],
+                }
+            )
+        )
+    }
+
+    pub fn add_child(g: &Arc<RwLock<Group>>, child: Arc<RwLock<dyn Shape>>) {
+        Group::add_children(g, vec![child]);
+    }
+
+    pub fn add_children(g: &Arc<RwLock<Group>>, children: Vec<Arc<RwLock<dyn Shape>>>) {
+        let parent: Arc<RwLock<dyn Shape>> = g.clone();
+        for child in children.iter() {
+            child.write().unwrap().set_parent(&parent);
+        }
+        g.write().unwrap().children.extend(children);
+        shape::changed();
+    }
+
+    pub fn get_children(&self) -> &Vec<Arc<RwLock<dyn Shape>>> {
+        &self.children
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn contains(&self, s: &Arc<RwLock<dyn Shape>>) -> bool {
+        self.children.iter().any(|c| Arc::ptr_eq(c, s))
+    }
+
+    // the bvh a frozen copy of the group traces rays with
+    pub fn bvh(&self) -> Bvh {
+        Bvh::build(&self.children)
+    }
+}
+
+impl Shape for Group {
+    fn intersect_local(&self, _: &Ray) -> Vec<f64> {
+        panic!("Group.intersect_local: the intersections belong to the children, use intersections_local");
+    }
+
+    // r is already in group space, every child applies its own transform on top
+    fn intersections_local(&self, r: &Ray, s: &Arc<RwLock<dyn Shape>>) -> IntersectionList {
+        let mut xs = vec![];
+        let mut scope = FreezeScope::inside(self.get_parent());
+        FrozenShape::intersect_local(&self.freeze(s, &mut scope), r, &mut xs);
+        IntersectionList::intersections_from_vec(xs)
+    }
+
+    fn has_descendant(&self, s: &Arc<RwLock<dyn Shape>>) -> bool {
+        self.children.iter().any(|c| <dyn Shape>::includes(c, s))
+    }
+
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.group(s, self, &self.children)
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
+        self.transform = transform.clone();
+        self.transform_inverse = Matrix::inverse(transform).unwrap();
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        shape::changed();
+        self.material = material.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
+        &mut self.material
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_transform_inverse(&self) -> &Matrix {
+        &self.transform_inverse
+    }
+
+    fn normal_at_local(&self, _: &Tuples) -> Tuples {
+        panic!("Group.normal_at_local: a group has no surface, use the normal of the child that was hit");
+    }
+
+    fn bounds(&self) -> Bounds {
+        self.children.iter().fold(Bounds::empty(), |b, c| Bounds::merge(&b, &<dyn Shape>::parent_space_bounds(c)))
+    }
+
+    fn get_type(&self) -> &str {
+        "Group"
+    }
+
+    fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
+        self.cast_shadows = b;
+    }
+
+    fn cast_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+        self.motion = Some(motion.clone());
+    }
+
+    fn get_motion(&self) -> Option<&Motion> {
+        self.motion.as_ref()
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
+}
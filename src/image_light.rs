@@ -0,0 +1,153 @@
+use crate::Tuples;
+use crate::Canvas;
+use crate::Rng;
+use crate::Light;
+use crate::LightSample;
+use crate::ImageBackground;
+use std::f64::consts::PI;
+
+fn luminance(c: &Tuples) -> f64 {
+    0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+}
+
+// lights the scene from all around with a latitude/longitude radiance map, laid out like
+// an ImageBackground. Samples are picked in proportion to the luminance of the pixels so
+// that bright parts of the map (the sun, the windows of a studio) get most of the rays
+#[derive(Debug, Clone)]
+pub struct ImageLight {
+    image: Canvas,
+    samples: usize,
+    scale: f64,
+    row_cdf: Vec<f64>, // running sum of the row weights
+    column_cdfs: Vec<Vec<f64>>, // running sum of the pixel weights of every row
+    average: Tuples, // mean radiance over the sphere before scaling
+    intensity: Tuples,
+    dominant: Tuples, // where most of the light comes from
+}
+
+impl ImageLight {
+    pub fn new(image: Canvas, samples: usize) -> ImageLight {
+        let (width, height) = (image.width, image.height);
+        let mut row_cdf = Vec::with_capacity(height);
+        let mut column_cdfs = Vec::with_capacity(height);
+        let mut average = Tuples::color(0.0, 0.0, 0.0);
+        let mut dominant = Tuples::vector(0.0, 0.0, 0.0);
+        let mut solid_angle = 0.0;
+        let mut row_sum = 0.0;
+        for y in 0..height {
+            // pixels near the poles cover less of the sphere
+            let sin_theta = (PI * (y as f64 + 0.5) / height as f64).sin();
+            let mut cdf = Vec::with_capacity(width);
+            let mut sum = 0.0;
+            for x in 0..width {
+                let color = image.pixel_at(x, y);
+                let weight = luminance(color) * sin_theta;
+                sum += weight;
+                cdf.push(sum);
+                average.add(&color.clone().scale(sin_theta));
+                let direction = ImageBackground::uv_to_direction((x as f64 + 0.5) / width as f64, (y as f64 + 0.5) / height as f64);
+                dominant.add(&direction.clone().scale(weight));
+            }
+            solid_angle += sin_theta * width as f64;
+            row_sum += sum;
+            row_cdf.push(row_sum);
+            column_cdfs.push(cdf);
+        }
+        if solid_angle > 0.0 {
+            average.scale(1.0 / solid_angle);
+        }
+        let dominant = if dominant.clone().magnitude() > 1e-9 { dominant.normalize() } else { Tuples::vector(0.0, 1.0, 0.0) };
+        ImageLight { image, samples, scale: 1.0, row_cdf, column_cdfs, average, intensity: average, dominant }
+    }
+
+    // multiplies the radiance of the whole map
+    pub fn with_scale(mut self, scale: f64) -> ImageLight {
+        self.scale = scale;
+        self.intensity = self.average.clone().scale(scale);
+        self
+    }
+
+    pub fn image(&self) -> &Canvas {
+        &self.image
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples
+    }
+
+    fn total_weight(&self) -> f64 {
+        self.row_cdf.last().copied().unwrap_or(0.0)
+    }
+
+    fn pixel_for(&self, direction: &Tuples) -> (usize, usize) {
+        let (u, v) = ImageBackground::direction_to_uv(direction);
+        let x = ((u * self.image.width as f64) as usize).min(self.image.width - 1);
+        let y = ((v * self.image.height as f64) as usize).min(self.image.height - 1);
+        (x, y)
+    }
+
+    pub fn radiance(&self, direction: &Tuples) -> Tuples {
+        let (x, y) = self.pixel_for(direction);
+        self.image.pixel_at(x, y).clone().scale(self.scale)
+    }
+
+    // probability density of sample_direction per unit solid angle
+    pub fn pdf(&self, direction: &Tuples) -> f64 {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let (x, y) = self.pixel_for(direction);
+        let (width, height) = (self.image.width as f64, self.image.height as f64);
+        let pixel_probability = luminance(self.image.pixel_at(x, y)) * (PI * (y as f64 + 0.5) / height).sin() / total;
+        let sin_theta = (1.0 - direction.y * direction.y).max(0.0).sqrt().max(1e-9);
+        pixel_probability * width * height / (2.0 * PI * PI * sin_theta)
+    }
+
+    // picks a pixel with the luminance cdfs and a point inside of it, all four numbers in 0..1
+    pub fn sample_direction(&self, r_row: f64, r_column: f64, r_u: f64, r_v: f64) -> Tuples {
+        let total = self.total_weight();
+        let y = self.row_cdf.partition_point(|c| *c <= r_row * total).min(self.image.height - 1);
+        let row = &self.column_cdfs[y];
+        let x = row.partition_point(|c| *c <= r_column * row.last().unwrap()).min(self.image.width - 1);
+        let u = (x as f64 + r_u) / self.image.width as f64;
+        let v = (y as f64 + r_v) / self.image.height as f64;
+        ImageBackground::uv_to_direction(u, v)
+    }
+}
+
+impl Light for ImageLight {
+    fn get_type(&self) -> &str {
+        "ImageLight"
+    }
+
+    fn intensity(&self) -> &Tuples {
+        &self.intensity
+    }
+
+    fn direction_to_light(&self, _point: &Tuples) -> Tuples {
+        self.dominant
+    }
+
+    fn distance_to_light(&self, _point: &Tuples) -> f64 {
+        f64::INFINITY
+    }
+
+    // lighting averages over the samples, so every sample carries radiance / (pi * pdf),
+    // which makes the average the irradiance of a diffuse surface
+    fn samples(&self, point: &Tuples) -> Vec<LightSample> {
+        if self.total_weight() <= 0.0 {
+            return vec![];
+        }
+        let mut rng = Rng::for_point(0, point);
+        let mut samples = Vec::with_capacity(self.samples);
+        for _ in 0..self.samples {
+            let direction = self.sample_direction(rng.next_f64(), rng.next_f64(), rng.next_f64(), rng.next_f64());
+            let pdf = self.pdf(&direction);
+            // directions that can't be picked still count towards the average
+            let intensity = if pdf > 0.0 { self.radiance(&direction).scale(1.0 / (PI * pdf)) } else { Tuples::color(0.0, 0.0, 0.0) };
+            samples.push(LightSample { direction, distance: f64::INFINITY, intensity });
+        }
+        samples
+    }
+}
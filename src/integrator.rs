@@ -0,0 +1,212 @@
+use crate::*;
+use crate::intersections::IntersectionList;
+use std::fmt::Debug;
+
+// computes the color seen along a camera ray, render calls it once per ray
+pub trait Integrator: Debug + Send + Sync {
+    // rng is seeded per pixel, integrators that don't sample may ignore it
+    fn color_at(&self, w: &World, r: &Ray, rng: &mut Rng) -> Tuples;
+}
+
+fn first_hit(w: &World, r: &Ray) -> Option<Computations> {
+    let il = World::intersect_world(w, r);
+    IntersectionList::hit(&il).map(|i| Intersection::prep_computations(i, r, &il))
+}
+
+fn black() -> Tuples {
+    Tuples::color(0.0, 0.0, 0.0)
+}
+
+// direct lighting, mirrors and glass as in World::color_at
+#[derive(Debug, Clone, Copy)]
+pub struct Whitted {
+    pub max_depth: i32,
+}
+
+impl Default for Whitted {
+    fn default() -> Self {
+        Whitted { max_depth: MAX_ITERATIONS }
+    }
+}
+
+impl Integrator for Whitted {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        World::color_at(w, r, self.max_depth)
+    }
+}
+
+// the outward surface normal in world space, each component mapped from -1..1 to 0..1
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalView;
+
+impl Integrator for NormalView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        let comps = match first_hit(w, r) {
+            Some(comps) => comps,
+            None => return black(),
+        };
+        let mut normal = comps.normal_v;
+        if comps.inside {
+            normal.negate();
+        }
+        Tuples::color((normal.x + 1.0) * 0.5, (normal.y + 1.0) * 0.5, (normal.z + 1.0) * 0.5)
+    }
+}
+
+// white at near, fading to black at far
+#[derive(Debug, Clone, Copy)]
+pub struct DepthView {
+    pub near: f64,
+    pub far: f64,
+}
+
+impl DepthView {
+    pub fn new(near: f64, far: f64) -> DepthView {
+        DepthView { near, far }
+    }
+}
+
+impl Integrator for DepthView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        let comps = match first_hit(w, r) {
+            Some(comps) => comps,
+            None => return black(),
+        };
+        let distance = comps.t * r.direction().clone().magnitude();
+        let gray = 1.0 - ((distance - self.near) / (self.far - self.near)).clamp(0.0, 1.0);
+        Tuples::color(gray, gray, gray)
+    }
+}
+
+// the color of the material without any lighting
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlbedoView;
+
+impl Integrator for AlbedoView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        match first_hit(w, r) {
+            Some(comps) => {
+                let material = comps.object.read().unwrap().get_material().clone();
+                let color = material.pattern.read().unwrap().color_at_object_at(&comps.object, &comps.point, comps.time);
+                color
+            },
+            None => w.get_background().color_at(&r.direction().clone().normalize()),
+        }
+    }
+}
+
+// u in red and v in green. Triangles show their barycentric coordinates, all other shapes
+// a latitude/longitude mapping of the hit point in object space
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvView;
+
+impl UvView {
+    pub fn surface_uv(comps: &Computations) -> (f64, f64) {
+        let kind = comps.object.read().unwrap().get_type().to_string();
+        if kind == "Triangle" || kind == "SmoothTriangle" {
+            return (comps.u, comps.v);
+        }
+        let p = <dyn Shape>::world_to_object_at(&comps.object, &comps.point, comps.time);
+        let mut direction = Tuples::vector(p.x, p.y, p.z);
+        if direction.clone().magnitude() == 0.0 {
+            return (0.0, 0.0);
+        }
+        ImageBackground::direction_to_uv(&direction.normalize())
+    }
+}
+
+impl Integrator for UvView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        match first_hit(w, r) {
+            Some(comps) => {
+                let (u, v) = UvView::surface_uv(&comps);
+                Tuples::color(u, v, 0.0)
+            },
+            None => black(),
+        }
+    }
+}
+
+// every object that was added to the world gets its own color, the children of groups and
+// csgs share the color of the object they belong to
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObjectIdView;
+
+impl ObjectIdView {
+    // index of the object in the world that the first visible hit along r belongs to
+    pub fn object_id(w: &World, r: &Ray) -> Option<usize> {
+        let scene = w.get_scene();
+        let il = scene.intersect(r);
+        IntersectionList::hit(&il).and_then(|i| i.frozen()).and_then(|node| scene.object_index(node))
+    }
+
+    // fully saturated hues spread by the golden ratio so that neighbouring ids differ
+    pub fn id_color(id: usize) -> Tuples {
+        let hue = (id as f64 * 0.618_033_988_75).fract() * 6.0;
+        let x = 1.0 - (hue % 2.0 - 1.0).abs();
+        match hue as usize {
+            0 => Tuples::color(1.0, x, 0.0),
+            1 => Tuples::color(x, 1.0, 0.0),
+            2 => Tuples::color(0.0, 1.0, x),
+            3 => Tuples::color(0.0, x, 1.0),
+            4 => Tuples::color(x, 0.0, 1.0),
+            _ => Tuples::color(1.0, 0.0, x),
+        }
+    }
+}
+
+impl Integrator for ObjectIdView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        match ObjectIdView::object_id(w, r) {
+            Some(id) => ObjectIdView::id_color(id),
+            None => black(),
+        }
+    }
+}
+
+// how many reflected and refracted rays the whitted tracer follows one after another,
+// black for none and white for max_depth
+#[derive(Debug, Clone, Copy)]
+pub struct BounceView {
+    pub max_depth: i32,
+}
+
+impl Default for BounceView {
+    fn default() -> Self {
+        BounceView { max_depth: MAX_ITERATIONS }
+    }
+}
+
+impl BounceView {
+    pub fn bounces(w: &World, r: &Ray, remaining: i32) -> i32 {
+        let comps = match first_hit(w, r) {
+            Some(comps) => comps,
+            None => return 0,
+        };
+        if remaining <= 0 {
+            return 0;
+        }
+        let (reflective, transparency) = {
+            let shape = comps.object.read().unwrap();
+            (shape.get_material().reflective, shape.get_material().transparency)
+        };
+        let mut bounces = 0;
+        if reflective > 0.0 {
+            let reflected = Ray::new_at(comps.over_point, comps.reflect_v, comps.time);
+            bounces = bounces.max(1 + BounceView::bounces(w, &reflected, remaining - 1));
+        }
+        if transparency > 0.0 {
+            if let Some(refracted) = World::refracted_ray(&comps) {
+                bounces = bounces.max(1 + BounceView::bounces(w, &refracted, remaining - 1));
+            }
+        }
+        bounces
+    }
+}
+
+impl Integrator for BounceView {
+    fn color_at(&self, w: &World, r: &Ray, _rng: &mut Rng) -> Tuples {
+        let gray = BounceView::bounces(w, r, self.max_depth) as f64 / self.max_depth.max(1) as f64;
+        Tuples::color(gray, gray, gray)
+    }
+}
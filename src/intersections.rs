@@ -1,9 +1,10 @@
 use crate::utils::EPSILON;
 use crate::Ray;
 use crate::Tuples;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 use crate::Shape;
+use crate::FrozenShape;
 
 #[derive(Debug, Clone)]
 pub struct IntersectionList {
@@ -16,7 +17,7 @@ impl IntersectionList {
         IntersectionList { xs: vec![], count: 0 }
     }
 
-    pub fn new(t: Vec<f64>, obj: &Rc<RefCell<dyn Shape>>) -> IntersectionList {
+    pub fn new(t: Vec<f64>, obj: &Arc<RwLock<dyn Shape>>) -> IntersectionList {
         let count = t.len();
         let xs = t.into_iter().map(|v| {
             Intersection::new(v, &obj)
@@ -24,6 +25,15 @@ impl IntersectionList {
         IntersectionList { xs, count }
     }
 
+    // t, u, v
+    pub fn new_with_uv(t_uv: Vec<(f64, f64, f64)>, obj: &Arc<RwLock<dyn Shape>>) -> IntersectionList {
+        let count = t_uv.len();
+        let xs = t_uv.into_iter().map(|(t, u, v)| {
+            Intersection::new_with_uv(t, obj, u, v)
+        }).collect();
+        IntersectionList { xs, count }
+    }
+
     pub fn merge(mut l1: IntersectionList, mut l2: IntersectionList) -> IntersectionList {
         l1.xs.append(&mut l2.xs);
         l1.count = l1.xs.len();
@@ -43,18 +53,22 @@ impl IntersectionList {
         IntersectionList { xs, count }
     }
 
+    // the first visible intersection in front of the ray, surfaces inside of a medium with a
+    // higher priority are skipped
     pub fn hit(il: &IntersectionList) -> Option<&Intersection> {
+        let mut media = Media::default();
         for i in il.xs() {
-            if i.t() >= 0.0 {
+            if i.t() >= 0.0 && media.is_visible(i) {
                 return Some(i);
             }
+            media.cross(i);
         }
         None
     }
 
     pub fn hit_shadow(il: &IntersectionList) -> Option<&Intersection> {
         for i in il.xs() {
-            if i.t() >= 0.0 && i.object().borrow().cast_shadows() {
+            if i.t() >= 0.0 && i.cast_shadows() {
                 return Some(i);
             }
         }
@@ -77,13 +91,16 @@ impl IntersectionList {
 #[derive(Debug, Clone)]
 pub struct Intersection {
     t: f64,
-    object: Rc<RefCell<dyn Shape>>,
+    object: Target,
+    // barycentric coordinates, only set for triangles
+    u: f64,
+    v: f64,
 }
 
 #[derive(Debug)]
 pub struct Computations {
     pub t: f64,
-    pub object: Rc<RefCell<dyn Shape>>,
+    pub object: Arc<RwLock<dyn Shape>>,
     pub point: Tuples,
     pub eye_v: Tuples,
     pub normal_v: Tuples,
@@ -93,17 +110,114 @@ pub struct Computations {
     pub reflect_v: Tuples,
     pub n1: f64,
     pub n2: f64,
+    pub medium: Option<Arc<RwLock<dyn Shape>>>, // what the ray travelled through to get here, None for empty space
+    pub next_medium: Option<Arc<RwLock<dyn Shape>>>, // what a refracted ray continues through
+    pub u: f64,
+    pub v: f64,
+    pub time: f64, // time of the ray, rays leaving the surface keep it
+}
+
+impl Computations {
+    // n1 and n2 for the red, green and blue channel, None if neither side disperses light
+    pub fn channel_indices(&self) -> Option<([f64; 3], [f64; 3])> {
+        let side = |medium: &Option<Arc<RwLock<dyn Shape>>>| match medium {
+            Some(obj) => {
+                let shape = obj.read().unwrap();
+                (shape.get_material().is_dispersive(), shape.get_material().channel_indices())
+            },
+            None => (false, [1.0; 3]),
+        };
+        let (dispersive1, n1) = side(&self.medium);
+        let (dispersive2, n2) = side(&self.next_medium);
+        if dispersive1 || dispersive2 {
+            Some((n1, n2))
+        } else {
+            None
+        }
+    }
+}
+
+// the media a ray is inside of at some point along it, in the order they were entered. Where
+// media overlap (water in a glass) the one with the highest medium_priority fills the space,
+// among equal priorities the one entered last
+#[derive(Debug, Default)]
+pub struct Media {
+    containers: Vec<Container>,
+}
+
+#[derive(Debug)]
+struct Container {
+    medium: Arc<RwLock<dyn Shape>>, // a csg counts as a single medium
+    entered: Arc<RwLock<dyn Shape>>, // the surface the ray went through
+    priority: u32,
+}
+
+// intersections found in a frozen world keep the frozen shape, which answers without locking
+#[derive(Debug, Clone)]
+enum Target {
+    Shape(Arc<RwLock<dyn Shape>>),
+    Frozen(Arc<FrozenShape>),
+}
+
+impl Media {
+    // the medium of the intersected object, its priority and if it lets light through
+    fn medium_of(i: &Intersection) -> (Arc<RwLock<dyn Shape>>, u32, bool) {
+        match &i.object {
+            Target::Shape(object) => {
+                let medium = <dyn Shape>::medium(object);
+                let shape = object.read().unwrap();
+                (medium, shape.get_material().medium_priority, shape.get_material().transparency > 0.0)
+            },
+            Target::Frozen(frozen) => (Arc::clone(frozen.medium()), frozen.priority(), frozen.is_transparent()),
+        }
+    }
+
+    // the surface that was entered of the medium that fills the space, None for empty space
+    pub fn current(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        let highest = self.containers.iter().map(|c| c.priority).max()?;
+        self.containers.iter().rev().find(|c| c.priority == highest).map(|c| Arc::clone(&c.entered))
+    }
+
+    // false for transparent surfaces inside of another medium with a higher priority, rays pass
+    // them unchanged. Opaque surfaces are always visible, a spoon in water doesn't disappear
+    pub fn is_visible(&self, i: &Intersection) -> bool {
+        let (medium, priority, transparent) = Media::medium_of(i);
+        !transparent || !self.containers.iter().any(|c| c.priority > priority && !Arc::ptr_eq(&c.medium, &medium))
+    }
+
+    // enters or leaves the medium of the intersected object
+    pub fn cross(&mut self, i: &Intersection) {
+        let (medium, priority, _) = Media::medium_of(i);
+        if let Some(index) = self.containers.iter().position(|c| Arc::ptr_eq(&c.medium, &medium)) {
+            self.containers.remove(index); // exiting
+        } else {
+            self.containers.push(Container { medium, entered: Arc::clone(i.object()), priority }); // entering
+        }
+    }
 }
 
 impl Intersection {
-    pub fn new(t: f64, object: &Rc<RefCell<dyn Shape>>) -> Intersection {
-        Intersection { t, object: Rc::clone(object) }
+    pub fn new(t: f64, object: &Arc<RwLock<dyn Shape>>) -> Intersection {
+        Intersection::new_with_uv(t, object, 0.0, 0.0)
+    }
+
+    pub fn new_with_uv(t: f64, object: &Arc<RwLock<dyn Shape>>, u: f64, v: f64) -> Intersection {
+        Intersection { t, object: Target::Shape(Arc::clone(object)), u, v }
+    }
+
+    pub fn new_frozen(t: f64, object: &Arc<FrozenShape>, u: f64, v: f64) -> Intersection {
+        Intersection { t, object: Target::Frozen(Arc::clone(object)), u, v }
     }
 
     pub fn schlick(comps: &Computations) -> f64 {
+        Intersection::schlick_with(comps, comps.n1, comps.n2)
+    }
+
+    // reflectance for other refractive indices than the ones in comps, e.g. of a single channel
+    pub fn schlick_with(comps: &Computations, n1: f64, n2: f64) -> f64 {
         let mut cos_theta_i = Tuples::dot(&comps.eye_v, &comps.normal_v);
-        if comps.n1 > comps.n2 {
-            let n = comps.n1 / comps.n2;
+        if n1 > n2 {
+            let n = n1 / n2;
             let sin2_theta = n.powf(2.0) * (1.0 - cos_theta_i.powf(2.0));
             if sin2_theta > 1.0 {
                 return 1.0
@@ -111,13 +225,16 @@ impl Intersection {
             let cos_theta_t = (1.0 - sin2_theta).sqrt();
             cos_theta_i = cos_theta_t;
         }
-        let r0 = ((comps.n1 - comps.n2) / (comps.n1 +  comps.n2)).powf(2.0);
+        let r0 = ((n1 - n2) / (n1 + n2)).powf(2.0);
         return r0 + (1.0 - r0) * (1.0 - cos_theta_i).powf(5.0);
     }
 
     pub fn prep_computations(hit: &Intersection, r: &Ray, xs: &IntersectionList) -> Computations {
         let point = Ray::position(r,hit.t());
-        let mut normal_v = <dyn Shape>::normal_at(&hit.object(), &point);
+        let mut normal_v = match &hit.object {
+            Target::Shape(object) => <dyn Shape>::normal_at_time(object, &point, hit.u(), hit.v(), r.time()),
+            Target::Frozen(frozen) => frozen.normal_at(&point, hit.u(), hit.v(), r.time()),
+        };
         let eye_v = r.direction().clone().negate().normalize();
         let inside = if Tuples::dot(&eye_v, &normal_v) < 0.0 { true } else { false };
         if inside {
@@ -128,42 +245,27 @@ impl Intersection {
         let under_point = point.clone().subtract(&normal_v_offset);
         let reflect_v = Tuples::reflect(r.direction(), &normal_v);
 
-        // refractive indices
-        let mut n1 = 1.0;
-        let mut n2 = 1.0;
-
-        let mut containers: Vec<Rc<RefCell<dyn Shape>>> = vec![];
+        // the media on both sides of the surface, None for empty space
+        let mut medium = None;
+        let mut next_medium = None;
+        let mut media = Media::default();
         for i_xs in xs.xs() {
             let is_hit = i_xs.is_equal(hit);
             if is_hit {
-                if containers.len() == 0 {
-                    n1 = 1.0;
-                } else {
-                    let obj = containers.last().unwrap();
-                    n1 = obj.borrow().get_material().refractive_index;
-                }
-            }
-        
-            if let Some(index) = containers.iter().position(|ci| Rc::ptr_eq(ci, i_xs.object())) {
-                containers.splice(index..index + 1, vec![]); // exiting
-            } else {
-                containers.push(Rc::clone(i_xs.object())); // entering
+                medium = media.current();
             }
-
+            media.cross(i_xs);
             if is_hit {
-                if containers.len() == 0 {
-                    n2 = 1.0;
-                } else {
-                    let obj = containers.last().unwrap();
-                    n2 = obj.borrow().get_material().refractive_index;
-                }
+                next_medium = media.current();
                 break;
             }
         }
+        let index = |m: &Option<Arc<RwLock<dyn Shape>>>| m.as_ref().map_or(1.0, |o| o.read().unwrap().get_material().refractive_index);
+        let (n1, n2) = (index(&medium), index(&next_medium));
 
         Computations {
             t: hit.t(),
-            object: Rc::clone(hit.object()),
+            object: Arc::clone(hit.object()),
             point,
             eye_v,
             normal_v,
@@ -173,6 +275,11 @@ impl Intersection {
             reflect_v,
             n1,
             n2,
+            medium,
+            next_medium,
+            u: hit.u(),
+            v: hit.v(),
+            time: r.time(),
         }
     }
 
@@ -180,14 +287,40 @@ impl Intersection {
         self.t
     }
 
-    pub fn object(&self) -> &Rc<RefCell<dyn Shape>> {
-        &self.object
+    pub fn object(&self) -> &Arc<RwLock<dyn Shape>> {
+        match &self.object {
+            Target::Shape(object) => object,
+            Target::Frozen(frozen) => frozen.shape(),
+        }
+    }
+
+    // the copy of the object in the frozen world the intersection was found in
+    pub fn frozen(&self) -> Option<&Arc<FrozenShape>> {
+        match &self.object {
+            Target::Shape(_) => None,
+            Target::Frozen(frozen) => Some(frozen),
+        }
+    }
+
+    pub fn cast_shadows(&self) -> bool {
+        match &self.object {
+            Target::Shape(object) => object.read().unwrap().cast_shadows(),
+            Target::Frozen(frozen) => frozen.cast_shadows(),
+        }
+    }
+
+    pub fn u(&self) -> f64 {
+        self.u
+    }
+
+    pub fn v(&self) -> f64 {
+        self.v
     }
 
     pub fn is_equal(&self, other: &Intersection) -> bool {
         if !crate::utils::is_equal_f64(self.t(), other.t()) {
             return false;
         }
-        Rc::ptr_eq(self.object(), other.object())
+        Arc::ptr_eq(self.object(), other.object())
     }
 }
\ No newline at end of file
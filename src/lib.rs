@@ -8,8 +8,16 @@ pub mod environment;
 pub use environment::Environment;
 pub mod canvas;
 pub use canvas::Canvas;
+pub use canvas::CanvasError;
+pub mod background;
+pub use background::Background;
+pub use background::SolidBackground;
+pub use background::GradientBackground;
+pub use background::ImageBackground;
 pub mod ray;
 pub use ray::Ray;
+pub mod motion;
+pub use motion::Motion;
 pub mod shape;
 pub use shape::Shape;
 pub mod sphere;
@@ -22,6 +30,14 @@ pub use intersections::IntersectionList;
 pub use intersections::Computations;
 pub mod lights;
 pub use lights::PointLight;
+pub use lights::AreaLight;
+pub use lights::SpotLight;
+pub use lights::DirectionalLight;
+pub use lights::Light;
+pub use lights::LightSample;
+pub use lights::Attenuation;
+pub mod image_light;
+pub use image_light::ImageLight;
 pub mod patterns;
 pub use patterns::Pattern;
 pub use patterns::TestPattern;
@@ -35,13 +51,39 @@ pub use patterns::NestedCheckersPattern;
 pub use patterns::BlendedPattern;
 pub use patterns::PerturbedPattern;
 pub mod materials;
+pub use materials::ShadingModel;
+pub use materials::Dispersion;
+pub mod microfacet;
 pub use materials::Material;
 pub mod render;
 pub use render::lighting;
+pub use render::RenderSettings;
+pub use render::Sampling;
+pub mod integrator;
+pub use integrator::Integrator;
+pub use integrator::Whitted;
+pub use integrator::NormalView;
+pub use integrator::DepthView;
+pub use integrator::AlbedoView;
+pub use integrator::UvView;
+pub use integrator::ObjectIdView;
+pub use integrator::BounceView;
+pub mod path_tracer;
+pub use path_tracer::PathTracer;
 pub mod world;
 pub use world::World;
 pub mod camera;
 pub use camera::Camera;
+pub use camera::Aperture;
+pub use camera::Projection;
+pub mod animation;
+pub use animation::Animation;
+pub use animation::AnimationError;
+pub use animation::Channel;
+pub use animation::Track;
+pub use animation::Interpolation;
+pub use animation::Interpolate;
+pub use animation::MaterialValue;
 pub mod constants;
 pub use constants::MAX_ITERATIONS;
 pub mod cube;
@@ -50,5 +92,28 @@ pub mod cylinder;
 pub use cylinder::Cylinder;
 pub mod cone;
 pub use cone::Cone;
+pub mod group;
+pub use group::Group;
+pub mod triangle;
+pub use triangle::Triangle;
+pub mod smooth_triangle;
+pub use smooth_triangle::SmoothTriangle;
+pub mod obj_file;
+pub use obj_file::ObjParser;
+pub use obj_file::ObjError;
+pub mod bounds;
+pub use bounds::Bounds;
+pub mod bvh;
+pub use bvh::Bvh;
+pub mod frozen;
+pub use frozen::FrozenShape;
+pub use frozen::FreezeScope;
+pub use frozen::FrozenScene;
+pub mod csg;
+pub use csg::Csg;
+pub use csg::CsgOperation;
+
+pub mod rng;
+pub use rng::Rng;
 
 pub mod utils;
\ No newline at end of file
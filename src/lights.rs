@@ -1,13 +1,110 @@
 use crate::Tuples;
+use crate::Rng;
+use std::fmt::Debug;
 
 pub fn point_light(position: &Tuples, intensity: &Tuples) -> PointLight {
-    PointLight { position: position.clone(), intensity: intensity.clone() }
+    PointLight { position: *position, intensity: *intensity, attenuation: Attenuation::None }
+}
+
+// full_uvec and full_vvec are the two edges of the rectangle, split into usteps x vsteps cells
+pub fn area_light(corner: &Tuples, full_uvec: &Tuples, usteps: usize, full_vvec: &Tuples, vsteps: usize, intensity: &Tuples) -> AreaLight {
+    let uvec = full_uvec.clone().scale(1.0 / usteps as f64);
+    let vvec = full_vvec.clone().scale(1.0 / vsteps as f64);
+    let position = corner.clone()
+        .add(&full_uvec.clone().scale(0.5))
+        .add(&full_vvec.clone().scale(0.5));
+    AreaLight { corner: *corner, uvec, usteps, vvec, vsteps, position, intensity: *intensity, attenuation: Attenuation::None, jitter: true }
+}
+
+// the angles are measured from the axis of the cone in radians, full intensity inside of
+// inner_angle, fading out until outer_angle
+pub fn spot_light(position: &Tuples, direction: &Tuples, inner_angle: f64, outer_angle: f64, intensity: &Tuples) -> SpotLight {
+    SpotLight {
+        position: *position,
+        direction: direction.clone().normalize(),
+        inner_angle,
+        outer_angle: outer_angle.max(inner_angle),
+        intensity: *intensity,
+        attenuation: Attenuation::None,
+    }
+}
+
+// direction is the way the light travels, e.g. vector(0, -1, 0) for a sun straight above
+pub fn directional_light(direction: &Tuples, intensity: &Tuples) -> DirectionalLight {
+    DirectionalLight { direction: direction.clone().normalize(), intensity: *intensity }
+}
+
+// how the intensity of a light falls off with the distance, with attenuation the intensity
+// of a light is the one that arrives at a distance of 1. Only diffuse and specular light
+// fall off, the ambient term stands in for light bounced around the scene and stays at the
+// full intensity, like it does outside of the cone of a spot light
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Attenuation {
+    #[default]
+    None,
+    Linear,
+    InverseSquare,
+    Coefficients { constant: f64, linear: f64, quadratic: f64 },
+}
+
+impl Attenuation {
+    // 1 / (constant + linear * d + quadratic * d²), lights at infinity are never attenuated
+    pub fn factor(&self, distance: f64) -> f64 {
+        let (constant, linear, quadratic) = match *self {
+            Attenuation::None => return 1.0,
+            Attenuation::Linear => (0.0, 1.0, 0.0),
+            Attenuation::InverseSquare => (0.0, 0.0, 1.0),
+            Attenuation::Coefficients { constant, linear, quadratic } => (constant, linear, quadratic),
+        };
+        let denominator = constant + linear * distance + quadratic * distance * distance;
+        if distance.is_infinite() || denominator <= 0.0 {
+            return 1.0;
+        }
+        1.0 / denominator
+    }
+}
+
+// a point on a light as seen from the shaded point
+#[derive(Debug, Clone, Copy)]
+pub struct LightSample {
+    pub direction: Tuples, // normalized, from the shaded point towards the light
+    pub distance: f64, // infinite for directional lights
+    pub intensity: Tuples, // color arriving from this sample if nothing is in the way
+}
+
+pub trait Light: Debug + Send + Sync {
+    fn get_type(&self) -> &str;
+    fn intensity(&self) -> &Tuples;
+    // color that reaches point if nothing is in the way
+    fn intensity_at(&self, _point: &Tuples) -> Tuples {
+        *self.intensity()
+    }
+    fn direction_to_light(&self, point: &Tuples) -> Tuples;
+    fn distance_to_light(&self, point: &Tuples) -> f64;
+    fn attenuation(&self) -> Attenuation {
+        Attenuation::None
+    }
+    // lights without a position, like directional lights, stay as they are
+    fn set_position(&mut self, _position: &Tuples) {}
+    // lights with an extent return one sample per cell for soft shadows
+    fn samples(&self, point: &Tuples) -> Vec<LightSample> {
+        let intensity = self.intensity_at(point);
+        vec![LightSample { direction: self.direction_to_light(point), distance: self.distance_to_light(point), intensity }]
+    }
+}
+
+// normalized direction from point to position and the distance between them
+fn towards(position: &Tuples, point: &Tuples) -> (Tuples, f64) {
+    let mut vector = position.clone().subtract(point);
+    let distance = vector.magnitude();
+    (vector.normalize(), distance)
 }
 
 #[derive(Debug, Clone)]
 pub struct PointLight {
     position: Tuples,
     intensity: Tuples,
+    attenuation: Attenuation,
 }
 
 impl PointLight {
@@ -15,11 +112,252 @@ impl PointLight {
         &self.position
     }
 
-    pub fn intensity(&self) -> &Tuples {
-        &self.intensity
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> PointLight {
+        self.attenuation = attenuation;
+        self
     }
 
     pub fn is_equal(&self, other: &PointLight) -> bool {
-        self.position().is_equal(other.position()) && self.intensity().is_equal(other.intensity()) 
+        self.position().is_equal(other.position()) && self.intensity().is_equal(other.intensity()) &&
+            self.attenuation == other.attenuation
+    }
+}
+
+impl Light for PointLight {
+    fn get_type(&self) -> &str {
+        "PointLight"
+    }
+
+    fn intensity(&self) -> &Tuples {
+        &self.intensity
+    }
+
+    fn direction_to_light(&self, point: &Tuples) -> Tuples {
+        towards(&self.position, point).0
+    }
+
+    fn distance_to_light(&self, point: &Tuples) -> f64 {
+        towards(&self.position, point).1
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
     }
-}
\ No newline at end of file
+
+    fn set_position(&mut self, position: &Tuples) {
+        self.position = *position;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AreaLight {
+    corner: Tuples,
+    uvec: Tuples, // edge of a single cell
+    usteps: usize,
+    vvec: Tuples,
+    vsteps: usize,
+    position: Tuples, // center of the rectangle
+    intensity: Tuples,
+    attenuation: Attenuation,
+    pub jitter: bool, // without jitter every cell is sampled at its center
+}
+
+impl AreaLight {
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> AreaLight {
+        self.attenuation = attenuation;
+        self
+    }
+
+    pub fn corner(&self) -> &Tuples {
+        &self.corner
+    }
+
+    pub fn uvec(&self) -> &Tuples {
+        &self.uvec
+    }
+
+    pub fn usteps(&self) -> usize {
+        self.usteps
+    }
+
+    pub fn vvec(&self) -> &Tuples {
+        &self.vvec
+    }
+
+    pub fn vsteps(&self) -> usize {
+        self.vsteps
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    pub fn position(&self) -> &Tuples {
+        &self.position
+    }
+
+    // u, v select the cell, ju, jv in 0..1 the position inside of it
+    pub fn point_on_light(&self, u: usize, v: usize, ju: f64, jv: f64) -> Tuples {
+        self.corner.clone()
+            .add(&self.uvec.clone().scale(u as f64 + ju))
+            .add(&self.vvec.clone().scale(v as f64 + jv))
+    }
+
+    // one point per cell, the jitter is seeded by the shaded point so that renders are reproducible
+    pub fn sample_points(&self, seed_point: &Tuples) -> Vec<Tuples> {
+        let mut rng = Rng::for_point(0, seed_point);
+        let mut points = Vec::with_capacity(self.sample_count());
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let (ju, jv) = if self.jitter { (rng.next_f64(), rng.next_f64()) } else { (0.5, 0.5) };
+                points.push(self.point_on_light(u, v, ju, jv));
+            }
+        }
+        points
+    }
+}
+
+impl Light for AreaLight {
+    fn get_type(&self) -> &str {
+        "AreaLight"
+    }
+
+    fn intensity(&self) -> &Tuples {
+        &self.intensity
+    }
+
+    fn direction_to_light(&self, point: &Tuples) -> Tuples {
+        towards(&self.position, point).0
+    }
+
+    fn distance_to_light(&self, point: &Tuples) -> f64 {
+        towards(&self.position, point).1
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    // moves the whole rectangle so that its center ends up at position
+    fn set_position(&mut self, position: &Tuples) {
+        let offset = position.clone().subtract(&self.position);
+        self.corner.add(&offset);
+        self.position = *position;
+    }
+
+    fn samples(&self, point: &Tuples) -> Vec<LightSample> {
+        let intensity = self.intensity_at(point);
+        self.sample_points(point).iter().map(|p| {
+            let (direction, distance) = towards(p, point);
+            LightSample { direction, distance, intensity }
+        }).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpotLight {
+    position: Tuples,
+    direction: Tuples, // axis of the cone, normalized
+    inner_angle: f64,
+    outer_angle: f64,
+    intensity: Tuples,
+    attenuation: Attenuation,
+}
+
+impl SpotLight {
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> SpotLight {
+        self.attenuation = attenuation;
+        self
+    }
+
+    pub fn position(&self) -> &Tuples {
+        &self.position
+    }
+
+    pub fn direction(&self) -> &Tuples {
+        &self.direction
+    }
+
+    pub fn inner_angle(&self) -> f64 {
+        self.inner_angle
+    }
+
+    pub fn outer_angle(&self) -> f64 {
+        self.outer_angle
+    }
+
+    // 1 inside of the inner cone, 0 outside of the outer cone, smoothstep in between
+    pub fn falloff(&self, point: &Tuples) -> f64 {
+        let to_point = self.direction_to_light(point).negate();
+        let cos_angle = Tuples::dot(&to_point, &self.direction);
+        let (cos_inner, cos_outer) = (self.inner_angle.cos(), self.outer_angle.cos());
+        if cos_angle >= cos_inner {
+            return 1.0;
+        }
+        if cos_angle <= cos_outer {
+            return 0.0;
+        }
+        let t = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn get_type(&self) -> &str {
+        "SpotLight"
+    }
+
+    fn intensity(&self) -> &Tuples {
+        &self.intensity
+    }
+
+    fn intensity_at(&self, point: &Tuples) -> Tuples {
+        self.intensity.clone().scale(self.falloff(point))
+    }
+
+    fn direction_to_light(&self, point: &Tuples) -> Tuples {
+        towards(&self.position, point).0
+    }
+
+    fn distance_to_light(&self, point: &Tuples) -> f64 {
+        towards(&self.position, point).1
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn set_position(&mut self, position: &Tuples) {
+        self.position = *position;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectionalLight {
+    direction: Tuples, // normalized
+    intensity: Tuples,
+}
+
+impl DirectionalLight {
+    pub fn direction(&self) -> &Tuples {
+        &self.direction
+    }
+}
+
+impl Light for DirectionalLight {
+    fn get_type(&self) -> &str {
+        "DirectionalLight"
+    }
+
+    fn intensity(&self) -> &Tuples {
+        &self.intensity
+    }
+
+    fn direction_to_light(&self, _point: &Tuples) -> Tuples {
+        self.direction.clone().negate()
+    }
+
+    fn distance_to_light(&self, _point: &Tuples) -> f64 {
+        f64::INFINITY
+    }
+}
@@ -1,11 +1,46 @@
 use crate::{utils::is_equal_f64, Tuples};
 use crate::patterns::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// how light from the lights is reflected, reflective and transparency work with both
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShadingModel {
+    #[default]
+    Phong, // ambient, diffuse, specular and shininess
+    Microfacet, // GGX / Cook-Torrance with the pattern as base color, roughness and metallic
+}
+
+// wavelengths in micrometers that the red, green and blue channels are refracted at
+pub const CHANNEL_WAVELENGTHS: [f64; 3] = [0.65, 0.55, 0.45];
+
+// how the refractive index changes with the wavelength of the light
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Dispersion {
+    #[default]
+    None, // refractive_index for every wavelength
+    Cauchy { a: f64, b: f64 }, // n = a + b / λ², λ in micrometers
+    Sellmeier { b: [f64; 3], c: [f64; 3] }, // n² = 1 + Σ bᵢ λ² / (λ² - cᵢ), cᵢ in square micrometers
+}
+
+impl Dispersion {
+    // None when the index doesn't depend on the wavelength
+    pub fn index_at(&self, wavelength: f64) -> Option<f64> {
+        let l2 = wavelength * wavelength;
+        match self {
+            Dispersion::None => None,
+            Dispersion::Cauchy { a, b } => Some(a + b / l2),
+            Dispersion::Sellmeier { b, c } => {
+                let n2 = 1.0 + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>();
+                Some(n2.max(0.0).sqrt())
+            },
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Material {
-    pub pattern: Rc<RefCell<dyn Pattern>>,
+    pub pattern: Arc<RwLock<dyn Pattern>>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
@@ -13,6 +48,19 @@ pub struct Material {
     pub reflective: f64,
     pub transparency: f64,
     pub refractive_index: f64,
+    pub dispersion: Dispersion, // refractive index per wavelength, overrides refractive_index for refraction
+    pub medium_priority: u32, // where transparent objects overlap the one with the higher priority fills the space
+    pub emission: Tuples, // color of the light the surface gives off by itself
+    pub emission_strength: f64,
+    pub shading: ShadingModel,
+    // 0 is perfectly smooth, rough surfaces blur reflection and refraction. Microfacet shading
+    // uses the same value for the width of its highlights, so a microfacet surface that is also
+    // reflective blurs its mirror image as much as its highlights are spread
+    pub roughness: f64,
+    pub glossy_samples: usize, // rays for blurred reflection and refraction
+    pub absorption: Tuples, // color of the medium inside of the object, white is perfectly clear
+    pub absorption_density: f64, // how quickly light takes on the absorption color, 0 for none
+    pub metallic: f64, // 0 for dielectrics, 1 for conductors
 }
 
 impl Material {
@@ -26,7 +74,52 @@ impl Material {
             reflective: 0.0,
             transparency: 0.0,
             refractive_index: 1.0,
+            dispersion: Dispersion::None,
+            medium_priority: 0,
+            emission: Tuples::color(0.0, 0.0, 0.0),
+            emission_strength: 1.0,
+            shading: ShadingModel::Phong,
+            roughness: 0.0,
+            glossy_samples: 16,
+            absorption: Tuples::color(1.0, 1.0, 1.0),
+            absorption_density: 0.0,
+            metallic: 0.0,
+        }
+    }
+
+    // light given off by the surface, seen no matter how it is lit
+    pub fn emitted(&self) -> Tuples {
+        self.emission.clone().scale(self.emission_strength)
+    }
+
+    // share of the light that is scattered diffusely by a surface of the given color
+    pub fn diffuse_albedo(&self, color: &Tuples) -> Tuples {
+        match self.shading {
+            ShadingModel::Phong => color.clone().scale(self.diffuse),
+            ShadingModel::Microfacet => color.clone().scale(1.0 - self.metallic),
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        self.dispersion != Dispersion::None
+    }
+
+    // refractive index for the red, green and blue channel
+    pub fn channel_indices(&self) -> [f64; 3] {
+        CHANNEL_WAVELENGTHS.map(|l| self.dispersion.index_at(l).unwrap_or(self.refractive_index))
+    }
+
+    // Beer-Lambert, the share of light that is left after travelling distance inside the object
+    pub fn transmittance(&self, distance: f64) -> Tuples {
+        if self.absorption_density <= 0.0 {
+            return Tuples::color(1.0, 1.0, 1.0);
         }
+        let k = self.absorption_density * distance;
+        Tuples::color(
+            (-(1.0 - self.absorption.x) * k).exp(),
+            (-(1.0 - self.absorption.y) * k).exp(),
+            (-(1.0 - self.absorption.z) * k).exp(),
+        )
     }
 
     pub fn is_equal(&self, other: &Material) -> bool {
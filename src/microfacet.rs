@@ -0,0 +1,60 @@
+use crate::Tuples;
+use crate::Material;
+use std::f64::consts::PI;
+
+// perfectly smooth surfaces would have an infinitely small highlight, their mirror image
+// comes from reflective instead
+const MIN_ROUGHNESS: f64 = 0.03;
+
+fn alpha2(roughness: f64) -> f64 {
+    let alpha = roughness.clamp(MIN_ROUGHNESS, 1.0).powi(2);
+    alpha * alpha
+}
+
+// GGX (Trowbridge-Reitz) distribution of the microfacet normals
+pub fn distribution(n_dot_h: f64, roughness: f64) -> f64 {
+    let a2 = alpha2(roughness);
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    a2 / (PI * d * d)
+}
+
+// Smith shadowing-masking, the share of microfacets seen from both directions
+pub fn geometry(n_dot_v: f64, n_dot_l: f64, roughness: f64) -> f64 {
+    let a2 = alpha2(roughness);
+    let g1 = |n_dot: f64| 2.0 * n_dot / (n_dot + (a2 + (1.0 - a2) * n_dot * n_dot).sqrt());
+    g1(n_dot_v) * g1(n_dot_l)
+}
+
+// Schlick's approximation for the reflectance at the given angle
+pub fn fresnel(f0: &Tuples, cos_theta: f64) -> Tuples {
+    let factor = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+    f0.clone().add(&Tuples::color(1.0, 1.0, 1.0).subtract(f0).scale(factor))
+}
+
+// reflectance at normal incidence. Dielectrics reflect a little of every color, as much as
+// the refractive index gives (0.04 for the default index of 1), conductors tint with the base color
+pub fn f0(material: &Material, base_color: &Tuples) -> Tuples {
+    let n = material.refractive_index;
+    let r = if n > 1.0 { ((n - 1.0) / (n + 1.0)).powi(2) } else { 0.04 };
+    Tuples::color(r, r, r).scale(1.0 - material.metallic).add(&base_color.clone().scale(material.metallic))
+}
+
+// Cook-Torrance: share of the light arriving from light_v that leaves towards eye_v, including
+// the cosine at the surface. Scaled by pi so that a white dielectric reflects about as much as
+// phong with diffuse 1
+pub fn reflectance(material: &Material, base_color: &Tuples, light_v: &Tuples, eye_v: &Tuples, normal_v: &Tuples) -> Tuples {
+    let n_dot_l = Tuples::dot(normal_v, light_v);
+    if n_dot_l <= 0.0 {
+        return Tuples::color(0.0, 0.0, 0.0);
+    }
+    let n_dot_v = Tuples::dot(normal_v, eye_v).max(1e-4);
+    let half_v = light_v.clone().add(eye_v).normalize();
+    let n_dot_h = Tuples::dot(normal_v, &half_v).max(0.0);
+    let v_dot_h = Tuples::dot(eye_v, &half_v).max(0.0);
+
+    let f = fresnel(&f0(material, base_color), v_dot_h);
+    let specular = f.clone().scale(distribution(n_dot_h, material.roughness) * geometry(n_dot_v, n_dot_l, material.roughness) / (4.0 * n_dot_l * n_dot_v));
+    // what is not reflected at the surface enters it, metals absorb all of it
+    let mut diffuse = Tuples::color(1.0, 1.0, 1.0).subtract(&f).multiply(base_color).scale((1.0 - material.metallic) / PI);
+    diffuse.add(&specular).scale(n_dot_l * PI)
+}
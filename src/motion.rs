@@ -0,0 +1,261 @@
+use crate::Matrix;
+use crate::Bounds;
+use crate::Tuples;
+
+// a transform at a point in time, split into translation, rotation and the scale (and shear)
+// that is left over, so that every part can be interpolated on its own
+#[derive(Debug, Clone, Copy)]
+struct Keyframe {
+    time: f64,
+    translation: [f64; 3],
+    rotation: [f64; 4], // unit quaternion w, x, y, z
+    stretch: [[f64; 3]; 3],
+}
+
+// transform of a shape that changes over time. Between keyframes the translation and scale
+// are interpolated linearly and the rotation along the shortest arc, so two keyframes more than
+// half a turn apart rotate the other way round. Before the first and after the last keyframe
+// the shape stands still
+#[derive(Debug, Clone)]
+pub struct Motion {
+    keyframes: Vec<Keyframe>,
+}
+
+// steps per pair of keyframes the bounds are sampled at
+const BOUNDS_STEPS: usize = 32;
+
+impl Motion {
+    // moves from start at time 0 to end at time 1
+    pub fn new(start: &Matrix, end: &Matrix) -> Motion {
+        Motion::from_keyframes(&[(0.0, start.clone()), (1.0, end.clone())])
+    }
+
+    pub fn from_keyframes(keyframes: &[(f64, Matrix)]) -> Motion {
+        if keyframes.is_empty() {
+            panic!("Motion.from_keyframes: at least one keyframe is needed");
+        }
+        let mut keyframes: Vec<Keyframe> = keyframes.iter().map(|(time, m)| Motion::decompose(*time, m)).collect();
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        // neighbouring rotations on the same side of the quaternion sphere take the short way
+        for i in 1..keyframes.len() {
+            if dot4(&keyframes[i - 1].rotation, &keyframes[i].rotation) < 0.0 {
+                keyframes[i].rotation = keyframes[i].rotation.map(|c| -c);
+            }
+        }
+        Motion { keyframes }
+    }
+
+    pub fn start(&self) -> f64 {
+        self.keyframes[0].time
+    }
+
+    pub fn end(&self) -> f64 {
+        self.keyframes[self.keyframes.len() - 1].time
+    }
+
+    pub fn transform_at(&self, time: f64) -> Matrix {
+        let next = self.keyframes.iter().position(|k| k.time > time);
+        let keyframe = match next {
+            None => self.keyframes[self.keyframes.len() - 1],
+            Some(0) => self.keyframes[0],
+            Some(i) => {
+                let (a, b) = (&self.keyframes[i - 1], &self.keyframes[i]);
+                Motion::interpolate(a, b, (time - a.time) / (b.time - a.time))
+            },
+        };
+        Motion::compose(&keyframe)
+    }
+
+    pub fn inverse_at(&self, time: f64) -> Matrix {
+        Matrix::inverse(&self.transform_at(time)).unwrap()
+    }
+
+    // box around the object space bounds b for every transform the motion goes through. The
+    // transforms are sampled, rotating points bulge out between two samples, so the box grows
+    // by the most any corner of b leaves the line between its places at neighbouring samples
+    pub fn bounds(&self, b: &Bounds) -> Bounds {
+        let mut out = Bounds::empty();
+        out.add_bounds(&Bounds::transform(b, &Motion::compose(&self.keyframes[0])));
+        let mut padding: f64 = 0.0;
+        for pair in self.keyframes.windows(2) {
+            let mut previous = Motion::compose(&pair[0]);
+            for step in 1..=BOUNDS_STEPS {
+                let f = step as f64 / BOUNDS_STEPS as f64;
+                let current = Motion::compose(&Motion::interpolate(&pair[0], &pair[1], f));
+                let halfway = Motion::compose(&Motion::interpolate(&pair[0], &pair[1], f - 0.5 / BOUNDS_STEPS as f64));
+                padding = padding.max(Motion::bulge(b, &previous, &halfway, &current));
+                out.add_bounds(&Bounds::transform(b, &current));
+                previous = current;
+            }
+        }
+        if padding > 0.0 && !out.is_infinite() {
+            let pad = Tuples::vector(padding, padding, padding);
+            out.min.subtract(&pad);
+            out.max.add(&pad);
+        }
+        out
+    }
+
+    // how far a corner of b ends up from the middle of its places under a and c when it is
+    // transformed by the transform halfway between them. The distance is affine in the point,
+    // so no point of b gets further away than the corners
+    fn bulge(b: &Bounds, a: &Matrix, halfway: &Matrix, c: &Matrix) -> f64 {
+        if b.is_empty() || b.is_infinite() {
+            return 0.0;
+        }
+        let mut most: f64 = 0.0;
+        for corner in 0..8 {
+            let p = Tuples::point(
+                if corner & 1 == 0 { b.min.x } else { b.max.x },
+                if corner & 2 == 0 { b.min.y } else { b.max.y },
+                if corner & 4 == 0 { b.min.z } else { b.max.z },
+            );
+            let middle = (a * &p).add(&(c * &p)).scale(0.5);
+            most = most.max((halfway * &p).subtract(&middle).magnitude());
+        }
+        most
+    }
+
+    fn interpolate(a: &Keyframe, b: &Keyframe, f: f64) -> Keyframe {
+        let lerp = |x: f64, y: f64| x + (y - x) * f;
+        let mut stretch = [[0.0; 3]; 3];
+        for (row, out) in stretch.iter_mut().enumerate() {
+            for (col, v) in out.iter_mut().enumerate() {
+                *v = lerp(a.stretch[row][col], b.stretch[row][col]);
+            }
+        }
+        Keyframe {
+            time: lerp(a.time, b.time),
+            translation: [0, 1, 2].map(|i| lerp(a.translation[i], b.translation[i])),
+            rotation: slerp(&a.rotation, &b.rotation, f),
+            stretch,
+        }
+    }
+
+    fn decompose(time: f64, m: &Matrix) -> Keyframe {
+        let translation = [m.get(0, 3), m.get(1, 3), m.get(2, 3)];
+        let mut linear = [[0.0; 3]; 3];
+        for (row, out) in linear.iter_mut().enumerate() {
+            for (col, v) in out.iter_mut().enumerate() {
+                *v = m.get(row, col);
+            }
+        }
+        // polar decomposition, averaging with the inverse transpose converges to the rotation
+        let mut rotation = linear;
+        for _ in 0..100 {
+            let inverse_t = transpose3(&inverse3(&rotation));
+            let mut next = [[0.0; 3]; 3];
+            let mut change: f64 = 0.0;
+            for row in 0..3 {
+                for col in 0..3 {
+                    next[row][col] = 0.5 * (rotation[row][col] + inverse_t[row][col]);
+                    change = change.max((next[row][col] - rotation[row][col]).abs());
+                }
+            }
+            rotation = next;
+            if change < 1e-12 {
+                break;
+            }
+        }
+        // a mirroring transform keeps the mirror in the scale
+        if det3(&rotation) < 0.0 {
+            rotation = rotation.map(|row| row.map(|v| -v));
+        }
+        let stretch = mul3(&transpose3(&rotation), &linear);
+        Keyframe { time, translation, rotation: quaternion(&rotation), stretch }
+    }
+
+    fn compose(k: &Keyframe) -> Matrix {
+        let linear = mul3(&rotation_matrix(&k.rotation), &k.stretch);
+        let mut m = Matrix::new(4);
+        for (row, values) in linear.iter().enumerate() {
+            for (col, v) in values.iter().enumerate() {
+                m.set(row, col, *v);
+            }
+            m.set(row, 3, k.translation[row]);
+        }
+        m
+    }
+}
+
+fn dot4(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn slerp(a: &[f64; 4], b: &[f64; 4], f: f64) -> [f64; 4] {
+    let cos_theta = dot4(a, b).clamp(-1.0, 1.0);
+    let (wa, wb) = if cos_theta > 0.9995 {
+        // nearly the same rotation, a straight line is close enough
+        (1.0 - f, f)
+    } else {
+        let theta = cos_theta.acos();
+        (((1.0 - f) * theta).sin() / theta.sin(), (f * theta).sin() / theta.sin())
+    };
+    let q = [0, 1, 2, 3].map(|i| wa * a[i] + wb * b[i]);
+    let length = dot4(&q, &q).sqrt();
+    q.map(|c| c / length)
+}
+
+fn quaternion(r: &[[f64; 3]; 3]) -> [f64; 4] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    if trace > 0.0 {
+        let s = 0.5 / (trace + 1.0).sqrt();
+        [0.25 / s, (r[2][1] - r[1][2]) * s, (r[0][2] - r[2][0]) * s, (r[1][0] - r[0][1]) * s]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = 2.0 * (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt();
+        [(r[2][1] - r[1][2]) / s, 0.25 * s, (r[0][1] + r[1][0]) / s, (r[0][2] + r[2][0]) / s]
+    } else if r[1][1] > r[2][2] {
+        let s = 2.0 * (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt();
+        [(r[0][2] - r[2][0]) / s, (r[0][1] + r[1][0]) / s, 0.25 * s, (r[1][2] + r[2][1]) / s]
+    } else {
+        let s = 2.0 * (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt();
+        [(r[1][0] - r[0][1]) / s, (r[0][2] + r[2][0]) / s, (r[1][2] + r[2][1]) / s, 0.25 * s]
+    }
+}
+
+fn rotation_matrix(q: &[f64; 4]) -> [[f64; 3]; 3] {
+    let [w, x, y, z] = *q;
+    [
+        [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+        [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+        [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+    ]
+}
+
+fn mul3(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut out = [[0.0; 3]; 3];
+    for (row, values) in out.iter_mut().enumerate() {
+        for (col, v) in values.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[row][k] * b[k][col]).sum();
+        }
+    }
+    out
+}
+
+fn transpose3(a: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut out = [[0.0; 3]; 3];
+    for (row, values) in out.iter_mut().enumerate() {
+        for (col, v) in values.iter_mut().enumerate() {
+            *v = a[col][row];
+        }
+    }
+    out
+}
+
+fn det3(a: &[[f64; 3]; 3]) -> f64 {
+    Tuples::dot(&Tuples::vector(a[0][0], a[0][1], a[0][2]), &Tuples::cross(&Tuples::vector(a[1][0], a[1][1], a[1][2]), &Tuples::vector(a[2][0], a[2][1], a[2][2])))
+}
+
+fn inverse3(a: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let det = det3(a);
+    let mut out = [[0.0; 3]; 3];
+    for (row, values) in out.iter_mut().enumerate() {
+        for (col, v) in values.iter_mut().enumerate() {
+            // cofactor of the transposed position
+            let (r1, r2) = ((col + 1) % 3, (col + 2) % 3);
+            let (c1, c2) = ((row + 1) % 3, (row + 2) % 3);
+            *v = (a[r1][c1] * a[r2][c2] - a[r1][c2] * a[r2][c1]) / det;
+        }
+    }
+    out
+}
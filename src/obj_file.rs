@@ -0,0 +1,205 @@
+use crate::Tuples;
+use crate::Shape;
+use crate::Group;
+use crate::Triangle;
+use crate::SmoothTriangle;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::fmt;
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjError {
+    pub line: usize, // 1-based, 0 if the file could not be read at all
+    pub message: String,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+// one corner of a face, indices are 0-based after parsing
+#[derive(Debug, Clone, Copy)]
+struct FaceVertex {
+    vertex: usize,
+    normal: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct ObjParser {
+    pub ignored: usize,
+    vertices: Vec<Tuples>,
+    normals: Vec<Tuples>,
+    texture_coords: Vec<Tuples>,
+    default_group: Arc<RwLock<Group>>,
+    named_groups: Vec<(String, Arc<RwLock<Group>>)>,
+}
+
+impl ObjParser {
+    pub fn parse_obj_file(path: &str) -> Result<ObjParser, ObjError> {
+        let content = fs::read_to_string(path).map_err(|e| ObjError { line: 0, message: format!("failed to read {path}: {e}") })?;
+        ObjParser::parse_obj(&content)
+    }
+
+    pub fn parse_obj(input: &str) -> Result<ObjParser, ObjError> {
+        let mut parser = ObjParser {
+            ignored: 0,
+            vertices: vec![],
+            normals: vec![],
+            texture_coords: vec![],
+            default_group: Group::new(),
+            named_groups: vec![],
+        };
+        // triangles are collected per group and added in one go, so every bvh is built only once
+        type Pending = (Arc<RwLock<Group>>, Vec<Arc<RwLock<dyn Shape>>>);
+        let mut pending: Vec<Pending> = vec![(Arc::clone(&parser.default_group), vec![])];
+        let mut current = 0;
+
+        for (idx, line) in input.lines().enumerate() {
+            let line_nr = idx + 1;
+            let mut tokens = line.split_whitespace();
+            let keyword = match tokens.next() {
+                Some(k) => k,
+                None => continue, // blank line
+            };
+            let args: Vec<&str> = tokens.collect();
+            match keyword {
+                "v" => {
+                    let v = ObjParser::parse_floats(&args, 3, line_nr)?;
+                    parser.vertices.push(Tuples::point(v[0], v[1], v[2]));
+                },
+                "vn" => {
+                    let v = ObjParser::parse_floats(&args, 3, line_nr)?;
+                    parser.normals.push(Tuples::vector(v[0], v[1], v[2]));
+                },
+                "vt" => {
+                    let v = ObjParser::parse_floats(&args, 1, line_nr)?;
+                    let (u, v, w) = (v[0], *v.get(1).unwrap_or(&0.0), *v.get(2).unwrap_or(&0.0));
+                    parser.texture_coords.push(Tuples::new(u, v, w, 0.0));
+                },
+                "f" => {
+                    let corners = parser.parse_face(&args, line_nr)?;
+                    pending[current].1.extend(parser.fan_triangulation(&corners));
+                },
+                "g" | "o" => {
+                    let name = args.join(" ");
+                    current = match parser.named_groups.iter().position(|(n, _)| *n == name) {
+                        Some(index) => index + 1,
+                        None => {
+                            let g = Group::new();
+                            parser.named_groups.push((name, Arc::clone(&g)));
+                            pending.push((g, vec![]));
+                            pending.len() - 1
+                        }
+                    };
+                },
+                _ => parser.ignored += 1,
+            }
+        }
+        for (group, children) in pending {
+            Group::add_children(&group, children);
+        }
+        Ok(parser)
+    }
+
+    // collects the default group and all named groups into a single group that can be added to a world
+    pub fn to_group(&self) -> Arc<RwLock<Group>> {
+        let g = Group::new();
+        if !self.default_group.read().unwrap().is_empty() {
+            Group::add_child(&g, self.default_group.clone());
+        }
+        for (_, named) in self.named_groups.iter() {
+            Group::add_child(&g, named.clone());
+        }
+        g
+    }
+
+    pub fn default_group(&self) -> &Arc<RwLock<Group>> {
+        &self.default_group
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Arc<RwLock<Group>>> {
+        self.named_groups.iter().find(|(n, _)| n == name).map(|(_, g)| g)
+    }
+
+    // OBJ indices start at 1
+    pub fn vertex(&self, index: usize) -> Option<&Tuples> {
+        index.checked_sub(1).and_then(|i| self.vertices.get(i))
+    }
+
+    pub fn normal(&self, index: usize) -> Option<&Tuples> {
+        index.checked_sub(1).and_then(|i| self.normals.get(i))
+    }
+
+    pub fn texture_coord(&self, index: usize) -> Option<&Tuples> {
+        index.checked_sub(1).and_then(|i| self.texture_coords.get(i))
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn parse_floats(args: &[&str], min_count: usize, line_nr: usize) -> Result<Vec<f64>, ObjError> {
+        if args.len() < min_count {
+            return Err(ObjError { line: line_nr, message: format!("expected at least {min_count} values, got {}", args.len()) });
+        }
+        args.iter().map(|a| {
+            a.parse::<f64>().map_err(|_| ObjError { line: line_nr, message: format!("invalid number '{a}'") })
+        }).collect()
+    }
+
+    // resolves a 1-based or negative (relative to the end) OBJ index
+    fn resolve_index(token: &str, len: usize, kind: &str, line_nr: usize) -> Result<usize, ObjError> {
+        let idx = token.parse::<i64>().map_err(|_| ObjError { line: line_nr, message: format!("invalid {kind} index '{token}'") })?;
+        let resolved = if idx < 0 { len as i64 + idx } else { idx - 1 };
+        if resolved < 0 || resolved >= len as i64 {
+            return Err(ObjError { line: line_nr, message: format!("{kind} index {idx} out of range, {len} defined") });
+        }
+        Ok(resolved as usize)
+    }
+
+    // accepts v, v/vt, v//vn and v/vt/vn
+    fn parse_face(&self, args: &[&str], line_nr: usize) -> Result<Vec<FaceVertex>, ObjError> {
+        if args.len() < 3 {
+            return Err(ObjError { line: line_nr, message: format!("a face needs at least 3 vertices, got {}", args.len()) });
+        }
+        args.iter().map(|arg| {
+            let parts: Vec<&str> = arg.split('/').collect();
+            if parts.len() > 3 {
+                return Err(ObjError { line: line_nr, message: format!("invalid face vertex '{arg}'") });
+            }
+            let vertex = ObjParser::resolve_index(parts[0], self.vertices.len(), "vertex", line_nr)?;
+            if let Some(vt) = parts.get(1).filter(|p| !p.is_empty()) {
+                ObjParser::resolve_index(vt, self.texture_coords.len(), "texture", line_nr)?;
+            }
+            let normal = match parts.get(2).filter(|p| !p.is_empty()) {
+                Some(vn) => Some(ObjParser::resolve_index(vn, self.normals.len(), "normal", line_nr)?),
+                None => None,
+            };
+            Ok(FaceVertex { vertex, normal })
+        }).collect()
+    }
+
+    // splits convex polygons into triangles sharing the first vertex
+    fn fan_triangulation(&self, corners: &[FaceVertex]) -> Vec<Arc<RwLock<dyn Shape>>> {
+        let mut triangles: Vec<Arc<RwLock<dyn Shape>>> = vec![];
+        let first = corners[0];
+        for pair in corners[1..].windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let p1 = self.vertices[first.vertex];
+            let p2 = self.vertices[a.vertex];
+            let p3 = self.vertices[b.vertex];
+            match (first.normal, a.normal, b.normal) {
+                (Some(n1), Some(n2), Some(n3)) => {
+                    triangles.push(SmoothTriangle::new(p1, p2, p3, self.normals[n1], self.normals[n2], self.normals[n3]));
+                },
+                _ => triangles.push(Triangle::new(p1, p2, p3)),
+            }
+        }
+        triangles
+    }
+}
@@ -0,0 +1,152 @@
+use crate::*;
+use crate::intersections::IntersectionList;
+
+// global illumination by following random paths through the scene. Diffuse surfaces bounce
+// light onto each other and emissive surfaces light the scene, mirrors and glass are traced
+// like in World::color_at so that purely specular scenes look the same
+#[derive(Debug, Clone, Copy)]
+pub struct PathTracer {
+    pub max_depth: usize, // bounces before a path is cut off
+    pub roulette_depth: usize, // bounces before paths may be terminated at random
+}
+
+impl Default for PathTracer {
+    fn default() -> Self {
+        PathTracer { max_depth: 8, roulette_depth: 3 }
+    }
+}
+
+impl PathTracer {
+    pub fn new(max_depth: usize) -> PathTracer {
+        PathTracer { max_depth, ..Default::default() }
+    }
+
+    // channel is the color channel the path is limited to since it went through a dispersive
+    // medium, None while it carries all of them. diffuse is true for rays of a diffuse bounce,
+    // whose light from lights was already added by sampling the lights
+    fn trace(&self, w: &World, r: &Ray, depth: usize, channel: Option<usize>, diffuse: bool, rng: &mut Rng) -> Tuples {
+        let il = World::intersect_world(w, r);
+        let hit = match IntersectionList::hit(&il) {
+            Some(i) => i,
+            None if diffuse && w.is_background_lit() => return Tuples::color(0.0, 0.0, 0.0),
+            None => return w.get_background().color_at(&r.direction().clone().normalize()),
+        };
+        let comps = Intersection::prep_computations(hit, r, &il);
+        let radiance = self.shade(w, &comps, depth, channel, rng);
+        World::absorb(radiance, &comps, r)
+    }
+
+    fn shade(&self, w: &World, comps: &Computations, depth: usize, channel: Option<usize>, rng: &mut Rng) -> Tuples {
+        let material = comps.object.read().unwrap().get_material().clone();
+        let color = material.pattern.read().unwrap().color_at_object_at(&comps.object, &comps.point, comps.time);
+
+        let mut radiance = material.emitted();
+        // light that arrives straight from the lights, the ambient term is left out because
+        // the bounces below take care of indirect light
+        for light in w.get_lights() {
+            let samples: Vec<(LightSample, Tuples)> = light.samples(&comps.point).into_iter()
+                .map(|s| (s, World::transmission_towards_at(w, &comps.over_point, &s, comps.time)))
+                .collect();
+            radiance.add(&render::direct_lighting(&material, &color, light.as_ref(), &samples, &comps.eye_v, &comps.normal_v));
+        }
+        if depth >= self.max_depth {
+            return radiance;
+        }
+
+        // lambertian bounce, the cosine weighted direction cancels cos / pdf so that only the
+        // albedo remains as the weight
+        let mut albedo = material.diffuse_albedo(&color);
+        if albedo.x > 0.0 || albedo.y > 0.0 || albedo.z > 0.0 {
+            let mut survive = 1.0;
+            if depth >= self.roulette_depth {
+                survive = albedo.x.max(albedo.y).max(albedo.z).clamp(0.05, 1.0);
+            }
+            if rng.next_f64() < survive {
+                let direction = cosine_hemisphere(&comps.normal_v, rng.next_f64(), rng.next_f64());
+                let bounce = Ray::new_at(comps.over_point, direction, comps.time);
+                radiance.add(&self.trace(w, &bounce, depth + 1, channel, true, rng).multiply(&albedo.scale(1.0 / survive)));
+            }
+        }
+
+        let mut reflected = Tuples::color(0.0, 0.0, 0.0);
+        if material.reflective > 0.0 {
+            let mut direction = comps.reflect_v;
+            if material.roughness > 0.0 {
+                direction = World::perturb(&direction, &comps.normal_v, material.roughness, rng);
+            }
+            let reflected_ray = Ray::new_at(comps.over_point, direction, comps.time);
+            reflected = self.trace(w, &reflected_ray, depth + 1, channel, false, rng).scale(material.reflective);
+        }
+        // dispersive media refract a single channel picked at random, three times as bright so
+        // that the channels average out to the full color. Paths keep their channel from then on
+        let mut dispersed = None;
+        if material.transparency > 0.0 {
+            if let Some((n1, n2)) = comps.channel_indices() {
+                let c = channel.unwrap_or_else(|| ((rng.next_f64() * 3.0) as usize).min(2));
+                dispersed = Some((n1, n2, c));
+            }
+        }
+        let mut refracted = Tuples::color(0.0, 0.0, 0.0);
+        if material.transparency > 0.0 {
+            let refracted_ray = match dispersed {
+                Some((n1, n2, c)) => World::refracted_ray_with(comps, n1[c], n2[c]),
+                None => World::refracted_ray(comps),
+            };
+            if let Some(mut refracted_ray) = refracted_ray {
+                if material.roughness > 0.0 {
+                    let direction = World::perturb(refracted_ray.direction(), &comps.normal_v.clone().negate(), material.roughness, rng);
+                    refracted_ray = Ray::new_at(*refracted_ray.origin(), direction, refracted_ray.time());
+                }
+                let refracted_channel = dispersed.map(|(_, _, c)| c).or(channel);
+                refracted = self.trace(w, &refracted_ray, depth + 1, refracted_channel, false, rng).scale(material.transparency);
+                if let (Some((_, _, c)), None) = (dispersed, channel) {
+                    let mut single = Tuples::color(0.0, 0.0, 0.0);
+                    single.set_at_idx(c, refracted.get_at_idx(c) * 3.0);
+                    refracted = single;
+                }
+            }
+        }
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            match dispersed {
+                Some((n1, n2, _)) => {
+                    let mut reflectance = Tuples::color(0.0, 0.0, 0.0);
+                    for c in 0..3 {
+                        reflectance.set_at_idx(c, Intersection::schlick_with(comps, n1[c], n2[c]));
+                    }
+                    reflected.multiply(&reflectance);
+                    refracted.multiply(&Tuples::color(1.0, 1.0, 1.0).subtract(&reflectance));
+                },
+                None => {
+                    let reflectance = Intersection::schlick(comps);
+                    reflected.scale(reflectance);
+                    refracted.scale(1.0 - reflectance);
+                },
+            }
+        } else if material.shading == ShadingModel::Microfacet {
+            let cos_theta = Tuples::dot(&comps.eye_v, &comps.normal_v);
+            reflected.multiply(&microfacet::fresnel(&microfacet::f0(&material, &color), cos_theta));
+        }
+        radiance.add(&reflected).add(&refracted)
+    }
+}
+
+impl Integrator for PathTracer {
+    // one random estimate of the light arriving along r, average many for a smooth image
+    fn color_at(&self, w: &World, r: &Ray, rng: &mut Rng) -> Tuples {
+        self.trace(w, r, 0, None, false, rng)
+    }
+}
+
+// direction around normal with a density proportional to the cosine of the angle to it,
+// u and v are uniform in 0..1
+pub fn cosine_hemisphere(normal: &Tuples, u: f64, v: f64) -> Tuples {
+    let radius = u.sqrt();
+    let phi = 2.0 * std::f64::consts::PI * v;
+    let (x, y, z) = (radius * phi.cos(), radius * phi.sin(), (1.0 - u).max(0.0).sqrt());
+
+    // any two vectors perpendicular to the normal and each other
+    let helper = if normal.x.abs() > 0.9 { Tuples::vector(0.0, 1.0, 0.0) } else { Tuples::vector(1.0, 0.0, 0.0) };
+    let tangent = Tuples::cross(&helper, normal).normalize();
+    let bitangent = Tuples::cross(normal, &tangent);
+    tangent.clone().scale(x).add(&bitangent.clone().scale(y)).add(&normal.clone().scale(z)).normalize()
+}
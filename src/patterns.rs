@@ -3,15 +3,19 @@ use crate::utils::perlin_noise;
 use crate::Tuples;
 use crate::Shape;
 use crate::Matrix;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
-pub trait Pattern: Debug {
+pub trait Pattern: Debug + Send + Sync {
     fn color_a(&self) -> &Tuples; // for testing only
     fn color_b(&self) -> &Tuples; // for testing only
     fn color_at(&self, point: &Tuples) -> Tuples;
-    fn color_at_object(&self, object: &Rc<RefCell<dyn Shape>>, point_world: &Tuples) -> Tuples {
-        let point_object = object.borrow().get_transform_inverse() * point_world;
+    fn color_at_object(&self, object: &Arc<RwLock<dyn Shape>>, point_world: &Tuples) -> Tuples {
+        self.color_at_object_at(object, point_world, 0.0)
+    }
+    // a moving object carries its pattern along
+    fn color_at_object_at(&self, object: &Arc<RwLock<dyn Shape>>, point_world: &Tuples, time: f64) -> Tuples {
+        let point_object = <dyn Shape>::world_to_object_at(object, point_world, time);
         let point_pattern = self.get_transform_inverse() * &point_object;
         self.color_at(&point_pattern)
     }
@@ -28,8 +32,8 @@ pub struct TestPattern {
 }
 
 impl TestPattern {
-    pub fn new() -> Rc<RefCell<TestPattern>> {
-        Rc::new(RefCell::new(TestPattern { color: Tuples::color(0.0,0.0,0.0), transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+    pub fn new() -> Arc<RwLock<TestPattern>> {
+        Arc::new(RwLock::new(TestPattern { color: Tuples::color(0.0,0.0,0.0), transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -63,8 +67,8 @@ pub struct SingleColorPattern {
 }
 
 impl SingleColorPattern {
-    pub fn new(color: Tuples) -> Rc<RefCell<SingleColorPattern>> {
-        Rc::new(RefCell::new(SingleColorPattern { color, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+    pub fn new(color: Tuples) -> Arc<RwLock<SingleColorPattern>> {
+        Arc::new(RwLock::new(SingleColorPattern { color, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -99,8 +103,8 @@ pub struct StripePattern {
 }
 
 impl StripePattern {
-    pub fn new(a: Tuples, b: Tuples) -> Rc<RefCell<StripePattern>> {
-        Rc::new(RefCell::new(StripePattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+    pub fn new(a: Tuples, b: Tuples) -> Arc<RwLock<StripePattern>> {
+        Arc::new(RwLock::new(StripePattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -140,9 +144,9 @@ pub struct GradientPattern {
 }
 
 impl GradientPattern {
-    pub fn new(a: Tuples, b: Tuples) -> Rc<RefCell<GradientPattern>> {
+    pub fn new(a: Tuples, b: Tuples) -> Arc<RwLock<GradientPattern>> {
         let color_distance = b.clone().subtract(&a);
-        Rc::new(RefCell::new(GradientPattern { a, b, color_distance, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+        Arc::new(RwLock::new(GradientPattern { a, b, color_distance, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -186,8 +190,8 @@ pub struct RingPattern {
 }
 
 impl RingPattern {
-    pub fn new(a: Tuples, b: Tuples) -> Rc<RefCell<RingPattern>> {
-        Rc::new(RefCell::new(RingPattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+    pub fn new(a: Tuples, b: Tuples) -> Arc<RwLock<RingPattern>> {
+        Arc::new(RwLock::new(RingPattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -224,9 +228,9 @@ pub struct RadialGradientPattern {
 }
 
 impl RadialGradientPattern {
-    pub fn new(a: Tuples, b: Tuples) -> Rc<RefCell<RadialGradientPattern>> {
+    pub fn new(a: Tuples, b: Tuples) -> Arc<RwLock<RadialGradientPattern>> {
         let color_distance = b.clone().subtract(&a);
-        Rc::new(RefCell::new(RadialGradientPattern { a, b, color_distance, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+        Arc::new(RwLock::new(RadialGradientPattern { a, b, color_distance, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -269,8 +273,8 @@ pub struct CheckersPattern {
 }
 
 impl CheckersPattern {
-    pub fn new(a: Tuples, b: Tuples) -> Rc<RefCell<CheckersPattern>> {
-        Rc::new(RefCell::new(CheckersPattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
+    pub fn new(a: Tuples, b: Tuples) -> Arc<RwLock<CheckersPattern>> {
+        Arc::new(RwLock::new(CheckersPattern { a, b, transform: Matrix::new(4), transform_inverse: Matrix::new(4) }))
     }
 }
 
@@ -300,18 +304,18 @@ impl Pattern for CheckersPattern {
 
 #[derive(Debug, Clone)]
 pub struct NestedCheckersPattern {
-    pub a: Rc<RefCell<dyn Pattern>>,
-    pub b: Rc<RefCell<dyn Pattern>>,
+    pub a: Arc<RwLock<dyn Pattern>>,
+    pub b: Arc<RwLock<dyn Pattern>>,
     test_color: Tuples,
     transform: Matrix,
     transform_inverse: Matrix,
 }
 
 impl NestedCheckersPattern {
-    pub fn new(a: Rc<RefCell<dyn Pattern>>, b: Rc<RefCell<dyn Pattern>>) -> Rc<RefCell<NestedCheckersPattern>> {
-        Rc::new(RefCell::new(NestedCheckersPattern {
-            a: Rc::clone(&a),
-            b: Rc::clone(&b),
+    pub fn new(a: Arc<RwLock<dyn Pattern>>, b: Arc<RwLock<dyn Pattern>>) -> Arc<RwLock<NestedCheckersPattern>> {
+        Arc::new(RwLock::new(NestedCheckersPattern {
+            a: Arc::clone(&a),
+            b: Arc::clone(&b),
             test_color: Tuples::color(0.0,0.0,0.0),
             transform: Matrix::new(4),
             transform_inverse: Matrix::new(4)
@@ -330,11 +334,11 @@ impl Pattern for NestedCheckersPattern {
         // should include point.y.floor() but breaks xz planes
         let dist = (point.x.floor() + point.z.floor()) as i32;
         if dist % 2 == 0 {
-            let point_sub_pattern = self.a.borrow().get_transform_inverse() * point;
-            self.a.borrow().color_at(&point_sub_pattern).clone()
+            let point_sub_pattern = self.a.read().unwrap().get_transform_inverse() * point;
+            self.a.read().unwrap().color_at(&point_sub_pattern).clone()
         } else {
-            let point_sub_pattern = self.b.borrow().get_transform_inverse() * point;
-            self.b.borrow().color_at(&point_sub_pattern).clone()
+            let point_sub_pattern = self.b.read().unwrap().get_transform_inverse() * point;
+            self.b.read().unwrap().color_at(&point_sub_pattern).clone()
         }
     }
     fn get_transform(&self) -> &Matrix {
@@ -351,18 +355,18 @@ impl Pattern for NestedCheckersPattern {
 
 #[derive(Debug, Clone)]
 pub struct BlendedPattern {
-    pub a: Rc<RefCell<dyn Pattern>>,
-    pub b: Rc<RefCell<dyn Pattern>>,
+    pub a: Arc<RwLock<dyn Pattern>>,
+    pub b: Arc<RwLock<dyn Pattern>>,
     test_color: Tuples,
     transform: Matrix,
     transform_inverse: Matrix,
 }
 
 impl BlendedPattern {
-    pub fn new(a: Rc<RefCell<dyn Pattern>>, b: Rc<RefCell<dyn Pattern>>) -> Rc<RefCell<BlendedPattern>> {
-        Rc::new(RefCell::new(BlendedPattern {
-            a: Rc::clone(&a),
-            b: Rc::clone(&b),
+    pub fn new(a: Arc<RwLock<dyn Pattern>>, b: Arc<RwLock<dyn Pattern>>) -> Arc<RwLock<BlendedPattern>> {
+        Arc::new(RwLock::new(BlendedPattern {
+            a: Arc::clone(&a),
+            b: Arc::clone(&b),
             test_color: Tuples::color(0.0,0.0,0.0),
             transform: Matrix::new(4),
             transform_inverse: Matrix::new(4)
@@ -378,10 +382,10 @@ impl Pattern for BlendedPattern {
         &self.test_color
     }
     fn color_at(&self, point: &Tuples) -> Tuples {
-        let point_sub_pattern = self.a.borrow().get_transform_inverse() * point;
-        let mut color = self.a.borrow().color_at(&point_sub_pattern).clone();
-        let point_sub_pattern = self.b.borrow().get_transform_inverse() * point;
-        let color_b = self.b.borrow().color_at(&point_sub_pattern).clone();
+        let point_sub_pattern = self.a.read().unwrap().get_transform_inverse() * point;
+        let mut color = self.a.read().unwrap().color_at(&point_sub_pattern).clone();
+        let point_sub_pattern = self.b.read().unwrap().get_transform_inverse() * point;
+        let color_b = self.b.read().unwrap().color_at(&point_sub_pattern).clone();
         color.add(&color_b).scale(0.5)
     }
     fn get_transform(&self) -> &Matrix {
@@ -398,16 +402,16 @@ impl Pattern for BlendedPattern {
 
 #[derive(Debug, Clone)]
 pub struct PerturbedPattern {
-    pub pattern: Rc<RefCell<dyn Pattern>>,
+    pub pattern: Arc<RwLock<dyn Pattern>>,
     test_color: Tuples,
     transform: Matrix,
     transform_inverse: Matrix,
 }
 
 impl PerturbedPattern {
-    pub fn new(pattern: Rc<RefCell<dyn Pattern>>) -> Rc<RefCell<PerturbedPattern>> {
-        Rc::new(RefCell::new(PerturbedPattern {
-            pattern: Rc::clone(&pattern),
+    pub fn new(pattern: Arc<RwLock<dyn Pattern>>) -> Arc<RwLock<PerturbedPattern>> {
+        Arc::new(RwLock::new(PerturbedPattern {
+            pattern: Arc::clone(&pattern),
             test_color: Tuples::color(0.0,0.0,0.0),
             transform: Matrix::new(4),
             transform_inverse: Matrix::new(4)
@@ -424,8 +428,8 @@ impl Pattern for PerturbedPattern {
     }
     fn color_at(&self, point: &Tuples) -> Tuples {
         let perturbed_point = perlin_noise(point);
-        let point_sub_pattern = self.pattern.borrow().get_transform_inverse() * &perturbed_point;
-        self.pattern.borrow().color_at(&point_sub_pattern).clone()
+        let point_sub_pattern = self.pattern.read().unwrap().get_transform_inverse() * &perturbed_point;
+        self.pattern.read().unwrap().color_at(&point_sub_pattern).clone()
     }
     fn get_transform(&self) -> &Matrix {
         &self.transform
@@ -1,11 +1,16 @@
 use crate::Shape;
+use crate::shape;
 use crate::Ray;
+use crate::Bounds;
 use crate::Tuples;
 use crate::Matrix;
 use crate::Material;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
 use std::f64::EPSILON;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
 
 #[derive(Debug, Clone)]
 pub struct Plane {
@@ -13,11 +18,13 @@ pub struct Plane {
     transform: Matrix,
     transform_inverse: Matrix,
     cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
 }
 
 impl Plane {
-    pub fn new() -> Rc<RefCell<Plane>> {
-        Rc::new(RefCell::new(Plane { material: Material::material(), transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true }))
+    pub fn new() -> Arc<RwLock<Plane>> {
+        Arc::new(RwLock::new(Plane { material: Material::material(), transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true, motion: None, parent: None }))
     }
 }
 
@@ -31,12 +38,18 @@ impl Shape for Plane {
         vec![t]
     }
 
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.primitive(s, Box::new(self.clone()))
+    }
+
     fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
         self.transform = transform.clone();
         self.transform_inverse = Matrix::inverse(transform).unwrap();
     }
 
     fn set_material(&mut self, material: &Material) {
+        shape::changed();
         self.material = material.clone();
     }
 
@@ -45,6 +58,7 @@ impl Shape for Plane {
     }
 
     fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
         &mut self.material
     }
 
@@ -61,15 +75,44 @@ impl Shape for Plane {
         Tuples::vector(0.0,1.0, 0.0)
     }
 
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Tuples::point(-f64::INFINITY, 0.0, -f64::INFINITY), Tuples::point(f64::INFINITY, 0.0, f64::INFINITY))
+    }
+
     fn get_type(&self) -> &str {
         "Plane"
     }
 
     fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
         self.cast_shadows = b;
     }
     
     fn cast_shadows(&self) -> bool {
         self.cast_shadows
     }
+
+    
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+    
+        self.motion = Some(motion.clone());
+    
+    }
+
+    
+    fn get_motion(&self) -> Option<&Motion> {
+    
+        self.motion.as_ref()
+    
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
 }
\ No newline at end of file
@@ -5,17 +5,26 @@ use crate::Matrix;
 pub struct Ray {
     origin: Tuples,
     direction: Tuples,
+    time: f64, // moment in the shutter interval the ray was cast at
 }
 
 impl Ray {
     pub fn new(origin: Tuples, direction: Tuples) -> Ray {
-        Ray { origin, direction }
+        Ray { origin, direction, time: 0.0 }
+    }
+
+    pub fn new_at(origin: Tuples, direction: Tuples, time: f64) -> Ray {
+        Ray { origin, direction, time }
     }
 
     pub fn transform(r: &Ray, m: &Matrix) -> Ray {
         let origin = m * r.origin();
         let direction = m * r.direction();
-        Ray {origin, direction }
+        Ray {origin, direction, time: r.time }
+    }
+
+    pub fn at_time(r: &Ray, time: f64) -> Ray {
+        Ray { origin: r.origin, direction: r.direction, time }
     }
 
     pub fn position(r: &Ray, time: f64) -> Tuples {
@@ -33,4 +42,8 @@ impl Ray {
     pub fn direction(&self) -> &Tuples {
         &self.direction
     }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
 }
\ No newline at end of file
@@ -1,42 +1,247 @@
 use crate::*;
-use std::rc::Rc;
-use std::cell::RefCell;
-
-pub fn lighting(material: &Material, object: &Rc<RefCell<dyn Shape>>, point_light: &PointLight, pos: &Tuples, eye_v: &Tuples, normal_v: &Tuples, in_shadow: bool) -> Tuples {
-    let eff_color = material.pattern.borrow().color_at_object(object, pos).clone().multiply(point_light.intensity());
-    let mut ambient = eff_color.clone().scale(material.ambient);
-    if in_shadow {
-        return ambient;
-    }
-    
-    let light_v = Tuples::normalize(&mut point_light.position().clone().subtract(&pos));
-    let light_dot_normal = Tuples::dot(&light_v, &normal_v);
-    if light_dot_normal < 0.0 {
-        return ambient;
-    } else {
-        let diffuse =  eff_color.clone().scale(material.diffuse).scale(light_dot_normal);
-        let reflect_v = Tuples::reflect(&light_v.clone().negate(), &normal_v);
-        let reflect_dot_eye = Tuples::dot(&reflect_v, &eye_v);
-        let specular = if reflect_dot_eye <= 0.0 {
-            Tuples::color(0.0,0.0,0.0)
-        } else {
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// light_transmission is the color that gets past the objects between light and point, white
+// if nothing is in the way and black in shadow, diffuse and specular are averaged over the
+// samples of the light
+pub fn lighting(material: &Material, object: &Arc<RwLock<dyn Shape>>, light: &dyn Light, pos: &Tuples, eye_v: &Tuples, normal_v: &Tuples, light_transmission: &Tuples) -> Tuples {
+    let samples: Vec<(LightSample, Tuples)> = light.samples(pos).into_iter().map(|s| (s, *light_transmission)).collect();
+    lighting_samples(material, object, light, &samples, pos, eye_v, normal_v)
+}
+
+// like lighting, but every sample of the light comes with its own transmission so that
+// shadows follow the direction of the samples
+pub fn lighting_samples(material: &Material, object: &Arc<RwLock<dyn Shape>>, light: &dyn Light, samples: &[(LightSample, Tuples)], pos: &Tuples, eye_v: &Tuples, normal_v: &Tuples) -> Tuples {
+    let color = material.pattern.read().unwrap().color_at_object(object, pos);
+    lighting_color(material, &color, light, samples, eye_v, normal_v)
+}
+
+// lighting_samples for a surface whose color at the point is already known
+pub fn lighting_color(material: &Material, color: &Tuples, light: &dyn Light, samples: &[(LightSample, Tuples)], eye_v: &Tuples, normal_v: &Tuples) -> Tuples {
+    // the ambient term stands in for indirect light and ignores where the light points
+    let mut ambient = color.clone().multiply(light.intensity()).scale(material.ambient);
+    ambient.add(&direct_lighting(material, color, light, samples, eye_v, normal_v))
+}
+
+// diffuse and specular part of lighting_samples for a surface of the given color, without
+// the ambient term
+pub fn direct_lighting(material: &Material, color: &Tuples, light: &dyn Light, samples: &[(LightSample, Tuples)], eye_v: &Tuples, normal_v: &Tuples) -> Tuples {
+    let black = Tuples::color(0.0, 0.0, 0.0);
+    if samples.iter().all(|(_, transmission)| transmission.is_equal(&black)) {
+        return black;
+    }
+
+    let attenuation = light.attenuation();
+    let mut diffuse = Tuples::color(0.0,0.0,0.0);
+    let mut specular = Tuples::color(0.0,0.0,0.0);
+    for (sample, transmission) in samples.iter() {
+        let light_v = sample.direction;
+        let light_dot_normal = Tuples::dot(&light_v, normal_v);
+        if light_dot_normal < 0.0 {
+            continue;
+        }
+        let falloff = attenuation.factor(sample.distance);
+        if material.shading == ShadingModel::Microfacet {
+            let reflected = microfacet::reflectance(material, color, &light_v, eye_v, normal_v);
+            diffuse.add(&reflected.clone().multiply(&sample.intensity).scale(falloff).multiply(transmission));
+            continue;
+        }
+        let eff_color = color.clone().multiply(&sample.intensity);
+        diffuse.add(&eff_color.clone().scale(material.diffuse).scale(light_dot_normal * falloff).multiply(transmission));
+        let reflect_v = Tuples::reflect(&light_v.clone().negate(), normal_v);
+        let reflect_dot_eye = Tuples::dot(&reflect_v, eye_v);
+        if reflect_dot_eye > 0.0 {
             let factor = reflect_dot_eye.powf(material.shininess);
-            point_light.intensity().clone().scale(material.specular).scale(factor)
-        };
-        return ambient.add(&diffuse).add(&specular);
+            specular.add(&sample.intensity.clone().scale(material.specular).scale(factor * falloff).multiply(transmission));
+        }
+    }
+    let weight = 1.0 / samples.len() as f64;
+    diffuse.scale(weight).add(&specular.scale(weight))
+}
+
+const TILE_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    Center, // a single ray through the middle of the pixel
+    Grid, // samples * samples rays on a regular grid
+    Jittered, // one random ray in every cell of the grid
+    Adaptive, // rays through the pixel corners, the jittered grid only if the corners differ
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    pub sampling: Sampling,
+    pub samples: usize, // per axis
+    pub adaptive_threshold: f64, // max difference of a color channel between the corner samples
+    pub seed: u64,
+    pub threads: usize,
+    pub integrator: Arc<dyn Integrator>, // how the color seen along a camera ray is computed
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            sampling: Sampling::Center,
+            samples: 1,
+            adaptive_threshold: 0.1,
+            seed: 0,
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            integrator: Arc::new(Whitted::default()),
+        }
     }
 }
 
+impl RenderSettings {
+    pub fn new(sampling: Sampling, samples: usize) -> RenderSettings {
+        RenderSettings { sampling, samples, ..Default::default() }
+    }
+}
 
+#[derive(Debug, Clone, Copy)]
+struct Tile {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
 
+// renders the image in tiles on all cores, the result is identical to render_single_threaded
 pub fn render(camera: &Camera, world: &World) -> Canvas {
+    render_with_settings(camera, world, &RenderSettings::default())
+}
+
+pub fn render_with_threads(camera: &Camera, world: &World, threads: usize) -> Canvas {
+    render_with_settings(camera, world, &RenderSettings { threads, ..Default::default() })
+}
+
+pub fn render_with_settings(camera: &Camera, world: &World, settings: &RenderSettings) -> Canvas {
+    let mut tiles = vec![];
+    for y in (0..camera.v_size).step_by(TILE_SIZE) {
+        for x in (0..camera.h_size).step_by(TILE_SIZE) {
+            let width = TILE_SIZE.min(camera.h_size - x);
+            let height = TILE_SIZE.min(camera.v_size - y);
+            tiles.push(Tile { x, y, width, height });
+        }
+    }
+
+    // every worker takes the next tile that nobody started yet
+    let next_tile = AtomicUsize::new(0);
+    let rendered: Vec<(Tile, Vec<Tuples>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..settings.threads.max(1)).map(|_| {
+            scope.spawn(|| {
+                let mut done = vec![];
+                while let Some(tile) = tiles.get(next_tile.fetch_add(1, Ordering::Relaxed)) {
+                    done.push((*tile, render_tile(camera, world, settings, tile)));
+                }
+                done
+            })
+        }).collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+    });
+
+    let mut canvas = Canvas::new(camera.h_size, camera.v_size);
+    for (tile, colors) in rendered {
+        for (i, color) in colors.iter().enumerate() {
+            canvas.write_pixel(tile.x + i % tile.width, tile.y + i / tile.width, color);
+        }
+    }
+    canvas
+}
+
+// row by row
+fn render_tile(camera: &Camera, world: &World, settings: &RenderSettings, tile: &Tile) -> Vec<Tuples> {
+    let mut colors = Vec::with_capacity(tile.width * tile.height);
+    for y in tile.y..tile.y + tile.height {
+        for x in tile.x..tile.x + tile.width {
+            let (color, _) = sample_pixel(camera, world, settings, x, y);
+            colors.push(color);
+        }
+    }
+    colors
+}
+
+// positions inside the pixel for grid and jittered sampling, 0..1 from the top left corner
+pub fn sample_offsets(settings: &RenderSettings, x: usize, y: usize) -> Vec<(f64, f64)> {
+    let n = settings.samples.max(1);
+    let cell = 1.0 / n as f64;
+    let mut rng = Rng::for_pixel(settings.seed, x, y);
+    let mut offsets = Vec::with_capacity(n * n);
+    for j in 0..n {
+        for i in 0..n {
+            let (dx, dy) = match settings.sampling {
+                Sampling::Grid | Sampling::Center => (0.5, 0.5),
+                Sampling::Jittered | Sampling::Adaptive => (rng.next_f64(), rng.next_f64()),
+            };
+            offsets.push(((i as f64 + dx) * cell, (j as f64 + dy) * cell));
+        }
+    }
+    offsets
+}
+
+// the color of the pixel and the number of rays that were needed
+pub fn sample_pixel(camera: &Camera, world: &World, settings: &RenderSettings, x: usize, y: usize) -> (Tuples, usize) {
+    // paths, points on the lens and moments in the shutter interval get their own sequences,
+    // independent of the jitter of the offsets
+    let mut rng = Rng::for_pixel(settings.seed.wrapping_add(1), x, y);
+    let mut lens_rng = Rng::for_pixel(settings.seed.wrapping_add(2), x, y);
+    let mut time_rng = Rng::for_pixel(settings.seed.wrapping_add(3), x, y);
+    let mut color_at_offset = |(dx, dy): (f64, f64)| {
+        let ray = if camera.aperture_radius > 0.0 {
+            Camera::ray_for_pixel_lens(camera, x, y, dx, dy, lens_rng.next_f64(), lens_rng.next_f64())
+        } else {
+            Camera::ray_for_pixel_offset(camera, x, y, dx, dy)
+        };
+        let ray = Ray::at_time(&ray, Camera::shutter_time(camera, time_rng.next_f64()));
+        settings.integrator.color_at(world, &ray, &mut rng)
+    };
+    match settings.sampling {
+        Sampling::Center => (color_at_offset((0.5, 0.5)), 1),
+        Sampling::Grid | Sampling::Jittered => {
+            let colors: Vec<Tuples> = sample_offsets(settings, x, y).into_iter().map(&mut color_at_offset).collect();
+            (average(&colors), colors.len())
+        },
+        Sampling::Adaptive => {
+            let corners: Vec<Tuples> = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)].into_iter().map(&mut color_at_offset).collect();
+            if color_spread(&corners) <= settings.adaptive_threshold {
+                return (average(&corners), corners.len());
+            }
+            // the corners are samples of the pixel as well
+            let mut colors = corners;
+            colors.extend(sample_offsets(settings, x, y).into_iter().map(&mut color_at_offset));
+            (average(&colors), colors.len())
+        },
+    }
+}
+
+fn average(colors: &[Tuples]) -> Tuples {
+    let mut sum = Tuples::color(0.0, 0.0, 0.0);
+    for c in colors {
+        sum.add(c);
+    }
+    sum.scale(1.0 / colors.len() as f64)
+}
+
+// largest difference of a single channel between any two colors
+fn color_spread(colors: &[Tuples]) -> f64 {
+    (0..3).map(|i| {
+        let min = colors.iter().map(|c| c.get_at_idx(i)).fold(f64::INFINITY, f64::min);
+        let max = colors.iter().map(|c| c.get_at_idx(i)).fold(-f64::INFINITY, f64::max);
+        max - min
+    }).fold(0.0, f64::max)
+}
+
+pub fn render_single_threaded(camera: &Camera, world: &World) -> Canvas {
+    let settings = RenderSettings::default();
     let mut canvas = Canvas::new(camera.h_size, camera.v_size);
     for y in 0..camera.v_size {
         for x in 0..camera.h_size {
-            let ray = Camera::ray_for_pixel(camera, x, y);
-            let color = World::color_at(world, &ray, MAX_ITERATIONS);
+            let (color, _) = sample_pixel(camera, world, &settings, x, y);
             canvas.write_pixel(x, y, &color);
         }
     }
     canvas
-}
\ No newline at end of file
+}
@@ -0,0 +1,42 @@
+use crate::Tuples;
+
+// small deterministic random number generator (splitmix64), seeded per pixel so that
+// renders are reproducible no matter how the work is split between threads
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn for_pixel(seed: u64, x: usize, y: usize) -> Rng {
+        let mut rng = Rng::new(seed ^ ((y as u64) << 32 | x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        rng.next_u64(); // decorrelate neighbouring pixels
+        rng
+    }
+
+    // for samples that belong to a point in the scene rather than to a pixel
+    pub fn for_point(seed: u64, p: &Tuples) -> Rng {
+        let mut state = seed;
+        for bits in [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()] {
+            state = Rng::new(state ^ bits).next_u64();
+        }
+        Rng::new(state)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
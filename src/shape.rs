@@ -1,14 +1,41 @@
 use crate::Matrix;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 use crate::IntersectionList;
 use crate::Material;
 use crate::Tuples;
 use crate::Ray;
+use crate::Bounds;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-pub trait Shape: Debug {
+// counts changes to any shape, worlds copy their objects again for rendering when it moved on
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// called by every setter of a shape
+pub fn changed() {
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Acquire)
+}
+
+pub trait Shape: Debug + Send + Sync {
     fn intersect_local(&self, r: &Ray) -> Vec<f64>;
+    // t, u and v of each intersection, triangles override this to tell where they were hit
+    fn intersect_local_uv(&self, r: &Ray) -> Vec<(f64, f64, f64)> {
+        self.intersect_local(r).into_iter().map(|t| (t, 0.0, 0.0)).collect()
+    }
+    // composite shapes override this to return intersections with their children
+    fn intersections_local(&self, r: &Ray, s: &Arc<RwLock<dyn Shape>>) -> IntersectionList {
+        IntersectionList::new_with_uv(self.intersect_local_uv(r), s)
+    }
+    // copy of s, which is this shape, that the world traces rays against while rendering
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape>;
     fn set_transform(&mut self, transform: &Matrix);
     fn get_transform(&self) -> &Matrix;
     fn get_transform_inverse(&self) -> &Matrix;
@@ -16,30 +43,118 @@ pub trait Shape: Debug {
     fn get_material(&self) -> &Material;
     fn get_mut_material(&mut self) -> &mut Material;
     fn normal_at_local(&self, p: &Tuples) -> Tuples;
+    // u and v are the barycentric coordinates of the hit, only used by smooth triangles
+    fn normal_at_local_uv(&self, p: &Tuples, _u: f64, _v: f64) -> Tuples {
+        self.normal_at_local(p)
+    }
     fn get_type(&self) -> &str;
     fn set_cast_shadows(&mut self, b: bool);
     fn cast_shadows(&self) -> bool;
+    // a shape with a motion ignores its transform and takes the one for the time of the ray
+    fn set_motion(&mut self, motion: &Motion);
+    fn get_motion(&self) -> Option<&Motion>;
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>>;
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>);
+    // true if s is a child of this shape or of one of its children
+    fn has_descendant(&self, _s: &Arc<RwLock<dyn Shape>>) -> bool {
+        false
+    }
+    // axis aligned box around the shape in object space
+    fn bounds(&self) -> Bounds;
 }
 
 impl dyn Shape {
-    pub fn intersect(s: &Rc<RefCell<dyn Shape>>, r: &Ray) -> IntersectionList {
-        let r = Ray::transform(r, s.borrow().get_transform_inverse());
-        let t = s.borrow().intersect_local(&r);
-        IntersectionList::new(t, &s)
+    // goes through a frozen copy of s, the same way rays are traced through a world
+    pub fn intersect(s: &Arc<RwLock<dyn Shape>>, r: &Ray) -> IntersectionList {
+        let mut xs = vec![];
+        FrozenShape::intersect(&<dyn Shape>::frozen(s), r, &mut xs);
+        IntersectionList::intersections_from_vec(xs)
+    }
+
+    // copy of s that knows the transforms of the groups around it
+    pub fn frozen(s: &Arc<RwLock<dyn Shape>>) -> Arc<FrozenShape> {
+        let parent = s.read().unwrap().get_parent();
+        let mut scope = FreezeScope::inside(parent);
+        s.read().unwrap().freeze(s, &mut scope)
+    }
+
+    // a moving shape covers everything it passes through while the shutter is open
+    pub fn parent_space_bounds(s: &Arc<RwLock<dyn Shape>>) -> Bounds {
+        let shape = s.read().unwrap();
+        match shape.get_motion() {
+            Some(motion) => motion.bounds(&shape.bounds()),
+            None => Bounds::transform(&shape.bounds(), shape.get_transform()),
+        }
+    }
+
+    pub fn normal_at(s: &Arc<RwLock<dyn Shape>>, p: &Tuples) -> Tuples {
+        <dyn Shape>::normal_at_uv(s, p, 0.0, 0.0)
     }
 
-    pub fn normal_at(s: &Rc<RefCell<dyn Shape>>, p: &Tuples) -> Tuples {
-        let inverse_transform = Matrix::inverse(s.borrow().get_transform()).unwrap();
-        let p_object_space = &inverse_transform * p;
-        let n_local = s.borrow().normal_at_local(&p_object_space);
-        let mut n_world = Matrix::transpose(&inverse_transform) * n_local;
+    pub fn normal_at_uv(s: &Arc<RwLock<dyn Shape>>, p: &Tuples, u: f64, v: f64) -> Tuples {
+        <dyn Shape>::normal_at_time(s, p, u, v, 0.0)
+    }
+
+    // normal of a moving shape where it is at the given time
+    pub fn normal_at_time(s: &Arc<RwLock<dyn Shape>>, p: &Tuples, u: f64, v: f64, time: f64) -> Tuples {
+        let p_object_space = <dyn Shape>::world_to_object_at(s, p, time);
+        let n_local = s.read().unwrap().normal_at_local_uv(&p_object_space, u, v);
+        <dyn Shape>::normal_to_world_at(s, &n_local, time)
+    }
+
+    pub fn world_to_object(s: &Arc<RwLock<dyn Shape>>, p: &Tuples) -> Tuples {
+        <dyn Shape>::world_to_object_at(s, p, 0.0)
+    }
+
+    // walks up the parent chain so that points in world space end up in the space of the given shape
+    pub fn world_to_object_at(s: &Arc<RwLock<dyn Shape>>, p: &Tuples, time: f64) -> Tuples {
+        let p = match s.read().unwrap().get_parent() {
+            Some(parent) => <dyn Shape>::world_to_object_at(&parent, p, time),
+            None => *p,
+        };
+        match s.read().unwrap().get_motion() {
+            Some(motion) => motion.inverse_at(time) * p,
+            None => s.read().unwrap().get_transform_inverse() * &p,
+        }
+    }
+
+    pub fn normal_to_world(s: &Arc<RwLock<dyn Shape>>, n: &Tuples) -> Tuples {
+        <dyn Shape>::normal_to_world_at(s, n, 0.0)
+    }
+
+    pub fn normal_to_world_at(s: &Arc<RwLock<dyn Shape>>, n: &Tuples, time: f64) -> Tuples {
+        let mut n_world = match s.read().unwrap().get_motion() {
+            Some(motion) => Matrix::transpose(&motion.inverse_at(time)) * n,
+            None => Matrix::transpose(s.read().unwrap().get_transform_inverse()) * n,
+        };
         n_world.w = 0.0; // remove influence from translation
-        n_world.normalize()
+        let n_world = n_world.normalize();
+        match s.read().unwrap().get_parent() {
+            Some(parent) => <dyn Shape>::normal_to_world_at(&parent, &n_world, time),
+            None => n_world,
+        }
+    }
+
+    // the outermost csg the shape is part of, or the shape itself
+    pub fn medium(s: &Arc<RwLock<dyn Shape>>) -> Arc<RwLock<dyn Shape>> {
+        let mut medium = Arc::clone(s);
+        let mut current = s.read().unwrap().get_parent();
+        while let Some(parent) = current {
+            if parent.read().unwrap().get_type() == "Csg" {
+                medium = Arc::clone(&parent);
+            }
+            current = parent.read().unwrap().get_parent();
+        }
+        medium
     }
 
-    pub fn is_equal(a: &Rc<RefCell<dyn Shape>>, b: &Rc<RefCell<dyn Shape>>) -> bool {
-        a.borrow().get_type() == b.borrow().get_type() &&
-        a.borrow().get_material().is_equal(&b.borrow().get_material()) &&
-        a.borrow().get_transform().is_equal(&b.borrow().get_transform())
+    pub fn includes(a: &Arc<RwLock<dyn Shape>>, b: &Arc<RwLock<dyn Shape>>) -> bool {
+        Arc::ptr_eq(a, b) || a.read().unwrap().has_descendant(b)
     }
-}
\ No newline at end of file
+
+    pub fn is_equal(a: &Arc<RwLock<dyn Shape>>, b: &Arc<RwLock<dyn Shape>>) -> bool {
+        a.read().unwrap().get_type() == b.read().unwrap().get_type() &&
+        a.read().unwrap().get_material().is_equal(&b.read().unwrap().get_material()) &&
+        a.read().unwrap().get_transform().is_equal(&b.read().unwrap().get_transform())
+    }
+}
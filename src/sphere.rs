@@ -1,10 +1,15 @@
 use crate::Shape;
+use crate::shape;
 use crate::Ray;
+use crate::Bounds;
 use crate::Tuples;
 use crate::Matrix;
 use crate::Material;
-use std::rc::Rc;
-use std::cell::RefCell;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
 
 #[derive(Debug, Clone)]
 pub struct Sphere {
@@ -12,18 +17,20 @@ pub struct Sphere {
     transform: Matrix,
     transform_inverse: Matrix,
     cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
 }
 
 impl Sphere {
-    pub fn new() -> Rc<RefCell<Sphere>> {
-        Rc::new(RefCell::new(Sphere { material: Material::material(), transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true }))
+    pub fn new() -> Arc<RwLock<Sphere>> {
+        Arc::new(RwLock::new(Sphere { material: Material::material(), transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true, motion: None, parent: None }))
     }
-    pub fn glass_sphere() -> Rc<RefCell<Sphere>> {
+    pub fn glass_sphere() -> Arc<RwLock<Sphere>> {
         let mut material = Material::material();
         material.transparency = 1.0;
         material.refractive_index = 1.5;
 
-        Rc::new(RefCell::new(Sphere { material, transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true }))
+        Arc::new(RwLock::new(Sphere { material, transform: Matrix::new(4), transform_inverse: Matrix::new(4), cast_shadows: true, motion: None, parent: None }))
     }
 }
 
@@ -46,12 +53,18 @@ impl Shape for Sphere {
         vec![t1, t2]
     }
 
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.primitive(s, Box::new(self.clone()))
+    }
+
     fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
         self.transform = transform.clone();
         self.transform_inverse = Matrix::inverse(transform).unwrap();
     }
 
     fn set_material(&mut self, material: &Material) {
+        shape::changed();
         self.material = material.clone();
     }
 
@@ -60,6 +73,7 @@ impl Shape for Sphere {
     }
 
     fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
         &mut self.material
     }
 
@@ -76,15 +90,38 @@ impl Shape for Sphere {
         Tuples::vector(p_object_space.x - origin.x, p_object_space.y - origin.y, p_object_space.z - origin.z)
     }
 
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Tuples::point(-1.0, -1.0, -1.0), Tuples::point(1.0, 1.0, 1.0))
+    }
+
     fn get_type(&self) -> &str {
         "Sphere"
     }
 
     fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
         self.cast_shadows = b;
     }
 
     fn cast_shadows(&self) -> bool {
         self.cast_shadows
     }
+
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+        self.motion = Some(motion.clone());
+    }
+
+    fn get_motion(&self) -> Option<&Motion> {
+        self.motion.as_ref()
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
 }
\ No newline at end of file
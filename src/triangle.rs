@@ -0,0 +1,162 @@
+use crate::Shape;
+use crate::shape;
+use crate::Ray;
+use crate::Bounds;
+use crate::Tuples;
+use crate::Matrix;
+use crate::Material;
+use crate::Motion;
+use crate::FrozenShape;
+use crate::FreezeScope;
+use std::sync::{Arc, Weak};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    material: Material,
+    transform: Matrix,
+    transform_inverse: Matrix,
+    cast_shadows: bool,
+    motion: Option<Motion>,
+    parent: Option<Weak<RwLock<dyn Shape>>>,
+    pub p1: Tuples,
+    pub p2: Tuples,
+    pub p3: Tuples,
+    pub e1: Tuples,
+    pub e2: Tuples,
+    pub normal: Tuples,
+}
+
+impl Triangle {
+    pub fn new(p1: Tuples, p2: Tuples, p3: Tuples) -> Arc<RwLock<Triangle>> {
+        let e1 = p2.clone().subtract(&p1);
+        let e2 = p3.clone().subtract(&p1);
+        let normal = Tuples::cross(&e2, &e1).normalize();
+        Arc::new(
+            RwLock::new(
+                Triangle {
+                    material: Material::material(),
+                    transform: Matrix::new(4),
+                    transform_inverse: Matrix::new(4),
+                    cast_shadows: true,
+                    motion: None,
+                    parent: None,
+                    p1, p2, p3, e1, e2, normal
+                }
+            )
+        )
+    }
+}
+
+// Möller–Trumbore, returns (t, u, v) of the hit
+pub fn intersect_triangle(p1: &Tuples, e1: &Tuples, e2: &Tuples, r: &Ray) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = Tuples::cross(r.direction(), e2);
+    let det = Tuples::dot(e1, &dir_cross_e2);
+    if det.abs() < crate::utils::EPSILON {
+        return None; // ray is parallel to the triangle
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = r.origin().clone().subtract(p1);
+    let u = f * Tuples::dot(&p1_to_origin, &dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = Tuples::cross(&p1_to_origin, e1);
+    let v = f * Tuples::dot(r.direction(), &origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = f * Tuples::dot(e2, &origin_cross_e1);
+    Some((t, u, v))
+}
+
+impl Shape for Triangle {
+    fn intersect_local(&self, r: &Ray) -> Vec<f64> {
+        match intersect_triangle(&self.p1, &self.e1, &self.e2, r) {
+            Some((t, _, _)) => vec![t],
+            None => vec![],
+        }
+    }
+
+    fn intersect_local_uv(&self, r: &Ray) -> Vec<(f64, f64, f64)> {
+        intersect_triangle(&self.p1, &self.e1, &self.e2, r).into_iter().collect()
+    }
+
+    fn freeze(&self, s: &Arc<RwLock<dyn Shape>>, scope: &mut FreezeScope) -> Arc<FrozenShape> {
+        scope.primitive(s, Box::new(self.clone()))
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        shape::changed();
+        self.transform = transform.clone();
+        self.transform_inverse = Matrix::inverse(transform).unwrap();
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        shape::changed();
+        self.material = material.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn get_mut_material(&mut self) -> &mut Material {
+        shape::changed();
+        &mut self.material
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_transform_inverse(&self) -> &Matrix {
+        &self.transform_inverse
+    }
+
+    fn normal_at_local(&self, _: &Tuples) -> Tuples {
+        self.normal
+    }
+
+    fn bounds(&self) -> Bounds {
+        let mut b = Bounds::empty();
+        b.add_point(&self.p1);
+        b.add_point(&self.p2);
+        b.add_point(&self.p3);
+        b
+    }
+
+    fn get_type(&self) -> &str {
+        "Triangle"
+    }
+
+    fn set_cast_shadows(&mut self, b: bool) {
+        shape::changed();
+        self.cast_shadows = b;
+    }
+
+    fn cast_shadows(&self) -> bool {
+        self.cast_shadows
+    }
+
+    fn set_motion(&mut self, motion: &Motion) {
+        shape::changed();
+        self.motion = Some(motion.clone());
+    }
+
+    fn get_motion(&self) -> Option<&Motion> {
+        self.motion.as_ref()
+    }
+
+    fn get_parent(&self) -> Option<Arc<RwLock<dyn Shape>>> {
+        self.parent.as_ref().and_then(|p| p.upgrade())
+    }
+
+    fn set_parent(&mut self, parent: &Arc<RwLock<dyn Shape>>) {
+        shape::changed();
+        self.parent = Some(Arc::downgrade(parent));
+    }
+}
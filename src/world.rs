@@ -1,125 +1,329 @@
 use crate::lights::point_light;
 use crate::Intersection;
-use crate::PointLight;
+use crate::Light;
+use crate::LightSample;
 use crate::Sphere;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 use crate::Material;
 use crate::Tuples;
 use crate::Shape;
 use crate::Matrix;
 use crate::intersections::IntersectionList;
+use crate::intersections::Media;
 use crate::Ray;
+use crate::FrozenScene;
 use crate::Computations;
 use crate::render;
 use crate::SingleColorPattern;
+use crate::Background;
+use crate::SolidBackground;
+use crate::ImageBackground;
+use crate::ImageLight;
+use crate::Canvas;
+use crate::ShadingModel;
+use crate::microfacet;
+use crate::Rng;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct World {
-    objects: Vec<Rc<RefCell<dyn Shape>>>,
-    point_lights: Vec<PointLight>,
+    objects: Vec<Arc<RwLock<dyn Shape>>>,
+    lights: Vec<Box<dyn Light>>,
+    background: Box<dyn Background>,
+    background_light: bool, // one of the lights samples the background for direct lighting
+    scene: RwLock<Option<Arc<FrozenScene>>>, // copy of the objects that rays are traced through
+}
+
+impl Default for World {
+    fn default() -> Self {
+        World::new()
+    }
 }
 
 impl World {
     pub fn new () -> World {
-        World { objects: vec![], point_lights: vec![] }
-    }
-
-    pub fn is_shadowed(w: &World, point: &Tuples, light: &PointLight) -> bool {
-        let mut vector = light.position().clone().subtract(point);
-        let distance = vector.clone().magnitude();
-        let direction = vector.normalize();
-        let ray = Ray::new(point.clone(),  direction);
-        let is = World::intersect_world(&w, &ray);
-        let hit = IntersectionList::hit_shadow(&is);
-        if let Some(h) = hit {
-            if h.t() < distance {
-                return true;
+        let background = Box::new(SolidBackground::new(Tuples::color(0.0, 0.0, 0.0)));
+        World { objects: vec![], lights: vec![], background, background_light: false, scene: RwLock::new(None) }
+    }
+
+    // only looks at the center of lights with an extent
+    pub fn is_shadowed(w: &World, point: &Tuples, light: &dyn Light) -> bool {
+        let sample = LightSample { direction: light.direction_to_light(point), distance: light.distance_to_light(point), intensity: light.intensity_at(point) };
+        World::is_shadowed_towards(w, point, &sample)
+    }
+
+    pub fn is_shadowed_towards(w: &World, point: &Tuples, sample: &LightSample) -> bool {
+        let black = Tuples::color(0.0, 0.0, 0.0);
+        World::transmission_towards(w, point, sample).is_equal(&black)
+    }
+
+    // color of the light that makes it through everything between point and the light,
+    // transparent objects filter it by their color at each surface the shadow ray crosses
+    pub fn transmission_towards(w: &World, point: &Tuples, sample: &LightSample) -> Tuples {
+        World::transmission_towards_at(w, point, sample, 0.0)
+    }
+
+    // moving objects cast their shadow from where they are at the given time
+    pub fn transmission_towards_at(w: &World, point: &Tuples, sample: &LightSample, time: f64) -> Tuples {
+        let ray = Ray::new_at(*point, sample.direction, time);
+        let is = World::intersect_world(w, &ray);
+        let mut transmission = Tuples::color(1.0, 1.0, 1.0);
+        // media along the way absorb light between one surface and the next
+        let mut media = Media::default();
+        let absorbed = |media: &Media, distance: f64| match media.current() {
+            Some(medium) => medium.read().unwrap().get_material().transmittance(distance),
+            None => Tuples::color(1.0, 1.0, 1.0),
+        };
+        let mut last_t = 0.0;
+        for i in is.xs() {
+            if !i.cast_shadows() {
+                continue;
+            }
+            let t = i.t().min(sample.distance);
+            if t > last_t {
+                transmission.multiply(&absorbed(&media, t - last_t));
+                last_t = t;
+            }
+            if i.t() >= sample.distance {
+                break;
             }
+            let visible = media.is_visible(i);
+            media.cross(i);
+            if i.t() < 0.0 || !visible {
+                continue;
+            }
+            let material = i.object().read().unwrap().get_material().clone();
+            if material.transparency <= 0.0 {
+                return Tuples::color(0.0, 0.0, 0.0);
+            }
+            let surface = material.pattern.read().unwrap().color_at_object_at(i.object(), &Ray::position(&ray, i.t()), time);
+            transmission.multiply(&surface.clone().scale(material.transparency));
+        }
+        // the light itself is inside of a medium
+        if sample.distance.is_finite() && sample.distance > last_t {
+            transmission.multiply(&absorbed(&media, sample.distance - last_t));
+        }
+        transmission
+    }
+
+    // color of the light that reaches the point, white if nothing is in the way, black in
+    // full shadow. Closer samples count more if the light is attenuated
+    pub fn intensity_at(w: &World, point: &Tuples, light: &dyn Light) -> Tuples {
+        World::intensity_at_time(w, point, light, 0.0)
+    }
+
+    // moving objects shadow the light from where they are at the given time
+    pub fn intensity_at_time(w: &World, point: &Tuples, light: &dyn Light, time: f64) -> Tuples {
+        let attenuation = light.attenuation();
+        let samples = light.samples(point);
+        let mut total = 0.0;
+        let mut transmitted = Tuples::color(0.0, 0.0, 0.0);
+        for sample in samples.iter() {
+            let weight = attenuation.factor(sample.distance);
+            total += weight;
+            transmitted.add(&World::transmission_towards_at(w, point, sample, time).scale(weight));
         }
-        return false;
+        if total > 0.0 { transmitted.scale(1.0 / total) } else { Tuples::color(0.0, 0.0, 0.0) }
     }
 
+    // color seen along a ray that starts a path, e.g. from the camera, with up to remaining
+    // reflections and refractions
     pub fn color_at(w: &World, r: &Ray, remaining: i32) -> Tuples {
+        World::trace(w, r, remaining, remaining, None)
+    }
+
+    // color_at for a ray somewhere along a path that was allowed max_depth bounces in total.
+    // channel is the color channel the path is limited to since it went through a dispersive
+    // surface, only that channel of the result is meaningful
+    pub fn trace(w: &World, r: &Ray, remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
         let il = World::intersect_world(w, r);
         let hit = IntersectionList::hit(&il);
         if let Some(i) = hit {
             let comps = Intersection::prep_computations(i, r, &il);
-            return World::shade_hit(w, &comps, remaining);
+            let color = World::shade_hit(w, &comps, remaining, max_depth, channel);
+            World::absorb(color, &comps, r)
         } else {
-            return Tuples::color(0.0,0.0,0.0);
+            w.background.color_at(&r.direction().clone().normalize())
         }
     }
 
-    pub fn shade_hit(w: &World, comps: &Computations, remaining: i32) -> Tuples {
-        let mut color = Tuples::color(0.0,0.0,0.0);
-        for light in w.get_point_lights() {
-            let in_shadow = World::is_shadowed(w, &comps.over_point, light);
-            let surface = render::lighting(comps.object.borrow().get_material(), &comps.object, light,
-            &comps.point, &comps.eye_v, &comps.normal_v, in_shadow);
+    // light on its way back through a medium loses some of its color
+    pub fn absorb(mut color: Tuples, comps: &Computations, r: &Ray) -> Tuples {
+        if let Some(medium) = &comps.medium {
+            let distance = comps.t * r.direction().clone().magnitude();
+            color.multiply(&medium.read().unwrap().get_material().transmittance(distance));
+        }
+        color
+    }
+
+    pub fn shade_hit(w: &World, comps: &Computations, remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
+        // glowing surfaces don't care about lights or shadows
+        let mut color = comps.object.read().unwrap().get_material().emitted();
+        for light in w.get_lights() {
+            // shadow rays start above the surface to avoid acne
+            let samples: Vec<(LightSample, Tuples)> = light.samples(&comps.point).into_iter()
+                .map(|s| (s, World::transmission_towards_at(w, &comps.over_point, &s, comps.time)))
+                .collect();
+            let material = comps.object.read().unwrap().get_material().clone();
+            let surface_color = material.pattern.read().unwrap().color_at_object_at(&comps.object, &comps.point, comps.time);
+            let surface = render::lighting_color(&material, &surface_color, light.as_ref(), &samples, &comps.eye_v, &comps.normal_v);
             color.add(&surface);
         }
 
-        let borrowed = comps.object.borrow();
-        let mat = borrowed.get_material();
-        let mut reflected = World::reflected_color(w, comps, remaining);
-        let mut refracted = World::refracted_color(w, comps, remaining);
-        if mat.reflective > 0.0 && mat.transparency > 0.0 {
+        // the lock is dropped before recursing, the rays may hit this shape again
+        let (transparency, reflective, shading) = {
+            let shape = comps.object.read().unwrap();
+            let mat = shape.get_material();
+            (mat.transparency, mat.reflective, mat.shading)
+        };
+        if transparency > 0.0 {
+            if let Some((n1, n2)) = comps.channel_indices() {
+                return color.add(&World::dispersed_color(w, comps, &n1, &n2, remaining, max_depth, channel));
+            }
+        }
+        let mut reflected = World::reflected_color(w, comps, remaining, max_depth, channel);
+        let mut refracted = World::refracted_color(w, comps, remaining, max_depth, channel);
+        if reflective > 0.0 && transparency > 0.0 {
             let reflectance = Intersection::schlick(comps);
             reflected.scale(reflectance);
             refracted.scale(1.0 - reflectance);
+        } else if shading == ShadingModel::Microfacet {
+            // conductors tint their reflection, dielectrics mostly reflect at grazing angles
+            let material = comps.object.read().unwrap().get_material().clone();
+            let base_color = material.pattern.read().unwrap().color_at_object_at(&comps.object, &comps.point, comps.time);
+            let cos_theta = Tuples::dot(&comps.eye_v, &comps.normal_v);
+            reflected.multiply(&microfacet::fresnel(&microfacet::f0(&material, &base_color), cos_theta));
         }
         color.add(&reflected);
         color.add(&refracted);
         color
     }
 
-    pub fn reflected_color(w: &World, comps: &Computations, remaining: i32) -> Tuples {
+    pub fn reflected_color(w: &World, comps: &Computations, remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
         if remaining == 0 {
             return Tuples::color(0.0,0.0,0.0);
         }
-        let reflective = comps.object.borrow().get_material().reflective;
+        let reflective = comps.object.read().unwrap().get_material().reflective;
         if reflective == 0.0 {
             Tuples::color(0.0,0.0,0.0)
         } else {
-            let reflected_ray = Ray::new(comps.over_point.clone(), comps.reflect_v.clone());
-            let mut reflected_color = World::color_at(w, &reflected_ray, remaining - 1);
+            let reflected_ray = Ray::new_at(comps.over_point, comps.reflect_v, comps.time);
+            let mut reflected_color = World::glossy_color(w, comps, &reflected_ray, &comps.normal_v, remaining - 1, max_depth, channel);
             reflected_color.scale(reflective)
         }
     }
 
-    pub fn refracted_color(w: &World, comps: &Computations, remaining: i32) -> Tuples {
+    // averages rays spread around r, which leaves the surface of comps, in a lobe that gets
+    // wider with the roughness of the surface. A roughness of 0 traces r alone. Only the first
+    // bounce of a path of max_depth bounces takes all samples, deeper ones take a single ray
+    // so that the number of rays doesn't explode
+    pub fn glossy_color(w: &World, comps: &Computations, r: &Ray, side: &Tuples, remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
+        let (roughness, samples) = {
+            let shape = comps.object.read().unwrap();
+            (shape.get_material().roughness, shape.get_material().glossy_samples)
+        };
+        if roughness <= 0.0 {
+            return World::trace(w, r, remaining, max_depth, channel);
+        }
+        let samples = if remaining >= max_depth - 1 { samples.max(1) } else { 1 };
+        let mut rng = Rng::for_point(0, r.origin());
+        let mut color = Tuples::color(0.0, 0.0, 0.0);
+        for _ in 0..samples {
+            let direction = World::perturb(r.direction(), side, roughness, &mut rng);
+            color.add(&World::trace(w, &Ray::new_at(*r.origin(), direction, r.time()), remaining, max_depth, channel));
+        }
+        color.scale(1.0 / samples as f64)
+    }
+
+    // direction moved by up to roughness (as the radius of a sphere around its tip) in a random
+    // direction, kept on the side of the surface that side points to
+    pub fn perturb(direction: &Tuples, side: &Tuples, roughness: f64, rng: &mut Rng) -> Tuples {
+        let mut offset;
+        loop {
+            offset = Tuples::vector(rng.next_f64() * 2.0 - 1.0, rng.next_f64() * 2.0 - 1.0, rng.next_f64() * 2.0 - 1.0);
+            if Tuples::dot(&offset, &offset) <= 1.0 {
+                break;
+            }
+        }
+        let mut perturbed = direction.clone().normalize().add(&offset.scale(roughness)).normalize();
+        let below = Tuples::dot(&perturbed, side);
+        if below < 0.0 {
+            // mirror back to the right side of the surface
+            perturbed.subtract(&side.clone().scale(2.0 * below));
+        }
+        perturbed
+    }
+
+    pub fn refracted_color(w: &World, comps: &Computations, remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
         if remaining == 0 {
             return Tuples::color(0.0,0.0,0.0);
         }
-        let transparency = comps.object.borrow().get_material().transparency;
+        let transparency = comps.object.read().unwrap().get_material().transparency;
         if transparency == 0.0 {
             return Tuples::color(0.0,0.0,0.0);
         }
+        let below = comps.normal_v.clone().negate();
+        match World::refracted_ray(comps) {
+            Some(r_refracted) => World::glossy_color(w, comps, &r_refracted, &below, remaining - 1, max_depth, channel).scale(transparency),
+            None => Tuples::color(0.0,0.0,0.0),
+        }
+    }
+
+    // reflected and refracted color where every channel is refracted with its own index, so
+    // that white light fans out into a spectrum. Each refracted ray carries its channel from
+    // then on, a path that already has one only follows its own channel
+    pub fn dispersed_color(w: &World, comps: &Computations, n1: &[f64; 3], n2: &[f64; 3], remaining: i32, max_depth: i32, channel: Option<usize>) -> Tuples {
+        let (reflective, transparency) = {
+            let shape = comps.object.read().unwrap();
+            (shape.get_material().reflective, shape.get_material().transparency)
+        };
+        let reflected = World::reflected_color(w, comps, remaining, max_depth, channel);
+        let below = comps.normal_v.clone().negate();
+        let channels = match channel {
+            Some(c) => c..c + 1,
+            None => 0..3,
+        };
+        let mut color = Tuples::color(0.0, 0.0, 0.0);
+        for c in channels {
+            let refracted = match World::refracted_ray_with(comps, n1[c], n2[c]) {
+                Some(ray) if remaining > 0 => World::glossy_color(w, comps, &ray, &below, remaining - 1, max_depth, Some(c)),
+                _ => Tuples::color(0.0, 0.0, 0.0),
+            };
+            let (mut reflected_c, mut refracted_c) = (reflected.get_at_idx(c), refracted.get_at_idx(c) * transparency);
+            if reflective > 0.0 {
+                let reflectance = Intersection::schlick_with(comps, n1[c], n2[c]);
+                reflected_c *= reflectance;
+                refracted_c *= 1.0 - reflectance;
+            }
+            color.set_at_idx(c, reflected_c + refracted_c);
+        }
+        color
+    }
+
+    // the ray that continues below the surface, None for total internal reflection
+    pub fn refracted_ray(comps: &Computations) -> Option<Ray> {
+        World::refracted_ray_with(comps, comps.n1, comps.n2)
+    }
+
+    pub fn refracted_ray_with(comps: &Computations, n1: f64, n2: f64) -> Option<Ray> {
         // Snell's law
-        let n_ratio = comps.n1 / comps.n2;
+        let n_ratio = n1 / n2;
         let cos_theta_i = Tuples::dot(&comps.eye_v, &comps.normal_v);
         let sin2_theta_t = n_ratio * n_ratio * (1.0 - cos_theta_i * cos_theta_i);
         
         if sin2_theta_t > 1.0 { // total internal reflection
-            return Tuples::color(0.0,0.0,0.0);
+            return None;
         }
     
         let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
         let direction_refracted = comps.normal_v.clone()
             .scale(n_ratio * cos_theta_i - cos_theta_t)
             .subtract( &comps.eye_v.clone().scale(n_ratio));
-        let r_refracted = Ray::new(comps.under_point.clone(), direction_refracted);
-        World::color_at(w, &r_refracted, remaining - 1).scale(transparency)
+        Some(Ray::new_at(comps.under_point, direction_refracted, comps.time))
     }
 
     pub fn intersect_world(w: &World, r: &Ray) -> IntersectionList {
-        let mut result: IntersectionList = IntersectionList::create_empty();
-        for s in w.get_objects() {
-            let xs = <dyn Shape>::intersect(s, r);
-            result = IntersectionList::merge(result, xs);
-        }
-        result
+        w.get_scene().intersect(r)
     }
 
     pub fn default_world () -> World {
@@ -129,39 +333,87 @@ impl World {
         material.pattern = SingleColorPattern::new(Tuples::color(0.8,1.0,0.6));
         material.diffuse = 0.7;
         material.specular = 0.2;
-        s1.borrow_mut().set_material(&material);
+        s1.write().unwrap().set_material(&material);
         world.add_object(s1);
         
         let s2 = Sphere::new();
         let transform = Matrix::scale(0.5, 0.5, 0.5);
-        s2.borrow_mut().set_transform(&transform);
+        s2.write().unwrap().set_transform(&transform);
         world.add_object(s2);
 
         let p = Tuples::point(-10.0,10.0,-10.0);
         let c = Tuples::color(1.0,1.0,1.0);
         let pl = point_light(&p, &c);
-        world.add_point_light(pl);
+        world.add_light(pl);
 
         world
     }
 
-    pub fn add_object(&mut self, sphere: Rc<RefCell<dyn Shape>>) {
-        let _ = &self.objects.push(sphere);
+    pub fn add_object(&mut self, sphere: Arc<RwLock<dyn Shape>>) {
+        self.objects.push(sphere);
+        *self.scene.get_mut().unwrap() = None;
     }
 
-    pub fn add_point_light(&mut self, point_light: PointLight) {
-        let _ = &self.point_lights.push(point_light);
+    pub fn add_objects(&mut self, objects: Vec<Arc<RwLock<dyn Shape>>>) {
+        self.objects.extend(objects);
+        *self.scene.get_mut().unwrap() = None;
+    }
+
+    pub fn add_light(&mut self, light: impl Light + 'static) {
+        self.lights.push(Box::new(light));
+    }
+
+    pub fn set_background(&mut self, background: impl Background + 'static) {
+        self.background = Box::new(background);
+        self.background_light = false;
+    }
+
+    // the map is both what rays that miss see and a light with the given number of samples
+    pub fn set_environment_map(&mut self, map: Canvas, samples: usize) {
+        self.set_background(ImageBackground::new(map.clone()));
+        self.add_light(ImageLight::new(map, samples));
+        self.background_light = true;
+    }
+
+    pub fn get_background(&self) -> &dyn Background {
+        self.background.as_ref()
     }
 
     pub fn remove_lights(&mut self) {
-        self.point_lights = vec![];
+        self.lights = vec![];
+        self.background_light = false;
     }
 
-    pub fn get_objects(&self) -> &Vec<Rc<RefCell<dyn Shape>>> {
+    // true if the light of the background already arrives through the samples of a light
+    pub fn is_background_lit(&self) -> bool {
+        self.background_light
+    }
+
+    pub fn get_objects(&self) -> &Vec<Arc<RwLock<dyn Shape>>> {
         &self.objects
     }
 
-    pub fn get_point_lights(&self) -> &Vec<PointLight> {
-        &self.point_lights
+    // the objects as they are now, copied again when any shape changed since the last copy
+    pub fn get_scene(&self) -> Arc<FrozenScene> {
+        if let Some(scene) = self.scene.read().unwrap().as_ref().filter(|s| s.is_current()) {
+            return Arc::clone(scene);
+        }
+        let mut scene = self.scene.write().unwrap();
+        match scene.as_ref().filter(|s| s.is_current()) {
+            Some(current) => Arc::clone(current),
+            None => {
+                let frozen = Arc::new(FrozenScene::freeze(&self.objects));
+                *scene = Some(Arc::clone(&frozen));
+                frozen
+            },
+        }
+    }
+
+    pub fn get_lights(&self) -> &Vec<Box<dyn Light>> {
+        &self.lights
+    }
+
+    pub fn get_mut_lights(&mut self) -> &mut Vec<Box<dyn Light>> {
+        &mut self.lights
     }
 }
\ No newline at end of file
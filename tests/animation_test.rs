@@ -0,0 +1,363 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::{Arc, RwLock};
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &AnimationWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+// translation(..), rotation_y(π/n) or identity_matrix
+fn parse_matrix(input: &str) -> Matrix {
+    let rx = cucumber::codegen::Regex::new(r"^(translation|rotation_y)\((.+)\)$").unwrap();
+    if input == "identity_matrix" {
+        return Matrix::new(4);
+    }
+    let m = rx.captures(input).unwrap();
+    match &m[1] {
+        "translation" => {
+            let v = parse_values_f64(&m[2].to_string());
+            Matrix::translate(v[0], v[1], v[2])
+        },
+        _ => Matrix::rotate_y(PI / m[2].trim_start_matches("π/").parse::<f64>().unwrap()),
+    }
+}
+
+fn parse_interpolation(input: &str) -> Interpolation {
+    match input {
+        "linear" => Interpolation::Linear,
+        _ => Interpolation::Smooth,
+    }
+}
+
+// time: value pairs separated by commas, values may contain commas inside of parentheses
+fn parse_keys(input: &str) -> Vec<(f64, String)> {
+    let rx = cucumber::codegen::Regex::new(r"([-\d.]+): (\w+\([^)]*\)|[-\w.]+)").unwrap();
+    rx.captures_iter(input).map(|m| (m[1].parse::<f64>().unwrap(), m[2].to_string())).collect()
+}
+
+fn frame_pattern() -> String {
+    let path = std::env::temp_dir().join(format!("rtxch_animation_{}_##.ppm", std::process::id()));
+    path.to_str().unwrap().to_string()
+}
+
+fn frame_file(frame: usize) -> String {
+    Animation::frame_path(&frame_pattern(), frame)
+}
+
+#[given(regex = r"^track ← (linear|smooth) track with keys (.+)$")]
+fn given_track(world: &mut AnimationWorld, matches: &[String]) {
+    let mut track = Track::new(parse_interpolation(&matches[0]));
+    for (time, value) in parse_keys(&matches[1]) {
+        track = track.with_key(time, value.parse::<f64>().unwrap());
+    }
+    world.track = Some(track);
+}
+
+#[given(regex = r"^track ← linear track of points (.+)$")]
+fn given_point_track(world: &mut AnimationWorld, matches: &[String]) {
+    let mut track = Track::new(Interpolation::Linear);
+    for (time, value) in parse_keys(&matches[0]) {
+        track = track.with_key(time, parse_tuple(world, &value));
+    }
+    world.point_track = Some(track);
+}
+
+#[given(regex = r"^track ← linear track of transforms (.+)$")]
+fn given_transform_track(world: &mut AnimationWorld, matches: &[String]) {
+    let mut track = Track::new(Interpolation::Linear);
+    for (time, value) in parse_keys(&matches[0]) {
+        track = track.with_key(time, parse_matrix(&value));
+    }
+    world.transform_track = Some(track);
+}
+
+#[then(regex = r"^value_at\(track, (.+)\) = (.+)$")]
+fn check_value_at(world: &mut AnimationWorld, matches: &[String]) {
+    let value = world.track.as_ref().unwrap().value_at(matches[0].parse::<f64>().unwrap());
+    assert!((value - matches[1].parse::<f64>().unwrap()).abs() < 0.0001, "{value}");
+}
+
+#[then(regex = r"^point_at\(track, (.+)\) = (.+)$")]
+fn check_point_at(world: &mut AnimationWorld, matches: &[String]) {
+    let value = world.point_track.as_ref().unwrap().value_at(matches[0].parse::<f64>().unwrap());
+    let target = parse_tuple(world, &matches[1]);
+    assert!(value.is_equal(&target), "{:?}", value);
+}
+
+#[then(regex = r"^transform_at\(track, (.+)\) = (.+)$")]
+fn check_transform_at(world: &mut AnimationWorld, matches: &[String]) {
+    let value = world.transform_track.as_ref().unwrap().value_at(matches[0].parse::<f64>().unwrap());
+    assert!(value.is_equal(&parse_matrix(&matches[1])), "{:?}", value);
+}
+
+#[given(regex = r"^animation ← animation\((\d+), (.+)\)$")]
+fn given_animation(world: &mut AnimationWorld, matches: &[String]) {
+    world.animation = Animation::new(matches[0].parse::<usize>().unwrap(), matches[1].parse::<f64>().unwrap());
+}
+
+#[given(regex = r"^animation\.looping ← (true|false)$")]
+fn given_looping(world: &mut AnimationWorld, matches: &[String]) {
+    world.animation.looping = matches[0] == "true";
+}
+
+#[then(regex = r"^time_of_frame\(animation, (\d+)\) = (.+)$")]
+fn check_time_of_frame(world: &mut AnimationWorld, matches: &[String]) {
+    let time = world.animation.time_of_frame(matches[0].parse::<usize>().unwrap());
+    assert!((time - matches[1].parse::<f64>().unwrap()).abs() < 0.0001, "{time}");
+}
+
+#[given(regex = r"^animation ← turntable\((\d+), (point\(.+\)), (point\(.+\)), (vector\(.+\))\)$")]
+fn given_turntable(world: &mut AnimationWorld, matches: &[String]) {
+    let (center, from, up) = (parse_tuple(world, &matches[1]), parse_tuple(world, &matches[2]), parse_tuple(world, &matches[3]));
+    world.animation = Animation::turntable(matches[0].parse::<usize>().unwrap(), &center, &from, &up);
+}
+
+#[given(regex = r"^animation ← fly_through\((\d+), ([\d.]+), (.+)\)$")]
+fn given_fly_through(world: &mut AnimationWorld, matches: &[String]) {
+    let rx = cucumber::codegen::Regex::new(r"point\([^)]*\)").unwrap();
+    let waypoints: Vec<Tuples> = rx.find_iter(&matches[2]).map(|m| parse_tuple(world, m.as_str())).collect();
+    match Animation::fly_through(matches[0].parse::<usize>().unwrap(), matches[1].parse::<f64>().unwrap(),
+        &waypoints, &Tuples::vector(0.0, 1.0, 0.0), Interpolation::Linear) {
+        Ok(animation) => world.animation = animation,
+        Err(e) => world.error = Some(e),
+    }
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut AnimationWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(v[0] as usize, v[1] as usize, v[2]);
+}
+
+#[when(regex = r"^the animation is applied to frame (\d+)$")]
+fn when_apply(world: &mut AnimationWorld, matches: &[String]) {
+    let time = world.animation.time_of_frame(matches[0].parse::<usize>().unwrap());
+    world.animation.apply(&mut world.camera, &mut world.world, time);
+}
+
+#[then(regex = r"^the camera is at (.+)$")]
+fn check_camera_position(world: &mut AnimationWorld, matches: &[String]) {
+    let position = Matrix::inverse(&world.camera.transform).unwrap() * Tuples::point(0.0, 0.0, 0.0);
+    assert!(position.is_equal(&parse_tuple(world, &matches[0])), "{:?}", position);
+}
+
+#[then(regex = r"^the camera looks at (.+)$")]
+fn check_camera_target(world: &mut AnimationWorld, matches: &[String]) {
+    let inverse = Matrix::inverse(&world.camera.transform).unwrap();
+    let position = &inverse * &Tuples::point(0.0, 0.0, 0.0);
+    let forward = &inverse * &Tuples::vector(0.0, 0.0, -1.0);
+    let target = parse_tuple(world, &matches[0]).subtract(&position).normalize();
+    assert!(forward.is_equal(&target), "{:?}", forward);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut AnimationWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut AnimationWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given("s ← sphere() in w")]
+fn given_sphere(world: &mut AnimationWorld) {
+    let sphere: Arc<RwLock<dyn Shape>> = Sphere::new();
+    world.world.add_object(sphere.clone());
+    world.shape = Some(sphere);
+}
+
+#[given("s ← sphere() in a group in w")]
+fn given_sphere_in_group(world: &mut AnimationWorld) {
+    let sphere: Arc<RwLock<dyn Shape>> = Sphere::new();
+    let group = Group::new();
+    Group::add_child(&group, sphere.clone());
+    world.world.add_object(group);
+    world.shape = Some(sphere);
+}
+
+#[given("s ← an emissive sphere in w")]
+fn given_emissive_sphere(world: &mut AnimationWorld) {
+    let sphere: Arc<RwLock<dyn Shape>> = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere.clone());
+    world.shape = Some(sphere);
+}
+
+#[given(regex = r"^s moves from (.+) to (.+) in animation$")]
+fn given_object_track(world: &mut AnimationWorld, matches: &[String]) {
+    let track = Track::new(Interpolation::Linear)
+        .with_key(0.0, parse_matrix(&matches[0]))
+        .with_key(world.animation.duration, parse_matrix(&matches[1]));
+    world.animation.add(Channel::ObjectTransform(world.shape.clone().unwrap(), track), &world.world).unwrap();
+}
+
+#[given(regex = r"^light (\d+) moves from (.+) to (.+) in animation$")]
+fn given_light_track(world: &mut AnimationWorld, matches: &[String]) {
+    let track = Track::new(Interpolation::Linear)
+        .with_key(0.0, parse_tuple(world, &matches[1]))
+        .with_key(world.animation.duration, parse_tuple(world, &matches[2]));
+    world.error = world.animation.add(Channel::LightPosition(matches[0].parse::<usize>().unwrap(), track), &world.world).err();
+}
+
+#[given(regex = r"^light (\d+) follows an empty track in animation$")]
+fn given_empty_light_track(world: &mut AnimationWorld, matches: &[String]) {
+    let track = Track::new(Interpolation::Linear);
+    world.error = world.animation.add(Channel::LightPosition(matches[0].parse::<usize>().unwrap(), track), &world.world).err();
+}
+
+#[given(regex = r"^w has an area light from (point\(.+\)) across (vector\(.+\)) and (vector\(.+\))$")]
+fn given_area_light(world: &mut AnimationWorld, matches: &[String]) {
+    let (corner, u, v) = (parse_tuple(world, &matches[0]), parse_tuple(world, &matches[1]), parse_tuple(world, &matches[2]));
+    world.world.add_light(lights::area_light(&corner, &u, 2, &v, 2, &Tuples::color(1.0, 1.0, 1.0)));
+}
+
+#[given(regex = r"^the color of s changes from (.+) to (.+) in animation$")]
+fn given_color_track(world: &mut AnimationWorld, matches: &[String]) {
+    let track = Track::new(Interpolation::Linear)
+        .with_key(0.0, parse_tuple(world, &matches[0]))
+        .with_key(world.animation.duration, parse_tuple(world, &matches[1]));
+    world.animation.add(Channel::MaterialColor(world.shape.clone().unwrap(), track), &world.world).unwrap();
+}
+
+#[given(regex = r"^the reflective of s changes from (.+) to (.+) in animation$")]
+fn given_reflective_track(world: &mut AnimationWorld, matches: &[String]) {
+    let track = Track::new(Interpolation::Linear)
+        .with_key(0.0, matches[0].parse::<f64>().unwrap())
+        .with_key(world.animation.duration, matches[1].parse::<f64>().unwrap());
+    world.animation.add(Channel::Material(world.shape.clone().unwrap(), MaterialValue::Reflective, track), &world.world).unwrap();
+}
+
+#[then(regex = r"^(adding the channel|creating the fly-through) (succeeds|fails)$")]
+fn check_added(world: &mut AnimationWorld, matches: &[String]) {
+    assert_eq!(world.error.is_none(), matches[1] == "succeeds", "{:?}", world.error);
+}
+
+#[then(regex = r"^animation has (\d+) channels$")]
+fn check_channels(world: &mut AnimationWorld, matches: &[String]) {
+    assert_eq!(world.animation.channels().len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^s\.transform = (.+)$")]
+fn check_transform(world: &mut AnimationWorld, matches: &[String]) {
+    let s = world.shape.as_ref().unwrap().read().unwrap();
+    assert!(s.get_transform().is_equal(&parse_matrix(&matches[0])), "{:?}", s.get_transform());
+}
+
+#[then(regex = r"^a ray from (point\(.+\)) towards (vector\(.+\)) hits s at (.+)$")]
+fn check_hit(world: &mut AnimationWorld, matches: &[String]) {
+    let r = Ray::new(parse_tuple(world, &matches[0]), parse_tuple(world, &matches[1]));
+    let xs = rtxch_lib::World::intersect_world(&world.world, &r);
+    let hit = IntersectionList::hit(&xs).expect("nothing was hit");
+    assert!(Arc::ptr_eq(hit.object(), world.shape.as_ref().unwrap()));
+    assert!((hit.t() - matches[2].parse::<f64>().unwrap()).abs() < 0.0001, "{}", hit.t());
+}
+
+#[then(regex = r"^the direction from (.+) to light (\d+) is (.+)$")]
+fn check_light_direction(world: &mut AnimationWorld, matches: &[String]) {
+    let point = if matches[0] == "the origin" { Tuples::point(0.0, 0.0, 0.0) } else { parse_tuple(world, &matches[0]) };
+    let light = &world.world.get_lights()[matches[1].parse::<usize>().unwrap()];
+    let direction = light.direction_to_light(&point);
+    assert!(direction.is_equal(&parse_tuple(world, &matches[2])), "{:?}", direction);
+}
+
+#[then(regex = r"^the color of s is (.+)$")]
+fn check_color(world: &mut AnimationWorld, matches: &[String]) {
+    let s = world.shape.as_ref().unwrap();
+    let pattern = s.read().unwrap().get_material().pattern.clone();
+    let color = pattern.read().unwrap().color_at_object(s, &Tuples::point(0.0, 0.0, 0.0));
+    assert!(color.is_equal(&parse_tuple(world, &matches[0])), "{:?}", color);
+}
+
+#[then(regex = r"^s\.material\.reflective = (.+)$")]
+fn check_reflective(world: &mut AnimationWorld, matches: &[String]) {
+    let reflective = world.shape.as_ref().unwrap().read().unwrap().get_material().reflective;
+    assert!((reflective - matches[0].parse::<f64>().unwrap()).abs() < 0.0001, "{reflective}");
+}
+
+#[then(regex = r"^frame_path\((.+), (\d+)\) = (.+)$")]
+fn check_frame_path(_world: &mut AnimationWorld, matches: &[String]) {
+    assert_eq!(Animation::frame_path(&matches[0], matches[1].parse::<usize>().unwrap()), matches[2]);
+}
+
+#[when(regex = r"^image ← render_frame\(animation, c, w, (\d+)\)$")]
+fn when_render_frame(world: &mut AnimationWorld, matches: &[String]) {
+    let frame = matches[0].parse::<usize>().unwrap();
+    world.image = world.animation.render_frame(&mut world.camera, &mut world.world, &RenderSettings::default(), frame);
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) is (white|black)$")]
+fn check_pixel(world: &mut AnimationWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let c = world.image.pixel_at(x, y);
+    let target = if matches[2] == "white" { Tuples::color(1.0, 1.0, 1.0) } else { Tuples::color(0.0, 0.0, 0.0) };
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[when(regex = r"^frames (\d+) to (\d+) are rendered to numbered files$")]
+fn when_render_frames(world: &mut AnimationWorld, matches: &[String]) {
+    let (first, last) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    for frame in 0..=last {
+        let _ = std::fs::remove_file(frame_file(frame));
+    }
+    let written = world.animation.render_frames(&mut world.camera, &mut world.world, &RenderSettings::default(), first..last, &frame_pattern()).unwrap();
+    assert_eq!(written, (first..last).map(frame_file).collect::<Vec<String>>());
+}
+
+#[then(regex = r"^the files of frames (\d+) and (\d+) (exist|don't exist)$")]
+fn check_files(_world: &mut AnimationWorld, matches: &[String]) {
+    for frame in [&matches[0], &matches[1]] {
+        let name = frame_file(frame.parse::<usize>().unwrap());
+        let exists = std::path::Path::new(&name).exists();
+        assert_eq!(exists, matches[2] == "exist", "{name}");
+        if exists {
+            let canvas = Canvas::canvas_from_ppm_file(&name).unwrap();
+            assert_eq!(canvas.get_pixels().len(), 25);
+            std::fs::remove_file(&name).unwrap();
+        }
+    }
+}
+
+#[derive(Debug, Default, World)]
+struct AnimationWorld {
+    track: Option<Track<f64>>,
+    point_track: Option<Track<Tuples>>,
+    transform_track: Option<Track<Matrix>>,
+    animation: Animation,
+    camera: Camera,
+    world: rtxch_lib::World,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    tuple: HashMap<String, Tuples>,
+    image: Canvas,
+    error: Option<AnimationError>,
+}
+
+fn main() {
+    futures::executor::block_on(AnimationWorld::run(
+        "tests/features/animation.feature",
+    ));
+}
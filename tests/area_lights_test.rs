@@ -0,0 +1,240 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, parse_values_usize, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &AreaLightsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut AreaLightsWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given("shape ← the first object in w")]
+fn given_first_object(world: &mut AreaLightsWorld) {
+    world.shape = Some(world.world.get_objects()[0].clone());
+}
+
+#[given("shape ← sphere()")]
+fn given_sphere(world: &mut AreaLightsWorld) {
+    world.shape = Some(Sphere::new());
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+#[when(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut AreaLightsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given("eyev ← normalize(eye - pt)")]
+fn given_eyev(world: &mut AreaLightsWorld) {
+    let eye = world.tuple.get("eye").unwrap();
+    let pt = world.tuple.get("pt").unwrap();
+    let eyev = eye.clone().subtract(pt).normalize();
+    world.tuple.insert("eyev".to_string(), eyev);
+}
+
+#[given("normalv ← vector(pt.x, pt.y, pt.z)")]
+fn given_normalv(world: &mut AreaLightsWorld) {
+    let pt = world.tuple.get("pt").unwrap();
+    let normalv = Tuples::vector(pt.x, pt.y, pt.z);
+    world.tuple.insert("normalv".to_string(), normalv);
+}
+
+#[given(regex = r"^light ← point_light\((.+\)), (.+\))\)$")]
+fn given_point_light(world: &mut AreaLightsWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.point_light = Some(lights::point_light(&position, &intensity));
+}
+
+#[given(regex = r"^light ← area_light\((\w+), (\w+), (\d+), (\w+), (\d+), (.+\))\)(?: with (\w+) attenuation)?$")]
+#[when(regex = r"^light ← area_light\((\w+), (\w+), (\d+), (\w+), (\d+), (.+\))\)(?: with (\w+) attenuation)?$")]
+fn given_area_light(world: &mut AreaLightsWorld, matches: &[String]) {
+    let corner = parse_tuple(world, &matches[0]);
+    let v1 = parse_tuple(world, &matches[1]);
+    let v2 = parse_tuple(world, &matches[3]);
+    let steps = parse_values_usize(&format!("{}, {}", matches[2], matches[4]));
+    let intensity = parse_tuple(world, &matches[5]);
+    let light = lights::area_light(&corner, &v1, steps[0], &v2, steps[1], &intensity);
+    let attenuation = match matches[6].as_str() {
+        "inverse_square" => Attenuation::InverseSquare,
+        "linear" => Attenuation::Linear,
+        _ => Attenuation::None,
+    };
+    world.area_light = Some(light.with_attenuation(attenuation));
+}
+
+#[given("light is not jittered")]
+fn given_not_jittered(world: &mut AreaLightsWorld) {
+    world.area_light.as_mut().unwrap().jitter = false;
+}
+
+#[given("w has only light")]
+fn given_only_light(world: &mut AreaLightsWorld) {
+    world.world.remove_lights();
+    world.world.add_light(world.area_light.clone().unwrap());
+}
+
+#[given(regex = r"^shape\.material\.(ambient|diffuse|specular|color) ← (.+)$")]
+fn given_material(world: &mut AreaLightsWorld, matches: &[String]) {
+    let value = matches[1].clone();
+    let color = if matches[0] == "color" { Some(parse_tuple(world, &value)) } else { None };
+    let shape = world.shape.as_ref().unwrap();
+    let mut shape = shape.write().unwrap();
+    let material = shape.get_mut_material();
+    match matches[0].as_str() {
+        "ambient" => material.ambient = value.parse::<f64>().unwrap(),
+        "diffuse" => material.diffuse = value.parse::<f64>().unwrap(),
+        "specular" => material.specular = value.parse::<f64>().unwrap(),
+        _ => material.pattern = SingleColorPattern::new(color.unwrap()),
+    }
+}
+
+#[when("intensity ← intensity_at(w, pt, light)")]
+fn when_intensity_at(world: &mut AreaLightsWorld) {
+    let pt = world.tuple.get("pt").unwrap();
+    let intensity = rtxch_lib::World::intensity_at(&world.world, pt, world.light());
+    assert!(is_equal_f64(intensity.x, intensity.y) && is_equal_f64(intensity.x, intensity.z), "{:?}", intensity);
+    world.intensity = intensity.x;
+}
+
+#[when(regex = r"^result ← lighting\(shape\.material, shape, light, pt, eyev, normalv, (.+)\)$")]
+fn when_lighting(world: &mut AreaLightsWorld, matches: &[String]) {
+    let visibility = matches[0].parse::<f64>().unwrap();
+    let shape = world.shape.as_ref().unwrap();
+    let material = shape.read().unwrap().get_material().clone();
+    let pt = world.tuple.get("pt").unwrap();
+    let eyev = world.tuple.get("eyev").unwrap();
+    let normalv = world.tuple.get("normalv").unwrap();
+    let result = lighting(&material, shape, world.light(), pt, eyev, normalv, &Tuples::color(visibility, visibility, visibility));
+    world.tuple.insert("result".to_string(), result);
+}
+
+#[when(regex = r"^pt ← point_on_light\(light, (\d+), (\d+), (.+), (.+)\)$")]
+fn when_point_on_light(world: &mut AreaLightsWorld, matches: &[String]) {
+    let light = world.area_light.as_ref().unwrap();
+    let (u, v) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let (ju, jv) = (matches[2].parse::<f64>().unwrap(), matches[3].parse::<f64>().unwrap());
+    world.tuple.insert("pt".to_string(), light.point_on_light(u, v, ju, jv));
+}
+
+#[when("samples ← sample_points(light, pt)")]
+fn when_sample_points(world: &mut AreaLightsWorld) {
+    let light = world.area_light.as_ref().unwrap();
+    world.samples = light.sample_points(world.tuple.get("pt").unwrap());
+}
+
+#[then(regex = r"^intensity = (.+)$")]
+fn check_intensity(world: &mut AreaLightsWorld, matches: &[String]) {
+    assert!(is_equal_f64(world.intensity, matches[0].parse::<f64>().unwrap()), "{}", world.intensity);
+}
+
+#[then("intensity is between 0 and 1")]
+fn check_intensity_partial(world: &mut AreaLightsWorld) {
+    assert!(world.intensity > 0.0 && world.intensity < 1.0, "{}", world.intensity);
+}
+
+#[then(regex = r"^(result|pt) = (.+)$")]
+fn check_tuple(world: &mut AreaLightsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^light\.(corner|uvec|vvec|position) = (.+)$")]
+fn check_light_tuple(world: &mut AreaLightsWorld, matches: &[String]) {
+    let light = world.area_light.as_ref().unwrap();
+    let target = parse_tuple(world, &matches[1]);
+    let t = match matches[0].as_str() {
+        "corner" => light.corner(),
+        "uvec" => light.uvec(),
+        "vvec" => light.vvec(),
+        _ => light.position(),
+    };
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^light\.(usteps|vsteps|samples) = (\d+)$")]
+fn check_light_count(world: &mut AreaLightsWorld, matches: &[String]) {
+    let light = world.area_light.as_ref().unwrap();
+    let count = match matches[0].as_str() {
+        "usteps" => light.usteps(),
+        "vsteps" => light.vsteps(),
+        _ => light.sample_count(),
+    };
+    assert_eq!(count, matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^samples has (\d+) points$")]
+fn check_sample_count(world: &mut AreaLightsWorld, matches: &[String]) {
+    assert_eq!(world.samples.len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then("every sample lies in its own cell of light")]
+fn check_sample_cells(world: &mut AreaLightsWorld) {
+    let light = world.area_light.as_ref().unwrap();
+    for (k, p) in world.samples.iter().enumerate() {
+        let (u, v) = (k % light.usteps(), k / light.usteps());
+        let lower = light.point_on_light(u, v, 0.0, 0.0);
+        let upper = light.point_on_light(u, v, 1.0, 1.0);
+        for i in 0..3 {
+            let (a, b) = (lower.get_at_idx(i).min(upper.get_at_idx(i)), lower.get_at_idx(i).max(upper.get_at_idx(i)));
+            assert!(a <= p.get_at_idx(i) && p.get_at_idx(i) <= b, "{:?} not in cell {u}, {v}", p);
+        }
+    }
+    let center = light.point_on_light(0, 0, 0.5, 0.5);
+    assert!(!world.samples[0].is_equal(&center));
+}
+
+#[then("sample_points(light, pt) = samples")]
+fn check_samples_reproducible(world: &mut AreaLightsWorld) {
+    let light = world.area_light.as_ref().unwrap();
+    assert_eq!(light.sample_points(world.tuple.get("pt").unwrap()), world.samples);
+}
+
+#[derive(Debug, Default, World)]
+struct AreaLightsWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    point_light: Option<PointLight>,
+    area_light: Option<AreaLight>,
+    samples: Vec<Tuples>,
+    intensity: f64,
+}
+
+impl AreaLightsWorld {
+    fn light(&self) -> &dyn Light {
+        match (&self.point_light, &self.area_light) {
+            (Some(light), None) => light,
+            (None, Some(light)) => light,
+            _ => panic!("expected exactly one light"),
+        }
+    }
+}
+
+fn main() {
+    futures::executor::block_on(AreaLightsWorld::run(
+        "tests/features/area_lights.feature",
+    ));
+}
@@ -0,0 +1,139 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World, gherkin::Step};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &BackgroundWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut BackgroundWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut BackgroundWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given(regex = r"^(\w+) ← a file containing:$")]
+fn file_containing(world: &mut BackgroundWorld, step: &Step, matches: &[String]) {
+    let content = step.docstring.as_ref().unwrap();
+    // the docstring starts with the line break after the opening quotes
+    world.file.insert(matches[0].clone(), content.strip_prefix('\n').unwrap_or(content).to_string());
+}
+
+#[given(regex = r"^w\.background ← solid\((.+)\)$")]
+fn given_solid(world: &mut BackgroundWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.world.set_background(SolidBackground::new(color));
+}
+
+#[given(regex = r"^w\.background ← gradient\((color\(.+\)), (color\(.+\))\)$")]
+fn given_gradient(world: &mut BackgroundWorld, matches: &[String]) {
+    let bottom = parse_tuple(world, &matches[0]);
+    let top = parse_tuple(world, &matches[1]);
+    world.world.set_background(GradientBackground::new(bottom, top));
+}
+
+#[given(regex = r"^w\.background ← image\((\w+)\)$")]
+fn given_image(world: &mut BackgroundWorld, matches: &[String]) {
+    let image = Canvas::canvas_from_ppm(world.file.get(&matches[0]).unwrap()).unwrap();
+    world.world.set_background(ImageBackground::new(image));
+}
+
+#[given(regex = r"^shape ← (plane|glass_sphere)\(\)$")]
+fn given_shape(world: &mut BackgroundWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = match matches[0].as_str() {
+        "plane" => Plane::new(),
+        _ => Sphere::glass_sphere(),
+    };
+    world.shape = Some(shape);
+}
+
+#[given(regex = r"^shape\.material\.reflective ← (.+)$")]
+fn given_reflective(world: &mut BackgroundWorld, matches: &[String]) {
+    let shape = world.shape.as_ref().unwrap();
+    shape.write().unwrap().get_mut_material().reflective = matches[0].parse::<f64>().unwrap();
+}
+
+#[given(regex = r"^shape\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut BackgroundWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut BackgroundWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut BackgroundWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut BackgroundWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut BackgroundWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when("(u, v) ← direction_to_uv(d)")]
+fn when_direction_to_uv(world: &mut BackgroundWorld) {
+    world.uv = ImageBackground::direction_to_uv(world.tuple.get("d").unwrap());
+}
+
+#[then(regex = r"^c = (.+)$")]
+fn check_color(world: &mut BackgroundWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^(u|v) = (.+)$")]
+fn check_uv(world: &mut BackgroundWorld, matches: &[String]) {
+    let value = if matches[0] == "u" { world.uv.0 } else { world.uv.1 };
+    assert!(is_equal_f64(value, matches[1].parse::<f64>().unwrap()), "{value}");
+}
+
+#[derive(Debug, Default, World)]
+struct BackgroundWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    file: HashMap<String, String>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    ray: Option<Ray>,
+    uv: (f64, f64),
+}
+
+fn main() {
+    futures::executor::block_on(BackgroundWorld::run(
+        "tests/features/background.feature",
+    ));
+}
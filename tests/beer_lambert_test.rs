@@ -0,0 +1,220 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &BeerLambertWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_ray(world: &BeerLambertWorld, input: &str) -> Ray {
+    let rx = cucumber::codegen::Regex::new(r"^ray\((.+\)), (.+\))\)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    Ray::new(parse_tuple(world, &m[1]), parse_tuple(world, &m[2]))
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut BeerLambertWorld) {
+    world.material = Some(Material::material());
+}
+
+#[given(regex = r"^m\.absorption ← (.+)$")]
+fn given_absorption(world: &mut BeerLambertWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.material.as_mut().unwrap().absorption = color;
+}
+
+#[given(regex = r"^m\.absorption_density ← (.+)$")]
+fn given_absorption_density(world: &mut BeerLambertWorld, matches: &[String]) {
+    world.material.as_mut().unwrap().absorption_density = matches[0].parse::<f64>().unwrap();
+}
+
+#[then(regex = r"^m\.absorption = (.+)$")]
+fn check_absorption(world: &mut BeerLambertWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    assert!(world.material.as_ref().unwrap().absorption.is_equal(&target));
+}
+
+#[then(regex = r"^m\.absorption_density = (.+)$")]
+fn check_absorption_density(world: &mut BeerLambertWorld, matches: &[String]) {
+    assert!(is_equal_f64(world.material.as_ref().unwrap().absorption_density, matches[0].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^transmittance\(m, (.+)\) = (.+)$")]
+fn check_transmittance(world: &mut BeerLambertWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.material.as_ref().unwrap().transmittance(matches[0].parse::<f64>().unwrap());
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut BeerLambertWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("the emissive sphere below the slab")]
+fn given_emissive_sphere(world: &mut BeerLambertWorld) {
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&Matrix::translate(0.0, -5.0, 0.0));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+#[given("shape ← glass_sphere()")]
+fn given_glass_sphere(world: &mut BeerLambertWorld) {
+    let shape: Arc<RwLock<dyn Shape>> = Sphere::glass_sphere();
+    world.shape.insert("shape".to_string(), shape);
+}
+
+// a wide gray glass slab centered on the origin
+#[given(regex = r"^slab ← absorbing_slab\((.+)\)$")]
+fn given_slab(world: &mut BeerLambertWorld, matches: &[String]) {
+    let thickness = matches[0].parse::<f64>().unwrap();
+    let slab: Arc<RwLock<dyn Shape>> = Cube::new();
+    {
+        let mut s = slab.write().unwrap();
+        s.set_transform(&Matrix::scale(10.0, thickness / 2.0, 10.0));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        material.absorption = Tuples::color(0.5, 0.5, 0.5);
+        material.absorption_density = 1.0;
+    }
+    world.shape.insert("slab".to_string(), slab);
+}
+
+#[given(regex = r"^(\w+)\.material\.absorption ← (.+)$")]
+fn given_shape_absorption(world: &mut BeerLambertWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[1]);
+    world.shape.get(&matches[0]).unwrap().write().unwrap().get_mut_material().absorption = color;
+}
+
+#[given(regex = r"^(\w+)\.material\.absorption_density ← (.+)$")]
+fn given_shape_absorption_density(world: &mut BeerLambertWorld, matches: &[String]) {
+    let density = matches[1].parse::<f64>().unwrap();
+    world.shape.get(&matches[0]).unwrap().write().unwrap().get_mut_material().absorption_density = density;
+}
+
+#[given(regex = r"^(\w+) is added to w$")]
+fn given_shape_added(world: &mut BeerLambertWorld, matches: &[String]) {
+    let shape = world.shape.get(&matches[0]).unwrap().clone();
+    world.world.add_object(shape);
+}
+
+#[given(regex = r"^light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut BeerLambertWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.light = Some(lights::point_light(&position, &intensity));
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut BeerLambertWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^r ← (ray\(.+\))$")]
+fn given_ray(world: &mut BeerLambertWorld, matches: &[String]) {
+    world.ray = Some(parse_ray(world, &matches[0]));
+}
+
+#[given(regex = r"^xs ← intersections\((.+):shape, (.+):shape\)$")]
+fn given_intersections(world: &mut BeerLambertWorld, matches: &[String]) {
+    let shape = world.shape.get("shape").unwrap().clone();
+    let t: Vec<f64> = matches.iter().map(|t| t.parse::<f64>().unwrap()).collect();
+    world.xs = Some(IntersectionList::new(t, &shape));
+}
+
+#[given(regex = r"^pt ← path_tracer\((\d+)\)$")]
+fn given_path_tracer(world: &mut BeerLambertWorld, matches: &[String]) {
+    world.tracer = PathTracer::new(matches[0].parse::<usize>().unwrap());
+}
+
+#[when(regex = r"^comps ← prepare_computations\(xs\[(\d+)\], r, xs\)$")]
+fn when_prepare_computations(world: &mut BeerLambertWorld, matches: &[String]) {
+    let xs = world.xs.as_ref().unwrap();
+    let i = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    world.comps = Some(Intersection::prep_computations(i, world.ray.as_ref().unwrap(), xs));
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut BeerLambertWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^c ← the path along (ray\(.+\))$")]
+fn when_path(world: &mut BeerLambertWorld, matches: &[String]) {
+    let ray = parse_ray(world, &matches[0]);
+    let c = world.tracer.color_at(&world.world, &ray, &mut Rng::new(0));
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when("t ← transmission_towards(w, p, light)")]
+fn when_transmission(world: &mut BeerLambertWorld) {
+    let p = *world.tuple.get("p").unwrap();
+    let sample = world.light.as_ref().unwrap().samples(&p)[0];
+    let t = rtxch_lib::World::transmission_towards(&world.world, &p, &sample);
+    world.tuple.insert("t".to_string(), t);
+}
+
+#[then(regex = r"^comps\.medium is (\w+)$")]
+fn check_medium(world: &mut BeerLambertWorld, matches: &[String]) {
+    let medium = world.comps.as_ref().unwrap().medium.as_ref();
+    match matches[0].as_str() {
+        "nothing" => assert!(medium.is_none()),
+        name => assert!(Arc::ptr_eq(medium.unwrap(), world.shape.get(name).unwrap())),
+    }
+}
+
+#[then(regex = r"^(c|t) = (.+)$")]
+fn check_color(world: &mut BeerLambertWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let c = world.tuple.get(&matches[0]).unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct BeerLambertWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    material: Option<Material>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    light: Option<PointLight>,
+    ray: Option<Ray>,
+    xs: Option<IntersectionList>,
+    comps: Option<Computations>,
+    tracer: PathTracer,
+}
+
+fn main() {
+    futures::executor::block_on(BeerLambertWorld::run(
+        "tests/features/beer_lambert.feature",
+    ));
+}
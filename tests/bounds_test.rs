@@ -0,0 +1,292 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..) or the name of a stored tuple
+fn parse_tuple(world: &BoundsWorld, input: &str) -> Tuples {
+    if let Some(v) = input.strip_prefix("point(").and_then(|s| s.strip_suffix(')')) {
+        let v = parse_values_f64(&v.to_string());
+        Tuples::point(v[0], v[1], v[2])
+    } else if let Some(v) = input.strip_prefix("vector(").and_then(|s| s.strip_suffix(')')) {
+        let v = parse_values_f64(&v.to_string());
+        Tuples::vector(v[0], v[1], v[2])
+    } else {
+        *world.tuple.get(input).unwrap()
+    }
+}
+
+// a product of transformations like translation(1, 2, 3) * scaling(2, 2, 2)
+fn parse_transform(input: &str) -> Matrix {
+    let rx = cucumber::codegen::Regex::new(r"^(translation|scaling|rotation_x|rotation_y)\((.+)\)$").unwrap();
+    input.split(" * ").fold(Matrix::new(4), |acc, part| {
+        let m = rx.captures(part).unwrap();
+        let transform = match &m[1] {
+            "translation" => {
+                let v = parse_values_f64(&m[2].to_string());
+                Matrix::translate(v[0], v[1], v[2])
+            },
+            "scaling" => {
+                let v = parse_values_f64(&m[2].to_string());
+                Matrix::scale(v[0], v[1], v[2])
+            },
+            "rotation_x" => Matrix::rotate_x(PI / m[2].trim_start_matches("π/").parse::<f64>().unwrap()),
+            "rotation_y" => Matrix::rotate_y(PI / m[2].trim_start_matches("π/").parse::<f64>().unwrap()),
+            _ => panic!("transform not implemented {}", &m[1]),
+        };
+        acc * transform
+    })
+}
+
+fn check_f64(a: f64, b: f64) -> bool {
+    a == b || is_equal_f64(a, b)
+}
+
+#[given(regex = r"^(\w+) ← bounding_box\(empty\)$")]
+fn given_empty_box(world: &mut BoundsWorld, matches: &[String]) {
+    world.bounds.insert(matches[0].clone(), Bounds::empty());
+}
+
+#[given(regex = r"^(\w+) ← bounding_box\(min=point\((.+)\), max=point\((.+)\)\)$")]
+fn given_box(world: &mut BoundsWorld, matches: &[String]) {
+    let min = parse_values_f64(&matches[1]);
+    let max = parse_values_f64(&matches[2]);
+    let b = Bounds::new(Tuples::point(min[0], min[1], min[2]), Tuples::point(max[0], max[1], max[2]));
+    world.bounds.insert(matches[0].clone(), b);
+}
+
+#[given(regex = r"^(\w+) ← (point|vector)\((.+)\)$")]
+fn given_tuple(world: &mut BoundsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &format!("{}({})", matches[1], matches[2]));
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^(\w+) ← normalize\((.+)\)$")]
+fn given_normalized(world: &mut BoundsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]).normalize();
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^(\w+) ← ray\((point\(.+?\)|\w+), (.+)\)$")]
+fn given_ray(world: &mut BoundsWorld, matches: &[String]) {
+    let o = parse_tuple(world, &matches[1]);
+    let d = parse_tuple(world, &matches[2]);
+    world.ray.insert(matches[0].clone(), Ray::new(o, d));
+}
+
+#[given(regex = r"^matrix ← (.+)$")]
+fn given_matrix(world: &mut BoundsWorld, matches: &[String]) {
+    world.matrix = Some(parse_transform(&matches[0]));
+}
+
+#[given(regex = r"^(\w+) ← (sphere|plane|cube|cylinder|cone|triangle|group|csg|world)\((.*)\)$")]
+fn given_shape(world: &mut BoundsWorld, matches: &[String]) {
+    let t = matches[0].clone();
+    let args: Vec<&str> = matches[2].split(", ").collect();
+    let shape: Arc<RwLock<dyn Shape>> = match matches[1].as_str() {
+        "sphere" => Sphere::new(),
+        "plane" => Plane::new(),
+        "cube" => Cube::new(),
+        "cylinder" if matches[2].is_empty() => Cylinder::new(),
+        "cylinder" => {
+            let v = parse_values_f64(&matches[2]);
+            Cylinder::new_limited(v[0], v[1], false)
+        },
+        "cone" if matches[2].is_empty() => Cone::new(),
+        "cone" => {
+            let v = parse_values_f64(&matches[2]);
+            Cone::new_limited(v[0], v[1], false)
+        },
+        "triangle" => {
+            let p: Vec<Tuples> = args.iter().map(|a| parse_tuple(world, a)).collect();
+            Triangle::new(p[0], p[1], p[2])
+        },
+        "group" => {
+            let g = Group::new();
+            world.group.insert(t.clone(), g.clone());
+            g
+        },
+        "csg" => {
+            let op = match args[0].trim_matches('"') {
+                "union" => CsgOperation::Union,
+                "intersection" => CsgOperation::Intersection,
+                "difference" => CsgOperation::Difference,
+                op => panic!("unknown operation {op}"),
+            };
+            let left = world.shape.get(args[1]).unwrap().clone();
+            let right = world.shape.get(args[2]).unwrap().clone();
+            Csg::new(op, left, right)
+        },
+        "world" => {
+            world.world = Some(rtxch_lib::World::new());
+            return;
+        },
+        _ => panic!("{} not implemented", matches[1]),
+    };
+    world.shape.insert(t, shape);
+}
+
+#[given(regex = r"^set_transform\((\w+), (.+)\)$")]
+#[when(regex = r"^set_transform\((\w+), (.+)\)$")]
+fn set_transform(world: &mut BoundsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    s.write().unwrap().set_transform(&parse_transform(&matches[1]));
+}
+
+#[given(regex = r"^add_child\((\w+), (\w+)\)$")]
+#[when(regex = r"^add_child\((\w+), (\w+)\)$")]
+fn add_child(world: &mut BoundsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let s = world.shape.get(&matches[1]).unwrap();
+    Group::add_child(g, s.clone());
+}
+
+#[given(regex = r"^(\d+) spheres in a row are added to (\w+)$")]
+fn add_spheres(world: &mut BoundsWorld, matches: &[String]) {
+    let count = matches[0].parse::<usize>().unwrap();
+    let spheres: Vec<Arc<RwLock<dyn Shape>>> = (0..count).map(|i| {
+        let s = Sphere::new();
+        s.write().unwrap().set_transform(&Matrix::translate(3.0 * i as f64, 0.0, 0.0));
+        let s: Arc<RwLock<dyn Shape>> = s;
+        s
+    }).collect();
+    if matches[1] == "w" {
+        world.world.as_mut().unwrap().add_objects(spheres);
+    } else {
+        Group::add_children(world.group.get(&matches[1]).unwrap(), spheres);
+    }
+}
+
+#[given(regex = r"^(\w+) is added to (\w+)$")]
+#[when(regex = r"^(\w+) is added to (\w+)$")]
+fn add_to(world: &mut BoundsWorld, matches: &[String]) {
+    if matches[1] == "w" {
+        let s = world.shape.get(&matches[0]).unwrap().clone();
+        world.world.as_mut().unwrap().add_object(s);
+    } else if let Some(p) = world.tuple.get(&matches[0]) {
+        world.bounds.get_mut(&matches[1]).unwrap().add_point(p);
+    } else {
+        let b = *world.bounds.get(&matches[0]).unwrap();
+        world.bounds.get_mut(&matches[1]).unwrap().add_bounds(&b);
+    }
+}
+
+#[when(regex = r"^(\w+) ← (bounds_of|parent_space_bounds_of)\((\w+)\)$")]
+fn when_bounds_of(world: &mut BoundsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[2]).unwrap();
+    let b = match matches[1].as_str() {
+        "bounds_of" => s.read().unwrap().bounds(),
+        _ => <dyn Shape>::parent_space_bounds(s),
+    };
+    world.bounds.insert(matches[0].clone(), b);
+}
+
+#[when(regex = r"^(\w+) ← transform\((\w+), matrix\)$")]
+fn when_transform(world: &mut BoundsWorld, matches: &[String]) {
+    let b = world.bounds.get(&matches[1]).unwrap();
+    let out = Bounds::transform(b, world.matrix.as_ref().unwrap());
+    world.bounds.insert(matches[0].clone(), out);
+}
+
+#[when(regex = r"^xs ← (intersect|intersect_world)\((\w+), r\)$")]
+fn when_intersect(world: &mut BoundsWorld, matches: &[String]) {
+    let r = world.ray.get("r").unwrap();
+    let xs = match matches[0].as_str() {
+        "intersect" => <dyn Shape>::intersect(world.shape.get(&matches[1]).unwrap(), r),
+        _ => rtxch_lib::World::intersect_world(world.world.as_ref().unwrap(), r),
+    };
+    world.xs = Some(xs);
+}
+
+#[then(regex = r"^(\w+) is (empty|infinite)$")]
+fn check_box_kind(world: &mut BoundsWorld, matches: &[String]) {
+    let b = match world.bounds.get(&matches[0]) {
+        Some(b) => *b,
+        None => world.shape.get(&matches[0]).unwrap().read().unwrap().bounds(),
+    };
+    match matches[1].as_str() {
+        "empty" => assert!(b.is_empty()),
+        _ => assert!(b.is_infinite()),
+    }
+}
+
+#[then(regex = r"^(\w+)\.(min|max) = point\((.+)\)$")]
+fn check_box_corner(world: &mut BoundsWorld, matches: &[String]) {
+    let b = world.bounds.get(&matches[0]).unwrap();
+    let corner = if matches[1] == "min" { b.min } else { b.max };
+    let v = parse_values_f64(&matches[2]);
+    assert!(check_f64(corner.x, v[0]) && check_f64(corner.y, v[1]) && check_f64(corner.z, v[2]), "{:?} != {:?}", corner, v);
+}
+
+#[then(regex = r"^box contains p is (true|false)$")]
+fn check_contains(world: &mut BoundsWorld, matches: &[String]) {
+    let b = world.bounds.get("box").unwrap();
+    let p = world.tuple.get("p").unwrap();
+    assert_eq!(b.contains_point(p), matches[0].parse::<bool>().unwrap());
+}
+
+#[then(regex = r"^intersects\(box, r\) is (true|false)$")]
+fn check_intersects(world: &mut BoundsWorld, matches: &[String]) {
+    let b = world.bounds.get("box").unwrap();
+    let r = world.ray.get("r").unwrap();
+    assert_eq!(b.intersects(r), matches[0].parse::<bool>().unwrap());
+}
+
+#[then(regex = r"^the bvh of (\w+) has (\d+) leaves or more$")]
+fn check_leaves(world: &mut BoundsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    assert!(g.read().unwrap().bvh().leaf_count() >= matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^the bvh of (\w+) is at least (\d+) levels deep$")]
+fn check_depth(world: &mut BoundsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    assert!(g.read().unwrap().bvh().depth() >= matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^the bvh of (\w+) has (\d+) unbounded shapes$")]
+fn check_unbounded(world: &mut BoundsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    assert_eq!(g.read().unwrap().bvh().unbounded_count(), matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^xs\.count = (\d+)$")]
+fn check_count(world: &mut BoundsWorld, matches: &[String]) {
+    assert_eq!(world.xs.as_ref().unwrap().count(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then("xs are sorted by t")]
+fn check_sorted(world: &mut BoundsWorld) {
+    let xs = world.xs.as_ref().unwrap().xs();
+    assert!(xs.windows(2).all(|w| w[0].t() <= w[1].t()));
+}
+
+#[then(regex = r"^xs\[(\d+)\]\.t = (.+)$")]
+fn check_t(world: &mut BoundsWorld, matches: &[String]) {
+    let xs = world.xs.as_ref().unwrap().xs();
+    let i = &xs[matches[0].parse::<usize>().unwrap()];
+    assert!(is_equal_f64(i.t(), matches[1].parse::<f64>().unwrap()));
+}
+
+#[derive(Debug, Default, World)]
+struct BoundsWorld {
+    bounds: HashMap<String, Bounds>,
+    tuple: HashMap<String, Tuples>,
+    ray: HashMap<String, Ray>,
+    matrix: Option<Matrix>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    group: HashMap<String, Arc<RwLock<Group>>>,
+    world: Option<rtxch_lib::World>,
+    xs: Option<IntersectionList>,
+}
+
+fn main() {
+    futures::executor::block_on(BoundsWorld::run(
+        "tests/features/bounds.feature",
+    ));
+}
@@ -3,7 +3,7 @@ extern crate rtxch_lib;
 use std::collections::HashMap;
 use std::f64::consts::PI;
 use cucumber::{given, when, then, World};
-use render::render;
+use render::{render, render_with_threads, render_single_threaded};
 use rtxch_lib::*;
 use rtxch_lib::utils::*;
 
@@ -22,6 +22,14 @@ fn ray_for_pixel(world: &mut CameraWorld, matches: &[String]) {
     world.ray.insert("r".to_string(), ray);
 }
 
+#[when(regex = r"^r ← ray_for_pixel_offset\(c, (\d+), (\d+), (.+), (.+)\)$")]
+fn ray_for_pixel_offset(world: &mut CameraWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let (dx, dy) = (matches[2].parse::<f64>().unwrap(), matches[3].parse::<f64>().unwrap());
+    let ray = Camera::ray_for_pixel_offset(&world.camera, x, y, dx, dy);
+    world.ray.insert("r".to_string(), ray);
+}
+
 #[then(regex = r"(.+)\.(origin|direction) = (.+)")]
 fn check_prop(world: &mut CameraWorld, matches: &[String]) {
     let r = world.ray.get(&matches[0]).unwrap();
@@ -59,6 +67,24 @@ fn render_image(world: &mut CameraWorld) {
     world.image = image;
 }
 
+#[when(regex = r"^image ← render_with_threads\(c, w, (\d+)\)$")]
+fn render_image_threads(world: &mut CameraWorld, matches: &[String]) {
+    world.image = render_with_threads(&world.camera, &world.world, matches[0].parse::<usize>().unwrap());
+}
+
+#[when("expected ← render_single_threaded(c, w)")]
+fn render_image_single_threaded(world: &mut CameraWorld) {
+    world.expected = render_single_threaded(&world.camera, &world.world);
+}
+
+#[then("every pixel of image is identical to expected")]
+fn check_identical(world: &mut CameraWorld) {
+    assert_eq!(world.image.width, world.expected.width);
+    assert_eq!(world.image.height, world.expected.height);
+    // bit for bit, no epsilon
+    assert!(world.image.get_pixels() == world.expected.get_pixels());
+}
+
 #[then("pixel_at(image, 5, 5) = color(0.38066, 0.47583, 0.2855)")]
 fn pixel_at(world: &mut CameraWorld) {
     let pixel = world.image.pixel_at(5, 5);
@@ -144,6 +170,7 @@ struct CameraWorld {
     ray: HashMap<String, Ray>,
     world: rtxch_lib::World,
     image: Canvas,
+    expected: Canvas,
 }
 
 
@@ -3,7 +3,7 @@ extern crate rtxch_lib;
 use rtxch_lib::utils::{parse_values_usize, parse_values_f64};
 use rtxch_lib::Tuples;
 use std::collections::HashMap;
-use cucumber::{given, when, then, World};
+use cucumber::{given, when, then, World, gherkin::Step};
 
 #[given(regex = r"(.+) ← canvas\((.+)\)")]
 fn create_canvas(world: &mut CanvasWorld, matches: &[String]) {
@@ -84,6 +84,23 @@ fn pixel_at(world: &mut CanvasWorld, matches: &[String]) {
     assert!(color.is_equal(world.canvas.pixel_at(x, y)), "{:?} not equal to {:?}", color, world.canvas.pixel_at(x, y));
 }
 
+#[given(regex = r"^ppm ← a file containing:$")]
+fn file_containing(world: &mut CanvasWorld, step: &Step) {
+    let content = step.docstring.as_ref().unwrap();
+    // the docstring starts with the line break after the opening quotes
+    world.ppm = content.strip_prefix('\n').unwrap_or(content).to_string();
+}
+
+#[when(regex = r"^c ← canvas_from_ppm\(ppm\)$")]
+fn read_ppm(world: &mut CanvasWorld) {
+    world.canvas = rtxch_lib::Canvas::canvas_from_ppm(&world.ppm).unwrap();
+}
+
+#[then(regex = r"^canvas_from_ppm\(ppm\) should fail$")]
+fn read_ppm_fails(world: &mut CanvasWorld) {
+    assert!(rtxch_lib::Canvas::canvas_from_ppm(&world.ppm).is_err());
+}
+
 #[derive(Debug, Default, World)]
 struct CanvasWorld {
     canvas: rtxch_lib::Canvas,
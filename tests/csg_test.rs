@@ -0,0 +1,208 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+fn parse_operation(op: &str) -> CsgOperation {
+    match op {
+        "union" => CsgOperation::Union,
+        "intersection" => CsgOperation::Intersection,
+        "difference" => CsgOperation::Difference,
+        _ => panic!("unknown operation {op}"),
+    }
+}
+
+#[given(regex = r"^(.+) ← (sphere|glass_sphere|cube|group|point|vector|ray|translation|csg|intersections|intersect)\((.*)\)$")]
+#[when(regex = r"^(.+) ← (csg|local_intersect|intersect|filter_intersections)\((.*)\)$")]
+fn given_item(world: &mut CsgWorld, matches: &[String]) {
+    create_item(world, matches);
+}
+
+fn create_item(world: &mut CsgWorld, matches: &[String]) {
+    let t = matches[0].clone();
+    let func = matches[1].as_str();
+    let v: Vec<&str> = matches[2].split(", ").collect();
+    match func {
+        "sphere" => {
+            world.shape.insert(t, Sphere::new());
+        },
+        "glass_sphere" => {
+            world.shape.insert(t, Sphere::glass_sphere());
+        },
+        "cube" => {
+            world.shape.insert(t, Cube::new());
+        },
+        "group" => {
+            let g = Group::new();
+            world.shape.insert(t.clone(), g.clone());
+            world.group.insert(t, g);
+        },
+        "point" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::point(v[0], v[1], v[2]));
+        },
+        "vector" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::vector(v[0], v[1], v[2]));
+        },
+        "ray" => {
+            let o = world.tuple.get(v[0]).unwrap();
+            let d = world.tuple.get(v[1]).unwrap();
+            world.ray.insert(t, Ray::new(*o, *d));
+        },
+        "translation" => {
+            let v = parse_values_f64(&matches[2]);
+            world.matrix.insert(t, Matrix::translate(v[0], v[1], v[2]));
+        },
+        "csg" => {
+            let op = parse_operation(v[0].trim_matches('"'));
+            let left = world.shape.get(v[1]).unwrap().clone();
+            let right = world.shape.get(v[2]).unwrap().clone();
+            let c = Csg::new(op, left, right);
+            world.shape.insert(t.clone(), c.clone());
+            world.csg.insert(t, c);
+        },
+        "intersections" => {
+            // t:object pairs
+            let xs: Vec<Intersection> = v.iter().map(|pair| {
+                let (time, obj) = pair.split_once(':').unwrap();
+                Intersection::new(time.parse::<f64>().unwrap(), world.shape.get(obj).unwrap())
+            }).collect();
+            world.inter.insert(t, IntersectionList::intersections_from_vec(xs));
+        },
+        "filter_intersections" => {
+            let c = world.csg.get(v[0]).unwrap();
+            let xs = world.inter.get(v[1]).unwrap();
+            let result = c.read().unwrap().filter_intersections(xs);
+            world.inter.insert(t, result);
+        },
+        "local_intersect" => {
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            let xs = s.read().unwrap().intersections_local(r, s);
+            world.inter.insert(t, xs);
+        },
+        "intersect" => {
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            world.inter.insert(t, <dyn Shape>::intersect(s, r));
+        },
+        _ => panic!("{func} not implemented")
+    }
+}
+
+#[given(regex = r"^set_transform\((.+), (.+)\)$")]
+fn set_transform(world: &mut CsgWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    let m = world.matrix.get(&matches[1]).unwrap();
+    s.write().unwrap().set_transform(m);
+}
+
+#[given(regex = r"^add_child\((.+), (.+)\)$")]
+fn add_child(world: &mut CsgWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let s = world.shape.get(&matches[1]).unwrap();
+    Group::add_child(g, s.clone());
+}
+
+#[when(regex = r#"^result ← intersection_allowed\("(.+)", (.+), (.+), (.+)\)$"#)]
+fn intersection_allowed(world: &mut CsgWorld, matches: &[String]) {
+    let op = parse_operation(&matches[0]);
+    let b: Vec<bool> = matches[1..4].iter().map(|m| m.parse::<bool>().unwrap()).collect();
+    world.result = Some(Csg::intersection_allowed(op, b[0], b[1], b[2]));
+}
+
+#[when(regex = r"^comps ← prepare_computations\(xs\[(\d+)\], r, xs\)$")]
+fn prepare_computations(world: &mut CsgWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let hit = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    let r = world.ray.get("r").unwrap();
+    world.comps = Some(Intersection::prep_computations(hit, r, xs));
+}
+
+#[then(regex = r"^result = (true|false)$")]
+fn check_result(world: &mut CsgWorld, matches: &[String]) {
+    assert_eq!(world.result.unwrap(), matches[0].parse::<bool>().unwrap());
+}
+
+#[then(regex = r#"^c\.operation = "(.+)"$"#)]
+fn check_operation(world: &mut CsgWorld, matches: &[String]) {
+    let c = world.csg.get("c").unwrap();
+    assert_eq!(c.read().unwrap().operation, parse_operation(&matches[0]));
+}
+
+#[then(regex = r"^c\.(left|right) = (.+)$")]
+fn check_child(world: &mut CsgWorld, matches: &[String]) {
+    let c = world.csg.get("c").unwrap().read().unwrap();
+    let child = if matches[0] == "left" { c.left() } else { c.right() };
+    let target = world.shape.get(&matches[1]).unwrap();
+    assert!(Arc::ptr_eq(child, target));
+}
+
+#[then(regex = r"^(.+)\.parent = (.+)$")]
+fn check_parent(world: &mut CsgWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    let target = world.shape.get(&matches[1]).unwrap();
+    assert!(Arc::ptr_eq(&s.read().unwrap().get_parent().unwrap(), target));
+}
+
+#[then(regex = r"^(.+)\.count = (\d+)$")]
+fn check_count(world: &mut CsgWorld, matches: &[String]) {
+    let xs = world.inter.get(&matches[0]).unwrap();
+    assert_eq!(xs.count(), matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^result\[(\d+)\] = xs\[(\d+)\]$")]
+fn check_filtered(world: &mut CsgWorld, matches: &[String]) {
+    let result = world.inter.get("result").unwrap();
+    let xs = world.inter.get("xs").unwrap();
+    let a = &result.xs()[matches[0].parse::<usize>().unwrap()];
+    let b = &xs.xs()[matches[1].parse::<usize>().unwrap()];
+    assert!(a.is_equal(b));
+}
+
+#[then(regex = r"^xs\[(\d+)\]\.t = (.+)$")]
+fn check_t(world: &mut CsgWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let i = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    assert!(is_equal_f64(i.t(), matches[1].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^xs\[(\d+)\]\.object = (.+)$")]
+fn check_object(world: &mut CsgWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let i = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    let target = world.shape.get(&matches[1]).unwrap();
+    assert!(Arc::ptr_eq(i.object(), target));
+}
+
+#[then(regex = r"^comps\.(n1|n2) = (.+)$")]
+fn check_comps(world: &mut CsgWorld, matches: &[String]) {
+    let comps = world.comps.as_ref().unwrap();
+    let val = if matches[0] == "n1" { comps.n1 } else { comps.n2 };
+    let target = matches[1].parse::<f64>().unwrap();
+    assert!(is_equal_f64(val, target), "{val} {target}");
+}
+
+#[derive(Debug, Default, World)]
+struct CsgWorld {
+    ray: HashMap<String, Ray>,
+    tuple: HashMap<String, Tuples>,
+    matrix: HashMap<String, Matrix>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    group: HashMap<String, Arc<RwLock<Group>>>,
+    csg: HashMap<String, Arc<RwLock<Csg>>>,
+    inter: HashMap<String, IntersectionList>,
+    result: Option<bool>,
+    comps: Option<Computations>,
+}
+
+fn main() {
+    futures::executor::block_on(CsgWorld::run(
+        "tests/features/csg.feature",
+    ));
+}
@@ -0,0 +1,215 @@
+extern crate rtxch_lib;
+
+use std::f64::consts::PI;
+use cucumber::{given, when, then, World};
+use render::render_with_settings;
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+
+// point(..), vector(..) or color(..)
+fn parse_tuple(input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    let v = parse_values_f64(&m[2].to_string());
+    match &m[1] {
+        "point" => Tuples::point(v[0], v[1], v[2]),
+        "vector" => Tuples::vector(v[0], v[1], v[2]),
+        _ => Tuples::color(v[0], v[1], v[2]),
+    }
+}
+
+// circle or polygon(n)
+fn parse_aperture(input: &str) -> Aperture {
+    let rx = cucumber::codegen::Regex::new(r"^polygon\((\d+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => Aperture::Polygon(m[1].parse::<usize>().unwrap()),
+        None => Aperture::Circle,
+    }
+}
+
+// the corners of a polygon aperture, points around the edge of a circle
+fn corners(aperture: &Aperture) -> Vec<(f64, f64)> {
+    let (n, start) = match aperture {
+        Aperture::Polygon(sides) => (*sides, PI / 2.0),
+        Aperture::Circle => (8, 0.0),
+    };
+    (0..n).map(|i| {
+        let angle = start + 2.0 * PI * i as f64 / n as f64;
+        (angle.cos(), angle.sin())
+    }).collect()
+}
+
+fn check_brightness(c: &Tuples, input: &str) {
+    match input {
+        "white" => assert!(c.x > 0.99 && c.y > 0.99 && c.z > 0.99, "{:?}", c),
+        "black" => assert!(c.x < 0.01 && c.y < 0.01 && c.z < 0.01, "{:?}", c),
+        _ => assert!(c.x > 0.05 && c.x < 0.95, "{:?}", c),
+    }
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(v[0] as usize, v[1] as usize, v[2]);
+}
+
+#[given("c.transform ← rotation_y(π/4) * translation(0, -2, 5)")]
+fn given_camera_transform(world: &mut DepthOfFieldWorld) {
+    world.camera.transform = Matrix::rotate_y(PI / 4.0) * Matrix::translate(0.0, -2.0, 5.0);
+}
+
+#[given(regex = r"^c\.(aperture_radius|focal_distance) ← (.+)$")]
+fn given_lens(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let value = matches[1].parse::<f64>().unwrap();
+    match matches[0].as_str() {
+        "aperture_radius" => world.camera.aperture_radius = value,
+        _ => world.camera.focal_distance = value,
+    }
+}
+
+#[given(regex = r"^c\.aperture = (.+)$")]
+fn given_camera_aperture(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    world.camera.aperture = parse_aperture(&matches[0]);
+}
+
+#[then(regex = r"^c\.(aperture_radius|focal_distance) = (.+)$")]
+fn check_lens(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let value = if matches[0] == "aperture_radius" { world.camera.aperture_radius } else { world.camera.focal_distance };
+    assert!(is_equal_f64(value, matches[1].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^c\.aperture = (.+)$")]
+fn check_camera_aperture(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    assert_eq!(world.camera.aperture, parse_aperture(&matches[0]));
+}
+
+#[when(regex = r"^r ← ray_for_pixel_lens\(c, (.+)\)$")]
+fn when_ray_for_pixel_lens(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.ray = Some(Camera::ray_for_pixel_lens(&world.camera, v[0] as usize, v[1] as usize, v[2], v[3], v[4], v[5]));
+}
+
+#[then(regex = r"^r\.(origin|direction) = (.+)$")]
+fn check_ray(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let r = world.ray.as_ref().unwrap();
+    let value = if matches[0] == "origin" { r.origin() } else { r.direction() };
+    assert!(value.is_equal(&parse_tuple(&matches[1])), "{:?}", value);
+}
+
+#[when(regex = r"^rays ← (\d+) rays for pixel (\d+), (\d+) with random points on the lens$")]
+fn when_lens_rays(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let (x, y) = (matches[1].parse::<usize>().unwrap(), matches[2].parse::<usize>().unwrap());
+    let mut rng = Rng::new(0);
+    world.rays = (0..matches[0].parse::<usize>().unwrap())
+        .map(|_| Camera::ray_for_pixel_lens(&world.camera, x, y, 0.5, 0.5, rng.next_f64(), rng.next_f64()))
+        .collect();
+}
+
+#[then(regex = r"^every ray starts within (.+) of (point\(.+\))$")]
+fn check_ray_origins(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let radius = matches[0].parse::<f64>().unwrap();
+    let center = parse_tuple(&matches[1]);
+    for r in &world.rays {
+        assert!(r.origin().clone().subtract(&center).magnitude() <= radius + 1e-9, "{:?}", r);
+    }
+}
+
+#[then(regex = r"^every ray passes through the pinhole ray through pixel (\d+), (\d+) at distance (.+)$")]
+fn check_focus(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let distance = matches[2].parse::<f64>().unwrap();
+    let pinhole = Camera::ray_for_pixel(&world.camera, x, y);
+    let forward = (Matrix::inverse(&world.camera.transform).unwrap() * Tuples::vector(0.0, 0.0, -1.0)).normalize();
+    let along = distance / Tuples::dot(pinhole.direction(), &forward);
+    let focus = Ray::position(&pinhole, along);
+    for r in &world.rays {
+        let to_focus = focus.clone().subtract(r.origin()).normalize();
+        assert!(to_focus.is_equal(r.direction()), "{:?} {:?}", to_focus, r);
+    }
+}
+
+#[given(regex = r"^aperture ← (.+)$")]
+fn given_aperture(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    world.aperture = parse_aperture(&matches[0]);
+}
+
+#[when(regex = r"^points ← (\d+) samples of aperture$")]
+fn when_aperture_samples(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let mut rng = Rng::new(0);
+    world.points = (0..matches[0].parse::<usize>().unwrap()).map(|_| world.aperture.sample(rng.next_f64(), rng.next_f64())).collect();
+}
+
+#[then(regex = r"^every point lies inside of (.+)$")]
+fn check_points_inside(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let aperture = parse_aperture(&matches[0]);
+    let corners = corners(&aperture);
+    for p in &world.points {
+        match aperture {
+            Aperture::Circle => assert!(p.0 * p.0 + p.1 * p.1 <= 1.0 + 1e-9, "{:?}", p),
+            Aperture::Polygon(_) => {
+                for i in 0..corners.len() {
+                    let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                    let cross = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+                    assert!(cross >= -1e-9, "{:?}", p);
+                }
+            },
+        }
+    }
+}
+
+#[then(regex = r"^the points cover the corners of (.+)$")]
+fn check_points_cover(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    for corner in corners(&parse_aperture(&matches[0])) {
+        assert!(world.points.iter().any(|p| (p.0 - corner.0).hypot(p.1 - corner.1) < 0.35), "{:?}", corner);
+    }
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut DepthOfFieldWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^the emissive sphere at (point\(.+\))$")]
+fn given_emissive_sphere(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let p = parse_tuple(&matches[0]);
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&Matrix::translate(p.x, p.y, p.z));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+#[when(regex = r"^image ← render_with_settings\(c, w, jittered, (\d+)\)$")]
+fn when_render(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let settings = RenderSettings::new(Sampling::Jittered, matches[0].parse::<usize>().unwrap());
+    world.image = render_with_settings(&world.camera, &world.world, &settings);
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) is (white|black|gray)$")]
+fn check_pixel(world: &mut DepthOfFieldWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    check_brightness(world.image.pixel_at(x, y), &matches[2]);
+}
+
+#[derive(Debug, Default, World)]
+struct DepthOfFieldWorld {
+    camera: Camera,
+    world: rtxch_lib::World,
+    image: Canvas,
+    ray: Option<Ray>,
+    rays: Vec<Ray>,
+    aperture: Aperture,
+    points: Vec<(f64, f64)>,
+}
+
+fn main() {
+    futures::executor::block_on(DepthOfFieldWorld::run(
+        "tests/features/depth_of_field.feature",
+    ));
+}
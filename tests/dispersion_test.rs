@@ -0,0 +1,263 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &DispersionWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_ray(world: &DispersionWorld, input: &str) -> Ray {
+    let rx = cucumber::codegen::Regex::new(r"^ray\((.+\)), (.+\))\)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    Ray::new(parse_tuple(world, &m[1]), parse_tuple(world, &m[2]))
+}
+
+// none, cauchy(a, b) or sellmeier(b1, b2, b3, c1, c2, c3)
+fn parse_dispersion(input: &str) -> Dispersion {
+    let rx = cucumber::codegen::Regex::new(r"^(cauchy|sellmeier)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "cauchy" => Dispersion::Cauchy { a: v[0], b: v[1] },
+                _ => Dispersion::Sellmeier { b: [v[0], v[1], v[2]], c: [v[3], v[4], v[5]] },
+            }
+        },
+        None => Dispersion::None,
+    }
+}
+
+fn check_indices(indices: &[f64; 3], input: &str) {
+    let v = parse_values_f64(&input.to_string());
+    for c in 0..3 {
+        assert!(is_equal_f64(indices[c], v[c]), "{:?}", indices);
+    }
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut DispersionWorld) {
+    world.material = Some(Material::material());
+}
+
+#[given(regex = r"^m\.refractive_index ← (.+)$")]
+fn given_refractive_index(world: &mut DispersionWorld, matches: &[String]) {
+    world.material.as_mut().unwrap().refractive_index = matches[0].parse::<f64>().unwrap();
+}
+
+#[given(regex = r"^m\.dispersion ← (.+)$")]
+fn given_dispersion(world: &mut DispersionWorld, matches: &[String]) {
+    world.material.as_mut().unwrap().dispersion = parse_dispersion(&matches[0]);
+}
+
+#[then("m.dispersion is none")]
+fn check_no_dispersion(world: &mut DispersionWorld) {
+    let material = world.material.as_ref().unwrap();
+    assert_eq!(material.dispersion, Dispersion::None);
+    assert!(!material.is_dispersive());
+}
+
+#[then(regex = r"^channel_indices\(m\) = (.+)$")]
+fn check_channel_indices(world: &mut DispersionWorld, matches: &[String]) {
+    check_indices(&world.material.as_ref().unwrap().channel_indices(), &matches[0]);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut DispersionWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("shape ← glass_sphere()")]
+fn given_glass_sphere(world: &mut DispersionWorld) {
+    let shape: Arc<RwLock<dyn Shape>> = Sphere::glass_sphere();
+    world.shape.insert("shape".to_string(), shape);
+}
+
+#[given(regex = r"^shape\.material\.dispersion ← (.+)$")]
+fn given_shape_dispersion(world: &mut DispersionWorld, matches: &[String]) {
+    let dispersion = parse_dispersion(&matches[0]);
+    world.shape.get("shape").unwrap().write().unwrap().get_mut_material().dispersion = dispersion;
+}
+
+// a 60 degree prism with its apex at (0, 1, 0) and its base at y = -1, the intersection of
+// two large rotated cubes for the slanted faces and a flat one for the base
+#[given(regex = r"^prism ← prism\((.+)\)$")]
+fn given_prism(world: &mut DispersionWorld, matches: &[String]) {
+    let mut material = Material::material();
+    material.ambient = 0.0;
+    material.diffuse = 0.0;
+    material.specular = 0.0;
+    material.transparency = 1.0;
+    material.refractive_index = 1.55;
+    material.dispersion = parse_dispersion(&matches[0]);
+
+    let face = |angle: f64| {
+        let cube: Arc<RwLock<dyn Shape>> = Cube::new();
+        // the top face turned by angle goes through the apex
+        let translation = Matrix::translate(10.0 * angle.sin(), 1.0 - 10.0 * angle.cos(), 0.0);
+        cube.write().unwrap().set_transform(&Matrix::transform_from_trs(&translation, &Matrix::rotate_z(angle), &Matrix::scale(10.0, 10.0, 10.0)));
+        cube.write().unwrap().set_material(&material);
+        cube
+    };
+    let base: Arc<RwLock<dyn Shape>> = Cube::new();
+    base.write().unwrap().set_transform(&Matrix::scale(5.0, 1.0, 5.0));
+    base.write().unwrap().set_material(&material);
+    let faces: Arc<RwLock<dyn Shape>> = Csg::new(CsgOperation::Intersection, face(PI / 3.0), face(-PI / 3.0));
+    let prism: Arc<RwLock<dyn Shape>> = Csg::new(CsgOperation::Intersection, faces, base);
+    world.shape.insert("prism".to_string(), prism);
+}
+
+#[given(regex = r"^(\w+) is added to w$")]
+fn given_shape_added(world: &mut DispersionWorld, matches: &[String]) {
+    let shape = world.shape.get(&matches[0]).unwrap().clone();
+    world.world.add_object(shape);
+}
+
+#[given(regex = r"^the emissive spot at (point\(.+\))$")]
+fn given_emissive_spot(world: &mut DispersionWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&(&Matrix::translate(p.x, p.y, p.z) * &Matrix::scale(0.12, 0.12, 0.12)));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+#[given(regex = r"^r ← (ray\(.+\))$")]
+fn given_ray(world: &mut DispersionWorld, matches: &[String]) {
+    world.ray = Some(parse_ray(world, &matches[0]));
+}
+
+#[given(regex = r"^pt ← path_tracer\((\d+)\)$")]
+fn given_path_tracer(world: &mut DispersionWorld, matches: &[String]) {
+    world.tracer = PathTracer::new(matches[0].parse::<usize>().unwrap());
+}
+
+#[when("comps ← the first hit of r on shape")]
+fn when_first_hit(world: &mut DispersionWorld) {
+    let mut w = rtxch_lib::World::new();
+    w.add_object(world.shape.get("shape").unwrap().clone());
+    let r = world.ray.as_ref().unwrap();
+    let xs = rtxch_lib::World::intersect_world(&w, r);
+    let hit = IntersectionList::hit(&xs).unwrap();
+    world.comps = Some(Intersection::prep_computations(hit, r, &xs));
+}
+
+#[then("comps.channel_indices is none")]
+fn check_no_channel_indices(world: &mut DispersionWorld) {
+    assert!(world.comps.as_ref().unwrap().channel_indices().is_none());
+}
+
+#[then(regex = r"^comps\.(n1|n2) channels = (.+)$")]
+fn check_comps_channels(world: &mut DispersionWorld, matches: &[String]) {
+    let (n1, n2) = world.comps.as_ref().unwrap().channel_indices().unwrap();
+    check_indices(if matches[0] == "n1" { &n1 } else { &n2 }, &matches[1]);
+}
+
+#[then("the blue channel is refracted closer to the normal than the red channel")]
+fn check_blue_bends_more(world: &mut DispersionWorld) {
+    let comps = world.comps.as_ref().unwrap();
+    let (n1, n2) = comps.channel_indices().unwrap();
+    let below = comps.normal_v.clone().negate();
+    let mut red = *rtxch_lib::World::refracted_ray_with(comps, n1[0], n2[0]).unwrap().direction();
+    let mut blue = *rtxch_lib::World::refracted_ray_with(comps, n1[2], n2[2]).unwrap().direction();
+    assert!(Tuples::dot(&blue.normalize(), &below) > Tuples::dot(&red.normalize(), &below));
+}
+
+#[then("schlick_with(comps, 1, 1.5) = schlick(comps)")]
+fn check_schlick_with(world: &mut DispersionWorld) {
+    let comps = world.comps.as_ref().unwrap();
+    assert!(is_equal_f64(Intersection::schlick_with(comps, 1.0, 1.5), Intersection::schlick(comps)));
+}
+
+#[then("the blue channel reflects more than the red channel")]
+fn check_blue_reflects_more(world: &mut DispersionWorld) {
+    let comps = world.comps.as_ref().unwrap();
+    let (n1, n2) = comps.channel_indices().unwrap();
+    assert!(Intersection::schlick_with(comps, n1[2], n2[2]) > Intersection::schlick_with(comps, n1[0], n2[0]));
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut DispersionWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^c ← trace\(w, r\) in channel (\d)$")]
+fn when_trace_channel(world: &mut DispersionWorld, matches: &[String]) {
+    let channel = matches[0].parse::<usize>().unwrap();
+    let c = rtxch_lib::World::trace(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS, MAX_ITERATIONS, Some(channel));
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^c ← the average of (\d+) paths along (ray\(.+\))$")]
+fn when_average_paths(world: &mut DispersionWorld, matches: &[String]) {
+    let n = matches[0].parse::<usize>().unwrap();
+    let ray = parse_ray(world, &matches[1]);
+    let mut rng = Rng::new(0);
+    let mut sum = Tuples::color(0.0, 0.0, 0.0);
+    for _ in 0..n {
+        sum.add(&world.tracer.color_at(&world.world, &ray, &mut rng));
+    }
+    world.tuple.insert("c".to_string(), sum.scale(1.0 / n as f64));
+}
+
+#[then(regex = r"^c = (color\(.+\))$")]
+fn check_color(world: &mut DispersionWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then("c has no red and no green")]
+fn check_only_blue(world: &mut DispersionWorld) {
+    let c = world.tuple.get("c").unwrap();
+    assert!(is_equal_f64(c.x, 0.0) && is_equal_f64(c.y, 0.0), "{:?}", c);
+}
+
+#[then(regex = r"^the blue of c is between (.+) and (.+)$")]
+fn check_blue(world: &mut DispersionWorld, matches: &[String]) {
+    let c = world.tuple.get("c").unwrap();
+    let (low, high) = (matches[0].parse::<f64>().unwrap(), matches[1].parse::<f64>().unwrap());
+    assert!(c.z > low && c.z < high, "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct DispersionWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    material: Option<Material>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    ray: Option<Ray>,
+    comps: Option<Computations>,
+    tracer: PathTracer,
+}
+
+fn main() {
+    futures::executor::block_on(DispersionWorld::run(
+        "tests/features/dispersion.feature",
+    ));
+}
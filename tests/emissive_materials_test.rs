@@ -0,0 +1,155 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &EmissiveWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut EmissiveWorld) {
+    world.material = Some(Material::material());
+}
+
+#[given(regex = r"^m\.emission ← (.+)$")]
+fn given_emission(world: &mut EmissiveWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.material.as_mut().unwrap().emission = color;
+}
+
+#[given(regex = r"^m\.emission_strength ← (.+)$")]
+fn given_emission_strength(world: &mut EmissiveWorld, matches: &[String]) {
+    world.material.as_mut().unwrap().emission_strength = matches[0].parse::<f64>().unwrap();
+}
+
+#[then(regex = r"^m\.emission = (.+)$")]
+fn check_emission(world: &mut EmissiveWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    assert!(world.material.as_ref().unwrap().emission.is_equal(&target));
+}
+
+#[then(regex = r"^m\.emission_strength = (.+)$")]
+fn check_emission_strength(world: &mut EmissiveWorld, matches: &[String]) {
+    assert!(is_equal_f64(world.material.as_ref().unwrap().emission_strength, matches[0].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^emitted\(m\) = (.+)$")]
+fn check_emitted(world: &mut EmissiveWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let emitted = world.material.as_ref().unwrap().emitted();
+    assert!(emitted.is_equal(&target), "{:?}", emitted);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut EmissiveWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^w\.light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut EmissiveWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.world.add_light(lights::point_light(&position, &intensity));
+}
+
+#[given(regex = r"^shape ← (sphere|plane)\(\)$")]
+fn given_shape(world: &mut EmissiveWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = if matches[0] == "sphere" { Sphere::new() } else { Plane::new() };
+    world.shape = Some(shape);
+}
+
+#[given(regex = r"^shape\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut EmissiveWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given(regex = r"^shape\.material\.emission ← (.+)$")]
+fn given_shape_emission(world: &mut EmissiveWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().get_mut_material().emission = color;
+}
+
+#[given(regex = r"^shape\.material\.(ambient|diffuse|specular|reflective|emission_strength) ← ([-\d.]+)$")]
+fn given_shape_value(world: &mut EmissiveWorld, matches: &[String]) {
+    let value = matches[1].parse::<f64>().unwrap();
+    let mut shape = world.shape.as_ref().unwrap().write().unwrap();
+    let material = shape.get_mut_material();
+    match matches[0].as_str() {
+        "ambient" => material.ambient = value,
+        "diffuse" => material.diffuse = value,
+        "specular" => material.specular = value,
+        "reflective" => material.reflective = value,
+        _ => material.emission_strength = value,
+    }
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut EmissiveWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^pt ← path_tracer\((\d+)\)$")]
+fn given_path_tracer(world: &mut EmissiveWorld, matches: &[String]) {
+    world.tracer = PathTracer::new(matches[0].parse::<usize>().unwrap());
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut EmissiveWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut EmissiveWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when("c ← path_color_at(pt, w, r)")]
+fn when_path_color_at(world: &mut EmissiveWorld) {
+    let mut rng = Rng::new(0);
+    let c = world.tracer.color_at(&world.world, world.ray.as_ref().unwrap(), &mut rng);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[then(regex = r"^c = (.+)$")]
+fn check_color(world: &mut EmissiveWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct EmissiveWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    material: Option<Material>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    tracer: PathTracer,
+    ray: Option<Ray>,
+}
+
+fn main() {
+    futures::executor::block_on(EmissiveWorld::run(
+        "tests/features/emissive_materials.feature",
+    ));
+}
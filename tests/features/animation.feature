@@ -0,0 +1,169 @@
+Feature: Animation
+
+Scenario Outline: A track interpolates between its keyframes
+  Given track ← <interpolation> track with keys 1: 2, 3: 6, 4: 0
+  Then value_at(track, <time>) = <value>
+
+  Examples:
+    | interpolation | time | value |
+    | linear        | 0    | 2     |
+    | linear        | 1    | 2     |
+    | linear        | 1.5  | 3     |
+    | linear        | 3.5  | 3     |
+    | linear        | 5    | 0     |
+    | smooth        | 1.5  | 2.625 |
+    | smooth        | 2    | 4     |
+    | smooth        | 3    | 6     |
+
+Scenario: Keyframes can be added in any order
+  Given track ← linear track with keys 3: 6, 1: 2
+  Then value_at(track, 2) = 4
+
+Scenario: A track of points moves along a straight line
+  Given track ← linear track of points 0: point(0, 0, 0), 2: point(4, -2, 0)
+  Then point_at(track, 0.5) = point(1, -0.5, 0)
+
+Scenario: A track of transforms rotates properly
+  Given track ← linear track of transforms 0: identity_matrix, 1: rotation_y(π/2)
+  Then transform_at(track, 0.5) = rotation_y(π/4)
+
+Scenario Outline: Frames are spread evenly over the animation
+  Given animation ← animation(<frames>, <duration>)
+    And animation.looping ← <looping>
+  Then time_of_frame(animation, <frame>) = <time>
+
+  Examples:
+    | frames | duration | looping | frame | time |
+    | 5      | 2        | false   | 0     | 0    |
+    | 5      | 2        | false   | 2     | 1    |
+    | 5      | 2        | false   | 4     | 2    |
+    | 4      | 1        | true    | 1     | 0.25 |
+    | 4      | 1        | true    | 3     | 0.75 |
+    | 1      | 1        | false   | 0     | 0    |
+
+Scenario Outline: A turntable circles the camera around the center
+  Given c ← camera(11, 11, 1.5708)
+    And animation ← turntable(4, point(0, 1, 0), point(0, 1, -5), vector(0, 1, 0))
+  When the animation is applied to frame <frame>
+  Then the camera is at <position>
+    And the camera looks at point(0, 1, 0)
+
+  Examples:
+    | frame | position        |
+    | 0     | point(0, 1, -5) |
+    | 1     | point(-5, 1, 0) |
+    | 2     | point(0, 1, 5)  |
+
+Scenario Outline: A fly-through follows its waypoints
+  Given c ← camera(11, 11, 1.5708)
+    And animation ← fly_through(5, 2, point(0, 0, 0), point(0, 0, 10), point(10, 0, 10))
+  When the animation is applied to frame <frame>
+  Then the camera is at <position>
+    And the camera looks at <target>
+
+  Examples:
+    | frame | position         | target           |
+    | 0     | point(0, 0, 0)   | point(0, 0, 10)  |
+    | 1     | point(0, 0, 5)   | point(5, 0, 10)  |
+    | 2     | point(0, 0, 10)  | point(10, 0, 10) |
+    | 4     | point(10, 0, 10) | point(20, 0, 10) |
+
+Scenario: Animated objects are found where they moved to
+  Given w ← world()
+    And c ← camera(11, 11, 1.5708)
+    And s ← sphere() in w
+    And animation ← animation(3, 1)
+    And s moves from translation(0, 0, 0) to translation(0, 0, 10) in animation
+  When the animation is applied to frame 1
+  Then s.transform = translation(0, 0, 5)
+    And a ray from point(0, 0, -10) towards vector(0, 0, 1) hits s at 14
+
+Scenario: Animated objects in groups update the bounds of the group
+  Given w ← world()
+    And c ← camera(11, 11, 1.5708)
+    And s ← sphere() in a group in w
+    And animation ← animation(3, 1)
+    And s moves from translation(0, 0, 0) to translation(0, 10, 0) in animation
+  When the animation is applied to frame 2
+  Then a ray from point(0, 10, -10) towards vector(0, 0, 1) hits s at 9
+
+Scenario: Lights can be moved
+  Given w ← default_world()
+    And c ← camera(11, 11, 1.5708)
+    And animation ← animation(2, 1)
+    And light 0 moves from point(0, 10, 0) to point(10, 0, 0) in animation
+  Then adding the channel succeeds
+  When the animation is applied to frame 1
+  Then the direction from the origin to light 0 is vector(1, 0, 0)
+
+Scenario: Only lights of the world can be moved
+  Given w ← default_world()
+    And animation ← animation(2, 1)
+    And light 1 moves from point(0, 10, 0) to point(10, 0, 0) in animation
+  Then adding the channel fails
+    And animation has 0 channels
+
+Scenario: Tracks without keyframes are rejected
+  Given w ← default_world()
+    And animation ← animation(2, 1)
+    And light 0 follows an empty track in animation
+  Then adding the channel fails
+    And animation has 0 channels
+
+Scenario: A fly-through needs at least two waypoints
+  Given animation ← fly_through(5, 2, point(0, 0, 0))
+  Then creating the fly-through fails
+
+Scenario: Area lights move as a whole
+  Given w ← world()
+    And w has an area light from point(-1, 5, -1) across vector(2, 0, 0) and vector(0, 0, 2)
+    And c ← camera(11, 11, 1.5708)
+    And animation ← animation(2, 1)
+    And light 0 moves from point(0, 5, 0) to point(10, 5, 0) in animation
+  When the animation is applied to frame 1
+  Then the direction from point(10, 0, 0) to light 0 is vector(0, 1, 0)
+
+Scenario: Material colors and values can be animated
+  Given w ← world()
+    And c ← camera(11, 11, 1.5708)
+    And s ← sphere() in w
+    And animation ← animation(3, 1)
+    And the color of s changes from color(1, 0, 0) to color(0, 0, 1) in animation
+    And the reflective of s changes from 0 to 0.8 in animation
+  When the animation is applied to frame 1
+  Then the color of s is color(0.5, 0, 0.5)
+    And s.material.reflective = 0.4
+
+Scenario Outline: Frames are written to numbered files
+  Then frame_path(<path>, <frame>) = <name>
+
+  Examples:
+    | path                  | frame | name                  |
+    | frames/frame_###.ppm  | 7     | frames/frame_007.ppm  |
+    | frames/frame_#.ppm    | 12    | frames/frame_12.ppm   |
+    | out.ppm               | 3     | out_0003.ppm          |
+    | renders.v2/out        | 42    | renders.v2/out_0042   |
+
+Scenario Outline: Rendering frame N of M shows the scene at the time of the frame
+  Given w ← world()
+    And c ← camera(11, 11, 0.5)
+    And s ← an emissive sphere in w
+    And animation ← animation(3, 1)
+    And s moves from translation(0, 0, -10) to translation(0, 5, -10) in animation
+  When image ← render_frame(animation, c, w, <frame>)
+  Then pixel_at(image, 5, 5) is <center>
+
+  Examples:
+    | frame | center |
+    | 0     | white  |
+    | 2     | black  |
+
+Scenario: A range of frames is rendered to files
+  Given w ← world()
+    And c ← camera(5, 5, 0.5)
+    And s ← an emissive sphere in w
+    And animation ← animation(4, 1)
+    And s moves from translation(0, 0, -10) to translation(0, 5, -10) in animation
+  When frames 1 to 3 are rendered to numbered files
+  Then the files of frames 1 and 2 exist
+    And the files of frames 0 and 3 don't exist
@@ -0,0 +1,143 @@
+Feature: Area lights and soft shadows
+
+Scenario Outline: A point light evaluates the light intensity at a given point
+  Given w ← default_world()
+    And light ← point_light(point(-10, 10, -10), color(1, 1, 1))
+    And pt ← point(<x>, <y>, <z>)
+  When intensity ← intensity_at(w, pt, light)
+  Then intensity = <result>
+
+  Examples:
+    | x       | y       | z       | result |
+    | 0       | 1.0001  | 0       | 1.0    |
+    | -1.0001 | 0       | 0       | 1.0    |
+    | 0       | 0       | -1.0001 | 1.0    |
+    | 0       | 0       | 1.0001  | 0.0    |
+    | 1.0001  | 0       | 0       | 0.0    |
+    | 0       | -1.0001 | 0       | 0.0    |
+    | 0       | 0       | 0       | 0.0    |
+
+Scenario Outline: lighting() uses light intensity to attenuate color
+  Given w ← default_world()
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And shape ← the first object in w
+    And shape.material.ambient ← 0.1
+    And shape.material.diffuse ← 0.9
+    And shape.material.specular ← 0
+    And shape.material.color ← color(1, 1, 1)
+    And pt ← point(0, 0, -1)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+  When result ← lighting(shape.material, shape, light, pt, eyev, normalv, <intensity>)
+  Then result = <result>
+
+  Examples:
+    | intensity | result                  |
+    | 1.0       | color(1, 1, 1)          |
+    | 0.5       | color(0.55, 0.55, 0.55) |
+    | 0.0       | color(0.1, 0.1, 0.1)    |
+
+Scenario: Creating an area light
+  Given corner ← point(0, 0, 0)
+    And v1 ← vector(2, 0, 0)
+    And v2 ← vector(0, 0, 1)
+  When light ← area_light(corner, v1, 4, v2, 2, color(1, 1, 1))
+  Then light.corner = corner
+    And light.uvec = vector(0.5, 0, 0)
+    And light.usteps = 4
+    And light.vvec = vector(0, 0, 0.5)
+    And light.vsteps = 2
+    And light.samples = 8
+    And light.position = point(1, 0, 0.5)
+
+Scenario Outline: Finding a single point on an area light
+  Given corner ← point(0, 0, 0)
+    And v1 ← vector(2, 0, 0)
+    And v2 ← vector(0, 0, 1)
+    And light ← area_light(corner, v1, 4, v2, 2, color(1, 1, 1))
+  When pt ← point_on_light(light, <u>, <v>, 0.5, 0.5)
+  Then pt = <result>
+
+  Examples:
+    | u | v | result               |
+    | 0 | 0 | point(0.25, 0, 0.25) |
+    | 1 | 0 | point(0.75, 0, 0.25) |
+    | 0 | 1 | point(0.25, 0, 0.75) |
+    | 2 | 0 | point(1.25, 0, 0.25) |
+    | 3 | 1 | point(1.75, 0, 0.75) |
+
+Scenario: Jittered samples stay inside their cell of the area light
+  Given corner ← point(0, 0, 0)
+    And v1 ← vector(2, 0, 0)
+    And v2 ← vector(0, 0, 1)
+    And light ← area_light(corner, v1, 4, v2, 2, color(1, 1, 1))
+    And pt ← point(0, 5, 0)
+  When samples ← sample_points(light, pt)
+  Then samples has 8 points
+    And every sample lies in its own cell of light
+    And sample_points(light, pt) = samples
+
+Scenario Outline: The area light intensity function
+  Given w ← default_world()
+    And corner ← point(-0.5, -0.5, -5)
+    And v1 ← vector(1, 0, 0)
+    And v2 ← vector(0, 1, 0)
+    And light ← area_light(corner, v1, 2, v2, 2, color(1, 1, 1))
+    And light is not jittered
+    And pt ← point(<x>, <y>, <z>)
+  When intensity ← intensity_at(w, pt, light)
+  Then intensity = <result>
+
+  Examples:
+    | x    | y    | z  | result |
+    | 0    | 0    | 2  | 0.0    |
+    | 1    | -1   | 2  | 0.25   |
+    | 1.5  | 0    | 2  | 0.5    |
+    | 1.25 | 1.25 | 3  | 0.75   |
+    | 0    | 0    | -2 | 1.0    |
+
+Scenario Outline: lighting() samples the area light
+  Given corner ← point(-0.5, -0.5, -5)
+    And v1 ← vector(1, 0, 0)
+    And v2 ← vector(0, 1, 0)
+    And light ← area_light(corner, v1, 2, v2, 2, color(1, 1, 1))
+    And light is not jittered
+    And shape ← sphere()
+    And shape.material.ambient ← 0.1
+    And shape.material.diffuse ← 0.9
+    And shape.material.specular ← 0
+    And shape.material.color ← color(1, 1, 1)
+    And eye ← point(0, 0, -5)
+    And pt ← <point>
+    And eyev ← normalize(eye - pt)
+    And normalv ← vector(pt.x, pt.y, pt.z)
+  When result ← lighting(shape.material, shape, light, pt, eyev, normalv, 1.0)
+  Then result = <result>
+
+  Examples:
+    | point                      | result                        |
+    | point(0, 0, -1)            | color(0.9965, 0.9965, 0.9965) |
+    | point(0, 0.7071, -0.7071)  | color(0.6232, 0.6232, 0.6232) |
+
+Scenario: An area light casts a penumbra
+  Given w ← default_world()
+    And corner ← point(-0.5, -0.5, -5)
+    And v1 ← vector(1, 0, 0)
+    And v2 ← vector(0, 1, 0)
+    And light ← area_light(corner, v1, 8, v2, 8, color(1, 1, 1))
+    And w has only light
+    And pt ← point(1.5, 0, 2)
+  When intensity ← intensity_at(w, pt, light)
+  Then intensity is between 0 and 1
+
+Scenario: Closer samples of an attenuated area light count more
+  Given w ← default_world()
+    And corner ← point(-2, 0, -3)
+    And v1 ← vector(8, 0, 0)
+    And v2 ← vector(0, 0, 0)
+    And light ← area_light(corner, v1, 2, v2, 1, color(1, 1, 1)) with inverse_square attenuation
+    And light is not jittered
+    And w has only light
+    And pt ← point(0, 0, 3)
+  When intensity ← intensity_at(w, pt, light)
+  Then intensity = 0.40909
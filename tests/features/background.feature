@@ -0,0 +1,114 @@
+Feature: World background
+
+Scenario: A world is black where rays miss by default
+  Given w ← world()
+    And r ← ray(point(0, 0, -5), vector(0, 1, 0))
+  When c ← color_at(w, r)
+  Then c = color(0, 0, 0)
+
+Scenario: A solid background is seen where rays miss
+  Given w ← default_world()
+    And w.background ← solid(color(0.2, 0.3, 0.4))
+    And r ← ray(point(0, 0, -5), vector(0, 1, 0))
+  When c ← color_at(w, r)
+  Then c = color(0.2, 0.3, 0.4)
+
+Scenario: A background does not change what rays hit
+  Given w ← default_world()
+    And w.background ← solid(color(0.2, 0.3, 0.4))
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← color_at(w, r)
+  Then c = color(0.38066, 0.47583, 0.2855)
+
+Scenario Outline: A gradient background blends from bottom to top
+  Given w ← world()
+    And w.background ← gradient(color(1, 1, 1), color(0.5, 0.7, 1))
+    And r ← ray(point(0, 0, 0), vector(<x>, <y>, <z>))
+  When c ← color_at(w, r)
+  Then c = color(<r>, <g>, <b>)
+
+  Examples:
+    | x | y  | z  | r       | g       | b |
+    | 0 | 1  | 0  | 0.5     | 0.7     | 1 |
+    | 0 | -1 | 0  | 1       | 1       | 1 |
+    | 0 | 0  | -1 | 0.75    | 0.85    | 1 |
+    | 0 | 2  | 2  | 0.57322 | 0.74393 | 1 |
+
+Scenario Outline: Mapping a direction onto an equirectangular image
+  Given d ← vector(<x>, <y>, <z>)
+  When (u, v) ← direction_to_uv(d)
+  Then u = <u>
+    And v = <v>
+
+  Examples:
+    | x        | y        | z        | u     | v    |
+    | 0        | 0        | -1       | 0.5   | 0.5  |
+    | 1        | 0        | 0        | 0.75  | 0.5  |
+    | 0        | 0        | 1        | 1     | 0.5  |
+    | -1       | 0        | 0        | 0.25  | 0.5  |
+    | 0        | 1        | 0        | 1     | 0    |
+    | 0        | -1       | 0        | 1     | 1    |
+    | 0        | 0.70711  | -0.70711 | 0.5   | 0.25 |
+
+Scenario Outline: An image background is looked up by the direction of the ray
+  Given image ← a file containing:
+    """
+    P3
+    4 2
+    100
+    100 0 0  0 100 0  0 0 100  100 100 100
+    100 0 0  0 100 0  0 0 100  100 100 100
+    """
+    And w ← world()
+    And w.background ← image(image)
+    And r ← ray(point(0, 0, 0), vector(<x>, <y>, <z>))
+  When c ← color_at(w, r)
+  Then c = color(<r>, <g>, <b>)
+
+  Examples:
+    | x        | y | z        | r   | g   | b   |
+    | 0.70711  | 0 | -0.70711 | 0   | 0   | 1   |
+    | 0.70711  | 0 | 0.70711  | 1   | 1   | 1   |
+    | -0.70711 | 0 | 0.70711  | 1   | 0   | 0   |
+    | 0        | 0 | -1       | 0   | 0.5 | 0.5 |
+    | 0        | 0 | 1        | 1   | 0.5 | 0.5 |
+
+Scenario Outline: Rows of an image background are clamped at the poles
+  Given image ← a file containing:
+    """
+    P3
+    1 2
+    1
+    1 0 0
+    0 0 1
+    """
+    And w ← world()
+    And w.background ← image(image)
+    And r ← ray(point(0, 0, 0), vector(0, <y>, 0))
+  When c ← color_at(w, r)
+  Then c = color(<r>, 0, <b>)
+
+  Examples:
+    | y  | r | b |
+    | 1  | 1 | 0 |
+    | -1 | 0 | 1 |
+
+Scenario: Reflected rays that escape pick up the background
+  Given w ← world()
+    And w.background ← solid(color(0.2, 0.4, 0.6))
+    And shape ← plane()
+    And shape.material.reflective ← 0.5
+    And shape.transform ← translation(0, -1, 0)
+    And shape is added to w
+    And r ← ray(point(0, 0, -3), vector(0, -0.70711, 0.70711))
+  When c ← color_at(w, r)
+  Then c = color(0.1, 0.2, 0.3)
+
+Scenario: Refracted rays that escape pick up the background
+  Given w ← world()
+    And w.background ← solid(color(0.2, 0.4, 0.6))
+    And shape ← glass_sphere()
+    And shape is added to w
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← color_at(w, r)
+  Then c = color(0.2, 0.4, 0.6)
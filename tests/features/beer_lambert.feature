@@ -0,0 +1,85 @@
+Feature: Absorption inside transparent media
+
+Scenario: Materials don't absorb by default
+  Given m ← material()
+  Then m.absorption = color(1, 1, 1)
+    And m.absorption_density = 0
+
+Scenario: Light takes on the absorption color with distance
+  Given m ← material()
+    And m.absorption ← color(0.2, 0.6, 1)
+    And m.absorption_density ← 1
+  Then transmittance(m, 0) = color(1, 1, 1)
+    And transmittance(m, 2) = color(0.20190, 0.44933, 1)
+
+Scenario: A material without density lets everything through
+  Given m ← material()
+    And m.absorption ← color(0.2, 0.6, 1)
+  Then transmittance(m, 100) = color(1, 1, 1)
+
+Scenario: A hit from the outside came through empty space
+  Given shape ← glass_sphere()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+    And xs ← intersections(4:shape, 6:shape)
+  When comps ← prepare_computations(xs[0], r, xs)
+  Then comps.medium is nothing
+
+Scenario: A hit from the inside came through the object
+  Given shape ← glass_sphere()
+    And r ← ray(point(0, 0, 0), vector(0, 0, 1))
+    And xs ← intersections(-1:shape, 1:shape)
+  When comps ← prepare_computations(xs[1], r, xs)
+  Then comps.medium is shape
+
+Scenario Outline: Thicker slabs absorb more of the light behind them
+  Given w ← world()
+    And the emissive sphere below the slab
+    And slab ← absorbing_slab(<thickness>)
+    And slab is added to w
+    And r ← ray(point(0, 5, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c = color(<gray>, <gray>, <gray>)
+
+  Examples:
+    | thickness | gray    |
+    | 0.2       | 0.90484 |
+    | 2         | 0.36788 |
+
+Scenario: The path tracer absorbs inside of media as well
+  Given w ← world()
+    And the emissive sphere below the slab
+    And slab ← absorbing_slab(2)
+    And slab is added to w
+    And pt ← path_tracer(8)
+  When c ← the path along ray(point(0, 5, 0), vector(0, -1, 0))
+  Then c = color(0.36788, 0.36788, 0.36788)
+
+Scenario: A clear slab only lets its absorbing content take light away
+  Given w ← world()
+    And the emissive sphere below the slab
+    And slab ← absorbing_slab(4)
+    And slab.material.absorption_density ← 0
+    And slab is added to w
+    And shape ← glass_sphere()
+    And shape.material.absorption ← color(0.5, 0.5, 0.5)
+    And shape.material.absorption_density ← 1
+    And shape is added to w
+    And r ← ray(point(0, 5, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c = color(0.36788, 0.36788, 0.36788)
+
+Scenario Outline: Shadow rays are absorbed along their way through media
+  Given w ← world()
+    And slab ← absorbing_slab(2)
+    And slab is added to w
+    And light ← point_light(point(0, <light>, 0), color(1, 1, 1))
+    And p ← point(0, <p>, 0)
+  When t ← transmission_towards(w, p, light)
+  Then t = color(<gray>, <gray>, <gray>)
+
+  Examples:
+    | light | p  | gray    |
+    | 5     | -5 | 0.36788 |
+    | 5     | 0  | 0.60653 |
+    | 0.5   | -5 | 0.47237 |
+    | 5     | 2  | 1       |
@@ -0,0 +1,262 @@
+Feature: Bounding boxes and bounding volume hierarchies
+
+Scenario: Creating an empty bounding box
+  Given box ← bounding_box(empty)
+  Then box is empty
+
+Scenario: Adding points to an empty bounding box
+  Given box ← bounding_box(empty)
+    And p1 ← point(-5, 2, 0)
+    And p2 ← point(7, 0, -3)
+  When p1 is added to box
+    And p2 is added to box
+  Then box.min = point(-5, 0, -3)
+    And box.max = point(7, 2, 0)
+
+Scenario: A sphere has a bounding box
+  Given shape ← sphere()
+  When box ← bounds_of(shape)
+  Then box.min = point(-1, -1, -1)
+    And box.max = point(1, 1, 1)
+
+Scenario: A plane has an infinite bounding box
+  Given shape ← plane()
+  When box ← bounds_of(shape)
+  Then box is infinite
+    And box.min = point(-infinity, 0, -infinity)
+    And box.max = point(infinity, 0, infinity)
+
+Scenario: A cube has a bounding box
+  Given shape ← cube()
+  When box ← bounds_of(shape)
+  Then box.min = point(-1, -1, -1)
+    And box.max = point(1, 1, 1)
+
+Scenario: An unbounded cylinder has an infinite bounding box
+  Given shape ← cylinder()
+  When box ← bounds_of(shape)
+  Then box is infinite
+    And box.min = point(-1, -infinity, -1)
+    And box.max = point(1, infinity, 1)
+
+Scenario: A bounded cylinder has a bounding box
+  Given shape ← cylinder(-5, 3)
+  When box ← bounds_of(shape)
+  Then box.min = point(-1, -5, -1)
+    And box.max = point(1, 3, 1)
+
+Scenario: An unbounded cone has an infinite bounding box
+  Given shape ← cone()
+  When box ← bounds_of(shape)
+  Then box is infinite
+    And box.min = point(-infinity, -infinity, -infinity)
+    And box.max = point(infinity, infinity, infinity)
+
+Scenario: A bounded cone has a bounding box
+  Given shape ← cone(-5, 3)
+  When box ← bounds_of(shape)
+  Then box.min = point(-5, -5, -5)
+    And box.max = point(5, 3, 5)
+
+Scenario: A triangle has a bounding box
+  Given p1 ← point(-3, 7, 2)
+    And p2 ← point(6, 2, -4)
+    And p3 ← point(2, -1, -1)
+    And shape ← triangle(p1, p2, p3)
+  When box ← bounds_of(shape)
+  Then box.min = point(-3, -1, -4)
+    And box.max = point(6, 7, 2)
+
+Scenario: Adding one bounding box to another
+  Given box1 ← bounding_box(min=point(-5, -2, 0), max=point(7, 4, 4))
+    And box2 ← bounding_box(min=point(8, -7, -2), max=point(14, 2, 8))
+  When box2 is added to box1
+  Then box1.min = point(-5, -7, -2)
+    And box1.max = point(14, 4, 8)
+
+Scenario Outline: Checking to see if a box contains a given point
+  Given box ← bounding_box(min=point(5, -2, 0), max=point(11, 4, 7))
+    And p ← point(<x>, <y>, <z>)
+  Then box contains p is <result>
+
+  Examples:
+    | x  | y  | z  | result |
+    | 5  | -2 | 0  | true   |
+    | 11 | 4  | 7  | true   |
+    | 8  | 1  | 3  | true   |
+    | 3  | 0  | 3  | false  |
+    | 8  | -4 | 3  | false  |
+    | 8  | 1  | -1 | false  |
+    | 13 | 1  | 3  | false  |
+    | 8  | 5  | 3  | false  |
+    | 8  | 1  | 8  | false  |
+
+Scenario: Transforming a bounding box
+  Given box ← bounding_box(min=point(-1, -1, -1), max=point(1, 1, 1))
+    And matrix ← rotation_x(π/4) * rotation_y(π/4)
+  When box2 ← transform(box, matrix)
+  Then box2.min = point(-1.4142, -1.7071, -1.7071)
+    And box2.max = point(1.4142, 1.7071, 1.7071)
+
+Scenario: Transforming an infinite bounding box keeps it infinite
+  Given box ← bounding_box(min=point(-infinity, 0, -infinity), max=point(infinity, 0, infinity))
+    And matrix ← translation(1, 2, 3)
+  When box2 ← transform(box, matrix)
+  Then box2.min = point(-infinity, 2, -infinity)
+    And box2.max = point(infinity, 2, infinity)
+
+Scenario: Querying a shape's bounding box in its parent's space
+  Given shape ← sphere()
+    And set_transform(shape, translation(1, -3, 5) * scaling(0.5, 2, 4))
+  When box ← parent_space_bounds_of(shape)
+  Then box.min = point(0.5, -5, 1)
+    And box.max = point(1.5, -1, 9)
+
+Scenario: A group has a bounding box that contains its children
+  Given s ← sphere()
+    And set_transform(s, translation(2, 5, -3) * scaling(2, 2, 2))
+    And c ← cylinder(-2, 2)
+    And set_transform(c, translation(-4, -1, 4) * scaling(0.5, 1, 0.5))
+    And shape ← group()
+    And add_child(shape, s)
+    And add_child(shape, c)
+  When box ← bounds_of(shape)
+  Then box.min = point(-4.5, -3, -5)
+    And box.max = point(4, 7, 4.5)
+
+Scenario: A group's bounding box grows when a nested group gets children
+  Given shape ← group()
+    And g2 ← group()
+    And add_child(shape, g2)
+    And s ← sphere()
+    And set_transform(s, translation(10, 0, 0))
+    And add_child(g2, s)
+  When box ← bounds_of(shape)
+  Then box.min = point(9, -1, -1)
+    And box.max = point(11, 1, 1)
+
+Scenario: A CSG shape has a bounding box that contains its children
+  Given left ← sphere()
+    And right ← sphere()
+    And set_transform(right, translation(2, 3, 4))
+    And shape ← csg("difference", left, right)
+  When box ← bounds_of(shape)
+  Then box.min = point(-1, -1, -1)
+    And box.max = point(3, 4, 5)
+
+Scenario Outline: Intersecting a ray with a bounding box at the origin
+  Given box ← bounding_box(min=point(-1, -1, -1), max=point(1, 1, 1))
+    And direction ← normalize(<direction>)
+    And r ← ray(<origin>, direction)
+  Then intersects(box, r) is <result>
+
+  Examples:
+    | origin            | direction         | result |
+    | point(5, 0.5, 0)  | vector(-1, 0, 0)  | true   |
+    | point(-5, 0.5, 0) | vector(1, 0, 0)   | true   |
+    | point(0.5, 5, 0)  | vector(0, -1, 0)  | true   |
+    | point(0.5, -5, 0) | vector(0, 1, 0)   | true   |
+    | point(0.5, 0, 5)  | vector(0, 0, -1)  | true   |
+    | point(0.5, 0, -5) | vector(0, 0, 1)   | true   |
+    | point(0, 0.5, 0)  | vector(0, 0, 1)   | true   |
+    | point(0, 0, 5)    | vector(0, 0, 1)   | true   |
+    | point(-2, 0, 0)   | vector(2, 4, 6)   | false  |
+    | point(0, -2, 0)   | vector(6, 2, 4)   | false  |
+    | point(0, 0, -2)   | vector(4, 6, 2)   | false  |
+    | point(2, 0, 2)    | vector(0, 0, -1)  | false  |
+    | point(0, 2, 2)    | vector(0, -1, 0)  | false  |
+    | point(2, 2, 0)    | vector(-1, 0, 0)  | false  |
+
+Scenario: An infinite bounding box is hit by every ray
+  Given box ← bounding_box(min=point(-infinity, 0, -infinity), max=point(infinity, 0, infinity))
+    And direction ← normalize(vector(1, 1, 0))
+    And r ← ray(point(0, 5, 0), direction)
+  Then intersects(box, r) is true
+
+Scenario: A group of many shapes is split into a hierarchy
+  Given shape ← group()
+    And 64 spheres in a row are added to shape
+  Then the bvh of shape has 16 leaves or more
+    And the bvh of shape is at least 5 levels deep
+
+Scenario: Unbounded shapes are kept out of the hierarchy
+  Given shape ← group()
+    And 8 spheres in a row are added to shape
+    And p ← plane()
+    And add_child(shape, p)
+  Then the bvh of shape has 1 unbounded shapes
+    And shape is infinite
+
+Scenario: The hierarchy finds the same intersections as testing every shape
+  Given shape ← group()
+    And 64 spheres in a row are added to shape
+    And r ← ray(point(-10, 0, 0), vector(1, 0, 0))
+  When xs ← intersect(shape, r)
+  Then xs.count = 128
+    And xs are sorted by t
+
+Scenario: A ray passing between the shapes of a hierarchy misses all of them
+  Given shape ← group()
+    And 64 spheres in a row are added to shape
+    And r ← ray(point(-10, 5, 0), vector(1, 0, 0))
+  When xs ← intersect(shape, r)
+  Then xs.count = 0
+
+Scenario: A world uses the hierarchy to find intersections
+  Given w ← world()
+    And 64 spheres in a row are added to w
+    And p ← plane()
+    And set_transform(p, translation(0, -1, 0))
+    And p is added to w
+    And r ← ray(point(3, 10, 0), vector(0, -1, 0))
+  When xs ← intersect_world(w, r)
+  Then xs.count = 3
+    And xs[0].t = 9
+    And xs[1].t = 11
+    And xs[2].t = 11
+
+Scenario: Objects transformed after they were added to a world are found where they are
+  Given w ← world()
+    And s ← sphere()
+    And s is added to w
+    And set_transform(s, translation(0, 5, 0))
+    And r ← ray(point(0, 5, -5), vector(0, 0, 1))
+  When xs ← intersect_world(w, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+
+Scenario: Objects moved after the world was intersected are found where they are
+  Given w ← world()
+    And s ← sphere()
+    And s is added to w
+    And r ← ray(point(0, 5, -5), vector(0, 0, 1))
+  When xs ← intersect_world(w, r)
+    And set_transform(s, translation(0, 5, 0))
+    And xs ← intersect_world(w, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+
+Scenario: Children added to a group after the world was intersected are found
+  Given w ← world()
+    And g ← group()
+    And g is added to w
+    And s ← sphere()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When xs ← intersect_world(w, r)
+    And add_child(g, s)
+    And xs ← intersect_world(w, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+
+Scenario: A csg in a world keeps only the intersections on its surface
+  Given w ← world()
+    And left ← sphere()
+    And right ← sphere()
+    And set_transform(right, translation(0, 0, 0.5))
+    And shape ← csg("union", left, right)
+    And shape is added to w
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When xs ← intersect_world(w, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+    And xs[1].t = 6.5
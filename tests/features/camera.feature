@@ -49,3 +49,30 @@ Scenario: Rendering a world with a camera
     And c.transform ← view_transform(from, to, up)
   When image ← render(c, w)
   Then pixel_at(image, 5, 5) = color(0.38066, 0.47583, 0.2855)
+
+Scenario: Rendering on several threads gives the same image as rendering on one
+  Given w ← default_world()
+    And c ← camera(37, 23, 1.5708)
+    And from ← point(0, 0, -5)
+    And to ← point(0, 0, 0)
+    And up ← vector(0, 1, 0)
+    And c.transform ← view_transform(from, to, up)
+  When image ← render_with_threads(c, w, 4)
+    And expected ← render_single_threaded(c, w)
+  Then every pixel of image is identical to expected
+
+Scenario: Constructing a ray through the top left corner of a pixel
+  Given c ← camera(201, 101, 1.5708)
+    And p ← point(0, 0, 0)
+    And v ← vector(0.6663, 0.33481, -0.6663)
+  When r ← ray_for_pixel_offset(c, 0, 0, 0, 0)
+  Then r.origin = p
+    And r.direction = v
+
+Scenario: An offset in the middle of the pixel gives the ray through its center
+  Given c ← camera(201, 101, 1.5708)
+    And p ← point(0, 0, 0)
+    And v ← vector(0.66519, 0.33259, -0.66851)
+  When r ← ray_for_pixel_offset(c, 0, 0, 0.5, 0.5)
+  Then r.origin = p
+    And r.direction = v
@@ -38,3 +38,114 @@ Scenario: PPM files are terminated by a newline character
   Given c ← canvas(5, 3)
   When ppm ← canvas_to_ppm(c)
   Then ppm ends with a newline character
+
+Scenario: Reading a file with the wrong magic number
+  Given ppm ← a file containing:
+    """
+    P32
+    1 1
+    255
+    0 0 0
+    """
+  Then canvas_from_ppm(ppm) should fail
+
+Scenario: Reading a PPM returns a canvas of the right size
+  Given ppm ← a file containing:
+    """
+    P3
+    10 2
+    255
+    0 0 0  0 0 0  0 0 0  0 0 0  0 0 0
+    0 0 0  0 0 0  0 0 0  0 0 0  0 0 0
+    0 0 0  0 0 0  0 0 0  0 0 0  0 0 0
+    0 0 0  0 0 0  0 0 0  0 0 0  0 0 0
+    """
+  When c ← canvas_from_ppm(ppm)
+  Then c.width = 10
+    And c.height = 2
+
+Scenario Outline: Reading pixel data from a PPM file
+  Given ppm ← a file containing:
+    """
+    P3
+    4 3
+    255
+    255 127 0  0 127 255  127 255 0  255 255 255
+    0 0 0  255 0 0  0 255 0  0 0 255
+    255 255 0  0 255 255  255 0 255  127 127 127
+    """
+    And color ← color(<color>)
+  When c ← canvas_from_ppm(ppm)
+  Then pixel_at(c, <x>, <y>) = color
+
+  Examples:
+    | x | y | color                    |
+    | 0 | 0 | 1, 0.49804, 0           |
+    | 1 | 0 | 0, 0.49804, 1           |
+    | 2 | 0 | 0.49804, 1, 0           |
+    | 3 | 0 | 1, 1, 1                 |
+    | 0 | 1 | 0, 0, 0                 |
+    | 1 | 1 | 1, 0, 0                 |
+    | 2 | 1 | 0, 1, 0                 |
+    | 3 | 1 | 0, 0, 1                 |
+    | 0 | 2 | 1, 1, 0                 |
+    | 1 | 2 | 0, 1, 1                 |
+    | 2 | 2 | 1, 0, 1                 |
+    | 3 | 2 | 0.49804, 0.49804, 0.49804 |
+
+Scenario: PPM parsing ignores comment lines
+  Given ppm ← a file containing:
+    """
+    P3
+    # this is a comment
+    2 1
+    # this, too
+    255
+    # another comment
+    255 255 255
+    # oh, no, comments in the pixel data!
+    255 0 255
+    """
+    And white ← color(1, 1, 1)
+    And magenta ← color(1, 0, 1)
+  When c ← canvas_from_ppm(ppm)
+  Then pixel_at(c, 0, 0) = white
+    And pixel_at(c, 1, 0) = magenta
+
+Scenario: PPM parsing allows an RGB triple to span lines
+  Given ppm ← a file containing:
+    """
+    P3
+    1 1
+    255
+    51
+    153
+
+    204
+    """
+    And color ← color(0.2, 0.6, 0.8)
+  When c ← canvas_from_ppm(ppm)
+  Then pixel_at(c, 0, 0) = color
+
+Scenario: PPM parsing respects the scale setting
+  Given ppm ← a file containing:
+    """
+    P3
+    2 2
+    100
+    100 100 100  50 50 50
+    75 50 25  0 0 0
+    """
+    And color ← color(0.75, 0.5, 0.25)
+  When c ← canvas_from_ppm(ppm)
+  Then pixel_at(c, 0, 1) = color
+
+Scenario: Reading a PPM file with missing pixel data
+  Given ppm ← a file containing:
+    """
+    P3
+    2 1
+    255
+    255 255 255
+    """
+  Then canvas_from_ppm(ppm) should fail
@@ -0,0 +1,133 @@
+Feature: Constructive Solid Geometry (CSG)
+
+Scenario: CSG is created with an operation and two shapes
+  Given s1 ← sphere()
+    And s2 ← cube()
+  When c ← csg("union", s1, s2)
+  Then c.operation = "union"
+    And c.left = s1
+    And c.right = s2
+    And s1.parent = c
+    And s2.parent = c
+
+Scenario Outline: Evaluating the rule for a CSG operation
+  When result ← intersection_allowed("<op>", <lhit>, <inl>, <inr>)
+  Then result = <result>
+
+  Examples:
+    | op           | lhit  | inl   | inr   | result |
+    | union        | true  | true  | true  | false  |
+    | union        | true  | true  | false | true   |
+    | union        | true  | false | true  | false  |
+    | union        | true  | false | false | true   |
+    | union        | false | true  | true  | false  |
+    | union        | false | true  | false | false  |
+    | union        | false | false | true  | true   |
+    | union        | false | false | false | true   |
+    | intersection | true  | true  | true  | true   |
+    | intersection | true  | true  | false | false  |
+    | intersection | true  | false | true  | true   |
+    | intersection | true  | false | false | false  |
+    | intersection | false | true  | true  | true   |
+    | intersection | false | true  | false | true   |
+    | intersection | false | false | true  | false  |
+    | intersection | false | false | false | false  |
+    | difference   | true  | true  | true  | false  |
+    | difference   | true  | true  | false | true   |
+    | difference   | true  | false | true  | false  |
+    | difference   | true  | false | false | true   |
+    | difference   | false | true  | true  | true   |
+    | difference   | false | true  | false | true   |
+    | difference   | false | false | true  | false  |
+    | difference   | false | false | false | false  |
+
+Scenario Outline: Filtering a list of intersections
+  Given s1 ← sphere()
+    And s2 ← cube()
+    And c ← csg("<operation>", s1, s2)
+    And xs ← intersections(1:s1, 2:s2, 3:s1, 4:s2)
+  When result ← filter_intersections(c, xs)
+  Then result.count = 2
+    And result[0] = xs[<x0>]
+    And result[1] = xs[<x1>]
+
+  Examples:
+    | operation    | x0 | x1 |
+    | union        | 0  | 3  |
+    | intersection | 1  | 2  |
+    | difference   | 0  | 1  |
+
+Scenario: Filtering intersections of a CSG with a group as its left child
+  Given s1 ← sphere()
+    And g ← group()
+    And add_child(g, s1)
+    And s2 ← cube()
+    And c ← csg("difference", g, s2)
+    And xs ← intersections(1:s1, 2:s2, 3:s1, 4:s2)
+  When result ← filter_intersections(c, xs)
+  Then result.count = 2
+    And result[0] = xs[0]
+    And result[1] = xs[1]
+
+Scenario: A ray misses a CSG object
+  Given s1 ← sphere()
+    And s2 ← cube()
+    And c ← csg("union", s1, s2)
+    And pr ← point(0, 2, -5)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+  When xs ← local_intersect(c, r)
+  Then xs.count = 0
+
+Scenario: A ray hits a CSG object
+  Given s1 ← sphere()
+    And s2 ← sphere()
+    And t2 ← translation(0, 0, 0.5)
+    And set_transform(s2, t2)
+    And c ← csg("union", s1, s2)
+    And pr ← point(0, 0, -5)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+  When xs ← local_intersect(c, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+    And xs[0].object = s1
+    And xs[1].t = 6.5
+    And xs[1].object = s2
+
+Scenario: A ray hits a nested CSG object
+  Given s1 ← sphere()
+    And s2 ← sphere()
+    And t2 ← translation(0, 0, 0.5)
+    And set_transform(s2, t2)
+    And inner ← csg("union", s1, s2)
+    And s3 ← cube()
+    And t3 ← translation(0, 0, 2)
+    And set_transform(s3, t3)
+    And c ← csg("difference", inner, s3)
+    And pr ← point(0, 0, -5)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+  When xs ← intersect(c, r)
+  Then xs.count = 2
+    And xs[0].t = 4
+    And xs[0].object = s1
+    And xs[1].t = 6
+    And xs[1].object = s3
+
+Scenario: Refractive indices at the surfaces of a glass CSG
+  Given s1 ← glass_sphere()
+    And s2 ← glass_sphere()
+    And t2 ← translation(0, 0, 0.5)
+    And set_transform(s2, t2)
+    And c ← csg("union", s1, s2)
+    And pr ← point(0, 0, -5)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+    And xs ← intersect(c, r)
+  When comps ← prepare_computations(xs[0], r, xs)
+  Then comps.n1 = 1
+    And comps.n2 = 1.5
+  When comps ← prepare_computations(xs[1], r, xs)
+  Then comps.n1 = 1.5
+    And comps.n2 = 1
@@ -0,0 +1,73 @@
+Feature: Depth of field
+
+Scenario: Cameras are pinholes by default
+  Given c ← camera(160, 120, 1.5708)
+  Then c.aperture_radius = 0
+    And c.focal_distance = 1
+    And c.aperture = circle
+
+Scenario: A pinhole camera ignores the point on the lens
+  Given c ← camera(201, 101, 1.5708)
+  When r ← ray_for_pixel_lens(c, 0, 0, 0.5, 0.5, 0.3, 0.8)
+  Then r.origin = point(0, 0, 0)
+    And r.direction = vector(0.66519, 0.33259, -0.66851)
+
+Scenario: The center of the lens gives the pinhole ray
+  Given c ← camera(201, 101, 1.5708)
+    And c.aperture_radius ← 0.5
+    And c.focal_distance ← 5
+  When r ← ray_for_pixel_lens(c, 0, 0, 0.5, 0.5, 0, 0)
+  Then r.origin = point(0, 0, 0)
+    And r.direction = vector(0.66519, 0.33259, -0.66851)
+
+Scenario: A ray from the edge of the lens
+  Given c ← camera(201, 101, 1.5708)
+    And c.aperture_radius ← 0.5
+    And c.focal_distance ← 5
+  When r ← ray_for_pixel_lens(c, 100, 50, 0.5, 0.5, 1, 0)
+  Then r.origin = point(0.5, 0, 0)
+    And r.direction = vector(-0.09950, 0, -0.99504)
+
+Scenario Outline: Rays through a pixel start on the lens and meet on the focal plane
+  Given c ← camera(201, 101, 1.5708)
+    And c.transform ← rotation_y(π/4) * translation(0, -2, 5)
+    And c.aperture_radius ← 0.5
+    And c.focal_distance ← <distance>
+    And c.aperture = <aperture>
+  When rays ← 100 rays for pixel 20, 70 with random points on the lens
+  Then every ray starts within 0.5 of point(0, 2, -5)
+    And every ray passes through the pinhole ray through pixel 20, 70 at distance <distance>
+
+  Examples:
+    | distance | aperture   |
+    | 3        | circle     |
+    | 8        | circle     |
+    | 8        | polygon(6) |
+
+Scenario Outline: Points on the aperture lie inside of its shape
+  Given aperture ← <aperture>
+  When points ← 500 samples of aperture
+  Then every point lies inside of <aperture>
+    And the points cover the corners of <aperture>
+
+  Examples:
+    | aperture   |
+    | circle     |
+    | polygon(3) |
+    | polygon(5) |
+    | polygon(8) |
+
+Scenario Outline: Only objects at the focal distance are sharp
+  Given w ← world()
+    And the emissive sphere at point(0, 0, -10)
+    And c ← camera(21, 21, 0.5)
+    And c.aperture_radius ← 0.5
+    And c.focal_distance ← <distance>
+  When image ← render_with_settings(c, w, jittered, 6)
+  Then pixel_at(image, 10, 10) is <center>
+    And pixel_at(image, 10, 16) is <outside>
+
+  Examples:
+    | distance | center | outside |
+    | 10       | white  | black   |
+    | 3        | gray   | gray    |
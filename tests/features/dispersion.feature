@@ -0,0 +1,95 @@
+Feature: Dispersion
+
+Scenario: Materials don't disperse light by default
+  Given m ← material()
+    And m.refractive_index ← 1.5
+  Then m.dispersion is none
+    And channel_indices(m) = 1.5, 1.5, 1.5
+
+Scenario: The Cauchy equation bends blue light more than red
+  Given m ← material()
+    And m.dispersion ← cauchy(1.5046, 0.0042)
+  Then channel_indices(m) = 1.51454, 1.51848, 1.52534
+
+Scenario: The Sellmeier equation for BK7 glass
+  Given m ← material()
+    And m.dispersion ← sellmeier(1.03961212, 0.231792344, 1.01046945, 0.00600069867, 0.0200179144, 103.560653)
+  Then channel_indices(m) = 1.51452, 1.51852, 1.52532
+
+Scenario: Glass without dispersion takes the single index path
+  Given shape ← glass_sphere()
+    And r ← ray(point(0, 0.5, -5), vector(0, 0, 1))
+  When comps ← the first hit of r on shape
+  Then comps.channel_indices is none
+
+Scenario: Entering a dispersive medium gives every channel its own index
+  Given shape ← glass_sphere()
+    And shape.material.dispersion ← cauchy(1.5046, 0.0042)
+    And r ← ray(point(0, 0.5, -5), vector(0, 0, 1))
+  When comps ← the first hit of r on shape
+  Then comps.n1 channels = 1, 1, 1
+    And comps.n2 channels = 1.51454, 1.51848, 1.52534
+
+Scenario: Leaving a dispersive medium
+  Given shape ← glass_sphere()
+    And shape.material.dispersion ← cauchy(1.5046, 0.0042)
+    And r ← ray(point(0, 0.5, 0), vector(0, 0, 1))
+  When comps ← the first hit of r on shape
+  Then comps.n1 channels = 1.51454, 1.51848, 1.52534
+    And comps.n2 channels = 1, 1, 1
+
+Scenario: Blue light is refracted closer to the normal than red light
+  Given shape ← glass_sphere()
+    And shape.material.dispersion ← cauchy(1.45, 0.03)
+    And r ← ray(point(0, 0.5, -5), vector(0, 0, 1))
+  When comps ← the first hit of r on shape
+  Then the blue channel is refracted closer to the normal than the red channel
+
+Scenario: Schlick takes the index of the channel
+  Given shape ← glass_sphere()
+    And shape.material.dispersion ← cauchy(1.45, 0.03)
+    And r ← ray(point(0, 0.5, -5), vector(0, 0, 1))
+  When comps ← the first hit of r on shape
+  Then schlick_with(comps, 1, 1.5) = schlick(comps)
+    And the blue channel reflects more than the red channel
+
+Scenario Outline: A prism splits white light into a spectrum
+  Given w ← world()
+    And prism ← prism(<dispersion>)
+    And prism is added to w
+    And the emissive spot at point(6, <y>, 0)
+    And r ← ray(point(-3, -1.2, 0), vector(0.94386, 0.33035, 0))
+  When c ← color_at(w, r)
+  Then c = <color>
+
+  Examples:
+    | dispersion          | y        | color           |
+    | cauchy(1.45, 0.03)  | -2.29693 | color(1, 0, 0)  |
+    | cauchy(1.45, 0.03)  | -2.57463 | color(0, 1, 0)  |
+    | cauchy(1.45, 0.03)  | -3.11868 | color(0, 0, 1)  |
+    | none                | -2.57463 | color(1, 1, 1)  |
+
+Scenario Outline: A path limited to a channel only follows the ray of that channel
+  Given w ← world()
+    And prism ← prism(cauchy(1.45, 0.03))
+    And prism is added to w
+    And the emissive spot at point(6, <y>, 0)
+    And r ← ray(point(-3, -1.2, 0), vector(0.94386, 0.33035, 0))
+  When c ← trace(w, r) in channel <channel>
+  Then c = <color>
+
+  Examples:
+    | y        | channel | color           |
+    | -2.29693 | 0       | color(1, 0, 0)  |
+    | -3.11868 | 2       | color(0, 0, 1)  |
+    | -3.11868 | 0       | color(0, 0, 0)  |
+
+Scenario: The path tracer splits white light as well
+  Given w ← world()
+    And prism ← prism(cauchy(1.45, 0.03))
+    And prism is added to w
+    And the emissive spot at point(6, -3.11868, 0)
+    And pt ← path_tracer(8)
+  When c ← the average of 300 paths along ray(point(-3, -1.2, 0), vector(0.94386, 0.33035, 0))
+  Then c has no red and no green
+    And the blue of c is between 0.8 and 1.2
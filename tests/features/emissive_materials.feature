@@ -0,0 +1,84 @@
+Feature: Emissive materials
+
+Scenario: The default material does not glow
+  Given m ← material()
+  Then m.emission = color(0, 0, 0)
+    And m.emission_strength = 1
+
+Scenario: The emitted light is the emission color times its strength
+  Given m ← material()
+    And m.emission ← color(1, 0.5, 0)
+    And m.emission_strength ← 4
+  Then emitted(m) = color(4, 2, 0)
+
+Scenario: An emissive surface glows without any lights
+  Given w ← world()
+    And shape ← sphere()
+    And shape.material.ambient ← 0
+    And shape.material.diffuse ← 0
+    And shape.material.emission ← color(1, 0.5, 0.25)
+    And shape is added to w
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← color_at(w, r)
+  Then c = color(1, 0.5, 0.25)
+
+Scenario: Emission is added to the light the surface reflects
+  Given w ← world()
+    And w.light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And shape ← sphere()
+    And shape.material.emission ← color(0.5, 0.5, 0.5)
+    And shape is added to w
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← color_at(w, r)
+  Then c = color(2.4, 2.4, 2.4)
+
+Scenario: Emission does not depend on shadows
+  Given w ← world()
+    And w.light ← point_light(point(0, 10, 3), color(1, 1, 1))
+    And shape ← sphere()
+    And shape.transform ← translation(0, 5, 3)
+    And shape is added to w
+    And shape ← sphere()
+    And shape.transform ← translation(0, 0, 3)
+    And shape.material.emission ← color(0.5, 0.5, 0.5)
+    And shape is added to w
+    And r ← ray(point(-5, 0.8, 3), vector(1, 0, 0))
+  When c ← color_at(w, r)
+  Then c = color(0.6, 0.6, 0.6)
+
+Scenario: A mirror reflects an emissive surface
+  Given w ← world()
+    And shape ← plane()
+    And shape.material.ambient ← 0
+    And shape.material.diffuse ← 0
+    And shape.material.reflective ← 1
+    And shape is added to w
+    And shape ← sphere()
+    And shape.transform ← translation(0, 2, 0)
+    And shape.material.ambient ← 0
+    And shape.material.diffuse ← 0
+    And shape.material.emission ← color(1, 1, 1)
+    And shape.material.emission_strength ← 2
+    And shape is added to w
+    And r ← ray(point(0, 2, -4), vector(0, -0.70711, 0.70711))
+  When c ← color_at(w, r)
+  Then c = color(2, 2, 2)
+
+Scenario: An emissive surface lights its surroundings with the path tracer
+  Given w ← world()
+    And shape ← plane()
+    And shape.material.ambient ← 0
+    And shape.material.diffuse ← 0.5
+    And shape.material.specular ← 0
+    And shape is added to w
+    And shape ← plane()
+    And shape.transform ← translation(0, 2, 0)
+    And shape.material.ambient ← 0
+    And shape.material.diffuse ← 0
+    And shape.material.emission ← color(1, 1, 1)
+    And shape.material.emission_strength ← 3
+    And shape is added to w
+    And pt ← path_tracer(1)
+    And r ← ray(point(0, 1, 0), vector(0, -1, 0))
+  When c ← path_color_at(pt, w, r)
+  Then c = color(1.5, 1.5, 1.5)
@@ -0,0 +1,100 @@
+Feature: Glossy reflection and frosted refraction
+
+Scenario: Materials are perfectly smooth by default
+  Given m ← material()
+  Then m.roughness = 0
+    And m.glossy_samples = 16
+
+Scenario: A smooth mirror reflects a single perfect ray
+  Given w ← default_world()
+    And shape ← plane()
+    And shape.material.reflective ← 0.5
+    And shape.transform ← translation(0, -1, 0)
+    And shape is added to w
+    And r ← ray(point(0, 0, -3), vector(0, -0.70711, 0.70711))
+  When c ← color_at(w, r)
+  Then c = color(0.87677, 0.92436, 0.82918)
+
+Scenario Outline: Perturbed directions stay in the lobe and above the surface
+  Given d ← vector(<dx>, <dy>, <dz>)
+    And side ← vector(0, 1, 0)
+  When directions ← 200 perturbations of d on side with roughness <roughness>
+  Then every direction is normalized
+    And every direction is on the side of side
+    And every direction is within <cos> of d
+
+  Examples:
+    | dx | dy   | dz | roughness | cos     |
+    | 0  | 1    | 0  | 0.3       | 0.95393 |
+    | 0  | 1    | 0  | 0.05      | 0.99874 |
+    | 1  | 0.05 | 0  | 0.5       | -1      |
+
+Scenario Outline: A rough mirror blurs what it reflects
+  Given w ← world()
+    And the emissive sphere above the mirror
+    And mirror ← plane()
+    And mirror.material.reflective ← 1
+    And mirror.material.roughness ← <roughness>
+    And mirror is added to w
+    And r ← ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882))
+  When c ← color_at(w, r)
+  Then c is <brightness>
+
+  Examples:
+    | roughness | brightness        |
+    | 0         | white             |
+    | 0.5       | between gray 0.05 and gray 0.95 |
+
+Scenario: More samples give a less noisy blurred reflection
+  Given w ← world()
+    And the emissive sphere above the mirror
+    And mirror ← plane()
+    And mirror.material.reflective ← 1
+    And mirror.material.roughness ← 0.5
+    And mirror is added to w
+  When mirror.material.glossy_samples ← 4
+    And spread ← the spread of 10 rays next to ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882))
+    And mirror.material.glossy_samples ← 256
+    And spread2 ← the spread of 10 rays next to ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882))
+  Then spread2 < spread / 2
+
+Scenario: Blurred reflections take all samples with fewer bounces as well
+  Given w ← world()
+    And the emissive sphere above the mirror
+    And mirror ← plane()
+    And mirror.material.reflective ← 1
+    And mirror.material.roughness ← 0.5
+    And mirror is added to w
+  When mirror.material.glossy_samples ← 4
+    And spread ← the spread of 10 rays next to ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882)) with max depth 2
+    And mirror.material.glossy_samples ← 256
+    And spread2 ← the spread of 10 rays next to ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882)) with max depth 2
+  Then spread2 < spread / 2
+
+Scenario Outline: Frosted glass blurs what is seen through it
+  Given w ← world()
+    And the emissive sphere below the pane
+    And pane ← glass_pane()
+    And pane.material.roughness ← <roughness>
+    And pane is added to w
+    And r ← ray(point(0, 5, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c is <brightness>
+
+  Examples:
+    | roughness | brightness                      |
+    | 0         | white                           |
+    | 0.6       | between gray 0.05 and gray 0.95 |
+
+Scenario: The path tracer blurs reflections of rough mirrors as well
+  Given w ← world()
+    And the emissive sphere above the mirror
+    And mirror ← plane()
+    And mirror.material.ambient ← 0
+    And mirror.material.diffuse ← 0
+    And mirror.material.reflective ← 1
+    And mirror.material.roughness ← 0.5
+    And mirror is added to w
+    And pt ← path_tracer(5)
+  When c ← the average of 400 paths along ray(point(0, 1.93185, -0.51764), vector(0, -0.96593, 0.25882))
+  Then c is between gray 0.05 and gray 0.95
@@ -0,0 +1,123 @@
+Feature: Groups
+
+Scenario: Creating a new group
+  Given g ← group()
+  Then g.transform = identity_matrix
+    And g is empty
+
+Scenario: A shape has a parent attribute
+  Given s ← sphere()
+  Then s.parent is nothing
+
+Scenario: Adding a child to a group
+  Given g ← group()
+    And s ← sphere()
+  When add_child(g, s)
+  Then g is not empty
+    And g includes s
+    And s.parent = g
+
+Scenario: Intersecting a ray with an empty group
+  Given g ← group()
+    And pr ← point(0, 0, 0)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+  When xs ← local_intersect(g, r)
+  Then xs.count = 0
+
+Scenario: Intersecting a ray with a nonempty group
+  Given g ← group()
+    And s1 ← sphere()
+    And s2 ← sphere()
+    And t2 ← translation(0, 0, -3)
+    And set_transform(s2, t2)
+    And s3 ← sphere()
+    And t3 ← translation(5, 0, 0)
+    And set_transform(s3, t3)
+    And add_child(g, s1)
+    And add_child(g, s2)
+    And add_child(g, s3)
+  When pr ← point(0, 0, -5)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+    And xs ← local_intersect(g, r)
+  Then xs.count = 4
+    And xs[0].object = s2
+    And xs[1].object = s2
+    And xs[2].object = s1
+    And xs[3].object = s1
+
+Scenario: Intersecting a transformed group
+  Given g ← group()
+    And tg ← scaling(2, 2, 2)
+    And set_transform(g, tg)
+    And s ← sphere()
+    And ts ← translation(5, 0, 0)
+    And set_transform(s, ts)
+    And add_child(g, s)
+  When pr ← point(10, 0, -10)
+    And vr ← vector(0, 0, 1)
+    And r ← ray(pr, vr)
+    And xs ← intersect(g, r)
+  Then xs.count = 2
+
+Scenario: Converting a point from world to object space
+  Given g1 ← group()
+    And t1 ← rotation_y(π/2)
+    And set_transform(g1, t1)
+    And g2 ← group()
+    And t2 ← scaling(2, 2, 2)
+    And set_transform(g2, t2)
+    And add_child(g1, g2)
+    And s ← sphere()
+    And ts ← translation(5, 0, 0)
+    And set_transform(s, ts)
+    And add_child(g2, s)
+    And pw ← point(-2, 0, -10)
+  When p ← world_to_object(s, pw)
+  Then p = point(0, 0, -1)
+
+Scenario: Converting a normal from object to world space
+  Given g1 ← group()
+    And t1 ← rotation_y(π/2)
+    And set_transform(g1, t1)
+    And g2 ← group()
+    And t2 ← scaling(1, 2, 3)
+    And set_transform(g2, t2)
+    And add_child(g1, g2)
+    And s ← sphere()
+    And ts ← translation(5, 0, 0)
+    And set_transform(s, ts)
+    And add_child(g2, s)
+    And v ← vector(0.57735, 0.57735, 0.57735)
+  When n ← normal_to_world(s, v)
+  Then n = vector(0.2857, 0.4286, -0.8571)
+
+Scenario: Finding the normal on a child object
+  Given g1 ← group()
+    And t1 ← rotation_y(π/2)
+    And set_transform(g1, t1)
+    And g2 ← group()
+    And t2 ← scaling(1, 2, 3)
+    And set_transform(g2, t2)
+    And add_child(g1, g2)
+    And s ← sphere()
+    And ts ← translation(5, 0, 0)
+    And set_transform(s, ts)
+    And add_child(g2, s)
+    And pw ← point(1.7321, 1.1547, -5.5774)
+  When n ← normal_at(s, pw)
+  Then n = vector(0.2857, 0.4286, -0.8571)
+
+Scenario: A pattern on a child object respects the group transformation
+  Given g ← group()
+    And tg ← scaling(2, 2, 2)
+    And set_transform(g, tg)
+    And s ← sphere()
+    And ts ← translation(0.5, 1, 1.5)
+    And set_transform(s, ts)
+    And add_child(g, s)
+    And pattern ← test_pattern()
+    And pw ← point(2.5, 3, 3.5)
+  When c ← color_at_object(pattern, s, pw)
+  Then c = color(0.75, 0.5, 0.25)
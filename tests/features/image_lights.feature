@@ -0,0 +1,99 @@
+Feature: Image based lighting
+
+Scenario: Reading a flat HDR file
+  Given data ← an hdr file of 2x1 pixels with rgbe(128, 64, 0, 129)
+  When image ← canvas_from_hdr(data)
+  Then image.width = 2
+    And image.height = 1
+    And pixel_at(image, 1, 0) = color(1, 0.5, 0)
+
+Scenario: Reading a run length encoded HDR file
+  Given data ← a run length encoded hdr file of 8x2 pixels with rgbe(128, 128, 128, 128)
+  When image ← canvas_from_hdr(data)
+  Then image.width = 8
+    And image.height = 2
+    And pixel_at(image, 0, 0) = color(0.5, 0.5, 0.5)
+    And pixel_at(image, 7, 1) = color(0.5, 0.5, 0.5)
+
+Scenario: A zero exponent is black
+  Given data ← an hdr file of 1x1 pixels with rgbe(200, 200, 200, 0)
+  When image ← canvas_from_hdr(data)
+  Then pixel_at(image, 0, 0) = color(0, 0, 0)
+
+Scenario: A HDR file needs the radiance header
+  Given data ← a file without hdr header
+  When image ← canvas_from_hdr(data)
+  Then reading the image fails
+
+Scenario: Missing pixel data is an error
+  Given data ← a truncated hdr file of 4x4 pixels
+  When image ← canvas_from_hdr(data)
+  Then reading the image fails
+
+Scenario Outline: Mapping a point of an equirectangular image onto a direction
+  When d ← uv_to_direction(<u>, <v>)
+  Then d = vector(<x>, <y>, <z>)
+    And direction_to_uv(d) = (<u>, <v>)
+
+  Examples:
+    | u    | v    | x  | y       | z        |
+    | 0.5  | 0.5  | 0  | 0       | -1       |
+    | 0.75 | 0.5  | 1  | 0       | 0        |
+    | 0.25 | 0.5  | -1 | 0       | 0        |
+    | 1    | 0.5  | 0  | 0       | 1        |
+    | 0.5  | 0.25 | 0  | 0.70711 | -0.70711 |
+
+Scenario: A uniform environment shines with its color
+  Given map ← canvas(16, 8) filled with color(1, 1, 1)
+    And light ← image_light(map, 16)
+  Then light.intensity = color(1, 1, 1)
+    And light has 16 samples at point(0, 0, 0)
+    And every sample of light at point(0, 0, 0) is infinitely far away
+
+Scenario: Lighting with a uniform environment adds up to the diffuse reflectance
+  Given map ← canvas(16, 8) filled with color(1, 1, 1)
+    And light ← image_light(map, 1024)
+    And m ← material()
+    And m.specular ← 0
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result is close to color(1, 1, 1)
+
+Scenario: Samples come from the bright parts of the environment
+  Given map ← canvas(8, 4) filled with color(0, 0, 0)
+    And pixel (2, 1) of map ← color(10, 10, 10)
+    And light ← image_light(map, 32)
+  Then every sample of light at point(0, 0, 0) comes from pixel (2, 1)
+    And direction_to_light(light, point(0, 0, 0)) = vector(-0.85355, 0.38268, -0.35355)
+
+Scenario: A black environment gives no light
+  Given map ← canvas(8, 4) filled with color(0, 0, 0)
+    And light ← image_light(map, 32)
+  Then light.intensity = color(0, 0, 0)
+    And light has 0 samples at point(0, 0, 0)
+
+Scenario: Objects shadow the environment
+  Given w ← world()
+    And map ← canvas(4, 2) filled with color(0, 0, 0)
+    And the top row of map is color(1, 1, 1)
+    And w.environment ← map with 32 samples
+    And pt ← point(0, 0, 0)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(1, 1, 1)
+  Given shape ← plane()
+    And shape.transform ← translation(0, 1, 0)
+    And shape is added to w
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(0, 0, 0)
+
+Scenario: Rays that miss see the environment map
+  Given w ← world()
+    And map ← canvas(4, 2) filled with color(0, 0, 0)
+    And the top row of map is color(1, 1, 1)
+    And w.environment ← map with 32 samples
+    And r ← ray(point(0, 0, 0), vector(0, 1, 0))
+  When c ← color_at(w, r)
+  Then c = color(1, 1, 1)
+    And w has 1 light
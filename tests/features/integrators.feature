@@ -0,0 +1,136 @@
+Feature: Integrators and debug views
+
+Scenario: Rendering uses the whitted integrator by default
+  Given settings ← render_settings()
+  Then settings.integrator is Whitted
+
+Scenario: The whitted integrator shades like color_at
+  Given w ← default_world()
+    And integrator ← whitted()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.38066, 0.47583, 0.2855)
+
+Scenario Outline: The normal view shows the outward normal of the surface
+  Given w ← default_world()
+    And integrator ← normal_view()
+    And r ← ray(point(<ox>, <oy>, <oz>), vector(<dx>, <dy>, <dz>))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(<r>, <g>, <b>)
+
+  Examples:
+    | ox | oy | oz | dx | dy | dz | r   | g   | b   |
+    | 0  | 0  | -5 | 0  | 0  | 1  | 0.5 | 0.5 | 0   |
+    | 0  | 5  | 0  | 0  | -1 | 0  | 0.5 | 1   | 0.5 |
+    | 0  | 0  | 0  | 0  | 0  | 1  | 0.5 | 0.5 | 1   |
+    | 0  | 0  | -5 | 0  | 1  | 0  | 0   | 0   | 0   |
+
+Scenario Outline: The depth view fades from white to black with the distance
+  Given w ← default_world()
+    And integrator ← depth_view(<near>, <far>)
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(<gray>, <gray>, <gray>)
+
+  Examples:
+    | near | far | gray |
+    | 0    | 10  | 0.6  |
+    | 4    | 8   | 1    |
+    | 0    | 2   | 0    |
+
+Scenario: The albedo view shows the unlit color of the material
+  Given w ← default_world()
+    And integrator ← albedo_view()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.8, 1, 0.6)
+
+Scenario: The albedo view shows the background where rays miss
+  Given w ← default_world()
+    And w.background ← solid(color(0.2, 0.3, 0.4))
+    And integrator ← albedo_view()
+    And r ← ray(point(0, 0, -5), vector(0, 1, 0))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.2, 0.3, 0.4)
+
+Scenario: The uv view maps spheres by latitude and longitude
+  Given w ← default_world()
+    And integrator ← uv_view()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.5, 0.5, 0)
+
+Scenario: The uv view shows the barycentric coordinates of triangles
+  Given w ← world()
+    And shape ← triangle(point(0, 1, 0), point(-1, 0, 0), point(1, 0, 0))
+    And shape is added to w
+    And integrator ← uv_view()
+    And r ← ray(point(-0.2, 0.3, -2), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.45, 0.25, 0)
+
+Scenario: Every object in the world has its own color in the object id view
+  Then id_color(0) = color(1, 0, 0)
+    And id_color(2) = color(0.58359, 1, 0)
+    And the id colors of 0 to 10 are all different
+
+Scenario: The children of a group share the id of the group
+  Given w ← default_world()
+    And shape ← sphere()
+    And shape.transform ← translation(5, 0, 0)
+    And g ← group() containing shape
+    And g is added to w
+    And integrator ← object_id_view()
+    And r ← ray(point(5, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = id_color(2)
+
+Scenario: The object id view is black where rays miss
+  Given w ← default_world()
+    And integrator ← object_id_view()
+    And r ← ray(point(0, 0, -5), vector(0, 1, 0))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0, 0, 0)
+
+Scenario: An opaque surface has no bounces
+  Given w ← default_world()
+    And integrator ← bounce_view()
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0, 0, 0)
+
+Scenario: A mirror adds a bounce
+  Given w ← world()
+    And shape ← plane()
+    And shape.transform ← translation(0, -1, 0)
+    And shape.material.reflective ← 0.5
+    And shape is added to w
+    And integrator ← bounce_view()
+    And r ← ray(point(0, 0, -3), vector(0, -0.70711, 0.70711))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(0.2, 0.2, 0.2)
+
+Scenario: Two facing mirrors bounce until the maximum depth
+  Given w ← world()
+    And shape ← plane()
+    And shape.transform ← translation(0, -1, 0)
+    And shape.material.reflective ← 1
+    And shape is added to w
+    And shape ← plane()
+    And shape.transform ← translation(0, 1, 0)
+    And shape.material.reflective ← 1
+    And shape is added to w
+    And integrator ← bounce_view()
+    And r ← ray(point(0, 0, 0), vector(0, -0.70711, 0.70711))
+  When c ← integrator_color_at(integrator, w, r)
+  Then c = color(1, 1, 1)
+
+Scenario: Rendering with a debug view
+  Given w ← default_world()
+    And c ← camera(11, 11, 1.5708)
+    And c.transform ← view_transform(point(0, 0, -5), point(0, 0, 0), vector(0, 1, 0))
+    And settings ← render_settings()
+    And settings.integrator ← normal_view()
+  When image ← render(c, w, settings)
+  Then pixel_at(image, 5, 5) = color(0.5, 0.5, 0)
+    And pixel_at(image, 0, 0) = color(0, 0, 0)
@@ -6,3 +6,126 @@ Scenario: A point light has a position and intensity
   When light ← point_light(position, intensity)
   Then light.position = position
     And light.intensity = intensity
+
+Scenario: A spot light has a position, a direction and two cone angles
+  Given position ← point(0, 10, 0)
+    And direction ← vector(0, -2, 0)
+    And intensity ← color(1, 1, 1)
+  When light ← spot_light(position, direction, 0.3, 0.5, intensity)
+  Then light.position = position
+    And light.direction = vector(0, -1, 0)
+    And light.inner_angle = 0.3
+    And light.outer_angle = 0.5
+    And light.intensity = intensity
+
+Scenario: The direction and distance to a spot light
+  Given light ← spot_light(point(0, 10, 0), vector(0, -1, 0), 0.3, 0.5, color(1, 1, 1))
+    And pt ← point(0, 0, 0)
+  Then direction_to_light(light, pt) = vector(0, 1, 0)
+    And distance_to_light(light, pt) = 10
+
+Scenario Outline: A spot light fades out between its inner and outer cone
+  Given light ← spot_light(point(0, 10, 0), vector(0, -1, 0), 0.3, 0.5, color(1, 1, 1))
+    And pt ← point(<x>, 0, 0)
+  Then intensity_at(light, pt) = color(<i>, <i>, <i>)
+
+  Examples:
+    | x         | i       |
+    | 0         | 1       |
+    | 2         | 1       |
+    | 4.2279322 | 0.58836 |
+    | 10        | 0       |
+    | -10       | 0       |
+
+Scenario: A directional light is infinitely far away
+  Given light ← directional_light(vector(0, -2, 0), color(1, 1, 1))
+    And pt ← point(5, 3, 2)
+  Then light.direction = vector(0, -1, 0)
+    And direction_to_light(light, pt) = vector(0, 1, 0)
+    And distance_to_light(light, pt) = infinity
+    And intensity_at(light, pt) = color(1, 1, 1)
+
+Scenario: Lighting with a directional light behind the eye
+  Given m ← material()
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← directional_light(vector(0, 0, 1), color(1, 1, 1))
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(1.9, 1.9, 1.9)
+
+Scenario: Lighting outside of the cone of a spot light leaves only the ambient term
+  Given m ← material()
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← spot_light(point(0, 0, -10), vector(0, 1, 0), 0.1, 0.2, color(1, 1, 1))
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(0.1, 0.1, 0.1)
+
+Scenario Outline: Shadows cast by a directional light
+  Given w ← default_world()
+    And light ← directional_light(vector(0, -1, 0), color(1, 1, 1))
+    And w has only light
+    And pt ← point(<x>, <y>, <z>)
+  Then is_shadowed(w, pt, light) is <result>
+
+  Examples:
+    | x  | y   | z | result |
+    | 0  | -10 | 0 | true   |
+    | 10 | -10 | 0 | false  |
+    | 0  | 10  | 0 | false  |
+
+Scenario: shade_hit goes through every kind of light
+  Given w ← default_world()
+    And light ← spot_light(point(0, 0, -10), vector(0, 0, 1), 0.3, 0.5, color(1, 1, 1))
+    And w has only light
+    And origin ← point(0, 0, -5)
+    And direction ← vector(0, 0, 1)
+    And r ← ray(origin, direction)
+  When c ← color_at(w, r)
+  Then c = color(0.84, 1.0, 0.68)
+
+Scenario Outline: Attenuation of a light with the distance
+  Then attenuation(<model>, <distance>) = <factor>
+
+  Examples:
+    | model                        | distance | factor  |
+    | none                         | 5        | 1       |
+    | linear                       | 2        | 0.5     |
+    | inverse_square               | 2        | 0.25    |
+    | coefficients(1, 0.5, 0.25)   | 2        | 0.33333 |
+    | coefficients(1, 0, 0)        | 7        | 1       |
+    | inverse_square               | infinity | 1       |
+
+Scenario: Lights are not attenuated by default
+  Given light ← point_light(point(0, 0, -2), color(1, 1, 1))
+  Then light.attenuation = none
+
+Scenario: Lighting with an attenuated point light
+  Given m ← material()
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, 0, -2), color(1, 1, 1)) with inverse_square attenuation
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then light.attenuation = inverse_square
+    And result = color(0.55, 0.55, 0.55)
+
+Scenario: The ambient term of an attenuated light doesn't fall off
+  Given m ← material()
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, 0, -1000), color(1, 1, 1)) with inverse_square attenuation
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(0.1, 0.1, 0.1)
+
+Scenario: Lighting with an attenuated spot light
+  Given m ← material()
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← spot_light(point(0, 0, -4), vector(0, 0, 1), 0.3, 0.5, color(1, 1, 1)) with linear attenuation
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(0.55, 0.55, 0.55)
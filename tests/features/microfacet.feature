@@ -0,0 +1,124 @@
+Feature: Microfacet materials
+
+Scenario: Materials use phong shading by default
+  Given m ← material()
+  Then m.shading = phong
+    And m.roughness = 0
+    And m.metallic = 0
+
+Scenario Outline: The GGX distribution concentrates around the normal for smooth surfaces
+  Then distribution(<n_dot_h>, <roughness>) = <value>
+
+  Examples:
+    | n_dot_h | roughness | value   |
+    | 1       | 1         | 0.31831 |
+    | 1       | 0.5       | 5.09296 |
+    | 0.8     | 0.5       | 0.12434 |
+    | 0.5     | 1         | 0.31831 |
+
+Scenario Outline: Smith shadowing and masking
+  Then geometry(<n_dot_v>, <n_dot_l>, <roughness>) = <value>
+
+  Examples:
+    | n_dot_v | n_dot_l | roughness | value   |
+    | 1       | 1       | 1         | 1       |
+    | 0.1     | 1       | 1         | 0.18182 |
+    | 0.1     | 0.1     | 1         | 0.03306 |
+    | 0.1     | 1       | 0.2       | 0.96326 |
+
+Scenario: Dielectrics reflect the same little amount of every color
+  Given m ← material()
+    And m.refractive_index ← 1.5
+  Then f0(m, color(1, 0.5, 0.25)) = color(0.04, 0.04, 0.04)
+
+Scenario: Conductors tint their reflection with the base color
+  Given m ← material()
+    And m.metallic ← 1
+  Then f0(m, color(1, 0.5, 0.25)) = color(1, 0.5, 0.25)
+
+Scenario Outline: Every surface reflects all light at grazing angles
+  Then fresnel(color(0.04, 0.5, 1), <cos>) = color(<r>, <g>, <b>)
+
+  Examples:
+    | cos | r       | g       | b |
+    | 1   | 0.04    | 0.5     | 1 |
+    | 0   | 1       | 1       | 1 |
+    | 0.5 | 0.07    | 0.51563 | 1 |
+
+Scenario: Lighting a rough dielectric with the eye and the light above the surface
+  Given m ← material()
+    And m.shading ← microfacet
+    And m.roughness ← 1
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(1.07, 1.07, 1.07)
+
+Scenario: Lighting a rough metal
+  Given m ← material()
+    And m.shading ← microfacet
+    And m.color ← color(1, 0.8, 0.2)
+    And m.roughness ← 1
+    And m.metallic ← 1
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+  When result ← lighting(m, light, position, eyev, normalv)
+  Then result = color(0.35, 0.28, 0.07)
+
+Scenario: Smooth surfaces have a brighter highlight than rough ones
+  Given m ← material()
+    And m.shading ← microfacet
+    And m.metallic ← 1
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0.70711, -0.70711)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, -10, -10), color(1, 1, 1))
+  When m.roughness ← 0.2
+    And result ← lighting(m, light, position, eyev, normalv)
+    And m.roughness ← 0.8
+    And result2 ← lighting(m, light, position, eyev, normalv)
+  Then result is brighter than result2
+
+Scenario: Rough surfaces spread the highlight further
+  Given m ← material()
+    And m.shading ← microfacet
+    And m.metallic ← 1
+    And position ← point(0, 0, 0)
+    And eyev ← vector(0, 0, -1)
+    And normalv ← vector(0, 0, -1)
+    And light ← point_light(point(0, -10, -10), color(1, 1, 1))
+  When m.roughness ← 0.2
+    And result ← lighting(m, light, position, eyev, normalv)
+    And m.roughness ← 0.8
+    And result2 ← lighting(m, light, position, eyev, normalv)
+  Then result2 is brighter than result
+
+Scenario: A metal mirror tints what it reflects
+  Given w ← world()
+    And w.background ← solid(color(1, 1, 1))
+    And shape ← plane()
+    And shape.material.shading ← microfacet
+    And shape.material.color ← color(1, 0.5, 0.25)
+    And shape.material.metallic ← 1
+    And shape.material.reflective ← 1
+    And shape is added to w
+    And r ← ray(point(0, 1, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c = color(1, 0.5, 0.25)
+
+Scenario: A dielectric mirror only reflects a little at normal incidence
+  Given w ← world()
+    And w.background ← solid(color(1, 1, 1))
+    And shape ← plane()
+    And shape.material.shading ← microfacet
+    And shape.material.color ← color(0, 0, 0)
+    And shape.material.refractive_index ← 1.5
+    And shape.material.reflective ← 1
+    And shape is added to w
+    And r ← ray(point(0, 1, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c = color(0.04, 0.04, 0.04)
@@ -0,0 +1,117 @@
+Feature: Motion Blur
+
+Scenario: Rays are cast at time 0 by default
+  Given r ← ray(point(1, 2, 3), vector(0, 0, 1))
+  Then r.time = 0
+
+Scenario: Transforming a ray keeps its time
+  Given r ← ray_at(point(1, 2, 3), vector(0, 1, 0), 0.75)
+  When r2 ← transform(r, translation(3, 4, 5))
+  Then r2.origin = point(4, 6, 8)
+    And r2.time = 0.75
+
+Scenario Outline: The transform of a motion is interpolated by time
+  Given m ← motion(<start>, <end>)
+  Then transform_at(m, <time>) = <transform>
+
+  Examples:
+    | start                                            | end                                                  | time | transform                                            |
+    | translation(0, 0, 0)                             | translation(2, 0, 0)                                 | 0    | translation(0, 0, 0)                                 |
+    | translation(0, 0, 0)                             | translation(2, 0, 0)                                 | 0.25 | translation(0.5, 0, 0)                               |
+    | translation(0, 0, 0)                             | translation(2, 0, 0)                                 | 1    | translation(2, 0, 0)                                 |
+    | scaling(1, 1, 1)                                 | scaling(3, 3, 3)                                     | 0.5  | scaling(2, 2, 2)                                     |
+    | identity_matrix                                  | rotation_y(π/2)                                      | 0.5  | rotation_y(π/4)                                      |
+    | rotation_z(π/4)                                  | rotation_z(-π/4)                                     | 0.25 | rotation_z(π/8)                                      |
+    | translation(1, 0, 0) * scaling(2, 2, 2)          | translation(3, 0, 0) * rotation_x(π/2) * scaling(2, 2, 2) | 0.5 | translation(2, 0, 0) * rotation_x(π/4) * scaling(2, 2, 2) |
+
+Scenario: Shapes stand still before the first and after the last keyframe
+  Given m ← keyframes(0: translation(0, 0, 0), 1: translation(2, 0, 0), 3: translation(2, 4, 0))
+  Then transform_at(m, -1) = translation(0, 0, 0)
+    And transform_at(m, 0.5) = translation(1, 0, 0)
+    And transform_at(m, 2) = translation(2, 2, 0)
+    And transform_at(m, 5) = translation(2, 4, 0)
+
+Scenario Outline: A ray hits a moving sphere where it is at the time of the ray
+  Given s ← sphere()
+    And s moves from translation(0, 0, 0) to translation(0, 2, 0)
+    And r ← ray_at(point(0, 0, -5), vector(0, 0, 1), <time>)
+  When xs ← intersect(s, r)
+  Then xs.count = <count>
+    And xs is <xs>
+
+  Examples:
+    | time | count | xs               |
+    | 0    | 2     | 4, 6             |
+    | 0.25 | 2     | 4.13397, 5.86603 |
+    | 1    | 0     | empty            |
+
+Scenario: The normal of a moving sphere follows the sphere
+  Given s ← sphere()
+    And s moves from translation(0, 0, 0) to translation(0, 2, 0)
+  When n ← normal_at_time(s, point(1, 2, 0), 1)
+  Then n = vector(1, 0, 0)
+
+Scenario: The bounds of a moving shape cover its whole path
+  Given s ← sphere()
+    And s moves from translation(0, 0, 0) to translation(4, 0, 0)
+  When box ← parent_space_bounds(s)
+  Then box.min = point(-1, -1, -1)
+    And box.max = point(5, 1, 1)
+
+Scenario: The bounds of a spinning cube include its corners halfway through
+  Given s ← cube()
+    And s moves from identity_matrix to rotation_y(π/2)
+  When box ← parent_space_bounds(s)
+  Then box contains point(1.41421, 1, 0)
+    And box contains point(0, -1, -1.41421)
+    And box.min is within 0.001 of point(-1.41421, -1, -1.41421)
+    And box.max is within 0.001 of point(1.41421, 1, 1.41421)
+
+Scenario: A pattern moves along with its shape
+  Given s ← sphere()
+    And s has a black and white stripe pattern
+    And s moves from translation(0, 0, 0) to translation(1, 0, 0)
+  Then color_at_object_at(s, point(1.5, 0, 0), 0) = color(0, 0, 0)
+    And color_at_object_at(s, point(1.5, 0, 0), 1) = color(1, 1, 1)
+
+Scenario: A moving shape casts its shadow from where it is at the time of the ray
+  Given w ← world()
+    And s ← sphere()
+    And s moves from translation(0, 0, 0) to translation(10, 0, 0)
+    And s is added to w
+  Then transmission_towards_at(w, point(0, -5, 0), vector(0, 1, 0), 10, 0) = color(0, 0, 0)
+    And transmission_towards_at(w, point(0, -5, 0), vector(0, 1, 0), 10, 1) = color(1, 1, 1)
+    And intensity_at_time(w, point(0, -5, 0), point_light(point(0, 5, 0), color(1, 1, 1)), 0) = color(0, 0, 0)
+    And intensity_at_time(w, point(0, -5, 0), point_light(point(0, 5, 0), color(1, 1, 1)), 1) = color(1, 1, 1)
+
+Scenario: The camera shutter is closed by default
+  Given c ← camera(160, 120, 1.5708)
+  Then c.shutter_open = 0
+    And c.shutter_close = 0
+
+Scenario Outline: Times are spread over the shutter interval
+  Given c ← camera(160, 120, 1.5708)
+    And c.shutter_open ← 1
+    And c.shutter_close ← 3
+  Then shutter_time(c, <u>) = <time>
+
+  Examples:
+    | u    | time |
+    | 0    | 1    |
+    | 0.25 | 1.5  |
+    | 1    | 3    |
+
+Scenario Outline: A shape that moves while the shutter is open is blurred
+  Given w ← world()
+    And the emissive sphere moves from translation(-1.5, 0, -10) to translation(1.5, 0, -10)
+    And c ← camera(21, 21, 0.5)
+    And c.projection ← orthographic(4)
+    And c.shutter_close ← <close>
+  When image ← render_with_settings(c, w, jittered, 4)
+  Then pixel_at(image, 10, 10) is <center>
+    And pixel_at(image, 10, 2) is black
+
+  Examples:
+    | close | center |
+    | 0     | black  |
+    | 1     | gray   |
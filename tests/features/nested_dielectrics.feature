@@ -0,0 +1,80 @@
+Feature: Nested dielectrics
+
+Background:
+  Given w ← world()
+    And the emissive sphere above the glass
+    And r ← ray(point(0, -5, 0), vector(0, 1, 0))
+
+Scenario: Materials have the lowest priority by default
+  Given m ← material()
+  Then m.medium_priority = 0
+
+Scenario Outline: The medium with the higher priority fills the overlap of glass and water
+  Given the glass of water with priorities 2 and 1
+    And xs ← intersect_world(w, r)
+  When comps ← prepare_computations(xs[<index>], r, xs)
+  Then comps.n1 = <n1>
+    And comps.n2 = <n2>
+
+  Examples:
+    | index | n1   | n2   |
+    | 0     | 1.0  | 1.5  |
+    | 1     | 1.5  | 1.5  |
+    | 2     | 1.5  | 1.33 |
+    | 3     | 1.33 | 1.0  |
+    | 4     | 1.0  | 1.0  |
+
+Scenario: The water surface inside the glass is skipped
+  Given the glass of water with priorities 2 and 1
+    And r ← ray(point(0, -1.9, 0), vector(0, 1, 0))
+    And xs ← intersect_world(w, r)
+  When i ← hit(xs)
+  Then i.t = 0.4
+    And i.object is glass
+
+Scenario: Surfaces of media with equal priority are all hit
+  Given the glass of water with priorities 0 and 0
+    And r ← ray(point(0, -1.9, 0), vector(0, 1, 0))
+    And xs ← intersect_world(w, r)
+  When i ← hit(xs)
+  Then i.t = 0.3
+    And i.object is water
+
+Scenario Outline: Only the medium that fills the space absorbs light
+  Given the glass of water with priorities <glass> and <water>
+    And the water absorbs with density 1
+  When c ← color_at(w, r)
+  Then c = color(<gray>, <gray>, <gray>)
+
+  Examples:
+    | glass | water | gray    |
+    | 2     | 1     | 0.28650 |
+    | 0     | 0     | 0.27253 |
+
+Scenario Outline: Shadow rays are absorbed by the medium that fills the space
+  Given the glass of water with priorities <glass> and <water>
+    And the water absorbs with density 1
+    And light ← point_light(point(0, 3, 0), color(1, 1, 1))
+  When t ← transmission_towards(w, point(0, -5, 0), light)
+  Then t = color(<gray>, <gray>, <gray>)
+
+  Examples:
+    | glass | water | gray    |
+    | 2     | 1     | 0.28650 |
+    | 0     | 0     | 0.27253 |
+
+Scenario: Opaque objects inside a medium with a higher priority stay visible
+  Given the glass of water with priorities 1 and 2
+    And the opaque spoon in the water
+    And r ← ray(point(0, -1, 0), vector(0, 1, 0))
+    And xs ← intersect_world(w, r)
+  When i ← hit(xs)
+  Then i.t = 0.8
+    And i.object is spoon
+
+Scenario: Opaque objects inside a medium with a higher priority cast shadows
+  Given the glass of water with priorities 1 and 2
+    And the opaque spoon in the water
+    And light ← point_light(point(0, 3, 0), color(1, 1, 1))
+  When t ← transmission_towards(w, point(0, -1, 0), light)
+  Then t = color(0, 0, 0)
@@ -0,0 +1,190 @@
+Feature: OBJ Files
+
+Scenario: Ignoring unrecognized lines
+  Given gibberish ← a file containing:
+    """
+    There was a young lady named Bright
+    who traveled much faster than light.
+    She set out one day
+    in a relative way,
+    and came back the previous night.
+    """
+  When parser ← parse_obj_file(gibberish)
+  Then parser should have ignored 5 lines
+
+Scenario: Vertex records
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1.0000 0.5000 0.0000
+    v 1 0 0
+    v 1 1 0
+    """
+  When parser ← parse_obj_file(file)
+  Then parser.vertices[1] = point(-1, 1, 0)
+    And parser.vertices[2] = point(-1, 0.5, 0)
+    And parser.vertices[3] = point(1, 0, 0)
+    And parser.vertices[4] = point(1, 1, 0)
+
+Scenario: Parsing triangle faces
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    v 1 0 0
+    v 1 1 0
+
+    f 1 2 3
+    f 1 3 4
+    """
+  When parser ← parse_obj_file(file)
+    And g ← parser.default_group
+  Then g has 2 children
+    And child 0 of g has points 1, 2, 3
+    And child 1 of g has points 1, 3, 4
+
+Scenario: Triangulating polygons
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    v 1 0 0
+    v 1 1 0
+    v 0 2 0
+
+    f 1 2 3 4 5
+    """
+  When parser ← parse_obj_file(file)
+    And g ← parser.default_group
+  Then g has 3 children
+    And child 0 of g has points 1, 2, 3
+    And child 1 of g has points 1, 3, 4
+    And child 2 of g has points 1, 4, 5
+
+Scenario: Triangles in groups
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    v 1 0 0
+    v 1 1 0
+
+    g FirstGroup
+    f 1 2 3
+    o SecondGroup
+    f 1 3 4
+    """
+  When parser ← parse_obj_file(file)
+    And g ← parser group "FirstGroup"
+    And h ← parser group "SecondGroup"
+  Then g has 1 children
+    And child 0 of g has points 1, 2, 3
+    And h has 1 children
+    And child 0 of h has points 1, 3, 4
+
+Scenario: Converting an OBJ file to a group
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    v 1 0 0
+    v 1 1 0
+
+    g FirstGroup
+    f 1 2 3
+    g SecondGroup
+    f 1 3 4
+    """
+  When parser ← parse_obj_file(file)
+    And g ← obj_to_group(parser)
+  Then g has 2 children
+    And g includes "FirstGroup" from parser
+    And g includes "SecondGroup" from parser
+
+Scenario: Vertex normal records
+  Given file ← a file containing:
+    """
+    vn 0 0 1
+    vn 0.707 0 -0.707
+    vn 1 2 3
+    """
+  When parser ← parse_obj_file(file)
+  Then parser.normals[1] = vector(0, 0, 1)
+    And parser.normals[2] = vector(0.707, 0, -0.707)
+    And parser.normals[3] = vector(1, 2, 3)
+
+Scenario: Faces referencing missing texture coordinates are reported with their line number
+  Given file ← a file containing:
+    """
+    v 0 1 0
+    v -1 0 0
+    v 1 0 0
+
+    vn -1 0 0
+    vn 1 0 0
+    vn 0 1 0
+
+    f 1//3 2//1 3//2
+    f 1/0/3 2/102/1 3/14/2
+    """
+  When parser ← parse_obj_file(file)
+  Then parser should fail on line 10
+
+Scenario: Faces with texture coordinates and normals
+  Given file ← a file containing:
+    """
+    v 0 1 0
+    v -1 0 0
+    v 1 0 0
+
+    vt 0 0
+    vt 1 0
+    vt 0.5 1
+
+    vn -1 0 0
+    vn 1 0 0
+    vn 0 1 0
+
+    f 1//3 2//1 3//2
+    f 1/1/3 2/2/1 3/3/2
+    f 1/1 2/2 3/3
+    """
+  When parser ← parse_obj_file(file)
+    And g ← parser.default_group
+  Then g has 3 children
+    And child 0 of g is a smooth triangle with normals 3, 1, 2
+    And child 1 of g is a smooth triangle with normals 3, 1, 2
+    And child 2 of g has points 1, 2, 3
+
+Scenario: Negative indices refer to the most recent vertices
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    v 1 0 0
+    v 1 1 0
+    f -4 -3 -2
+    """
+  When parser ← parse_obj_file(file)
+    And g ← parser.default_group
+  Then child 0 of g has points 1, 2, 3
+
+Scenario: Invalid numbers are reported with their line number
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    # a comment
+    v -1 zero 0
+    """
+  When parser ← parse_obj_file(file)
+  Then parser should fail on line 3
+
+Scenario: Faces referencing missing vertices are reported with their line number
+  Given file ← a file containing:
+    """
+    v -1 1 0
+    v -1 0 0
+    f 1 2 3
+    """
+  When parser ← parse_obj_file(file)
+  Then parser should fail on line 3
@@ -0,0 +1,173 @@
+Feature: Path tracing
+
+Scenario Outline: Cosine weighted directions stay on the side of the normal
+  Given n ← vector(<nx>, <ny>, <nz>)
+  When d ← cosine_hemisphere(n, <u>, <v>)
+  Then d is normalized
+    And dot(d, n) >= 0
+
+  Examples:
+    | nx      | ny      | nz | u    | v    |
+    | 0       | 1       | 0  | 0.5  | 0.5  |
+    | 1       | 0       | 0  | 0.99 | 0.1  |
+    | 0       | 0       | -1 | 0.01 | 0.75 |
+    | 0.57735 | 0.57735 | 0.57735 | 0.3 | 0.9 |
+
+Scenario: The center of the distribution is the normal
+  Given n ← vector(0, 0, 1)
+  When d ← cosine_hemisphere(n, 0, 0.3)
+  Then d = vector(0, 0, 1)
+
+Scenario: A path that misses everything sees the background
+  Given w ← world()
+    And w.background ← solid(color(0.2, 0.3, 0.4))
+    And pt ← path_tracer(5)
+    And r ← ray(point(0, 0, -5), vector(0, 1, 0))
+  When c ← path_color_at(pt, w, r)
+  Then c = color(0.2, 0.3, 0.4)
+
+Scenario: A path that hits an emissive surface sees its emission
+  Given w ← world()
+    And shape ← sphere()
+    And shape.material.diffuse ← 0
+    And shape.material.ambient ← 0
+    And shape.material.emission ← color(1, 0.5, 0.25)
+    And shape is added to w
+    And pt ← path_tracer(5)
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← path_color_at(pt, w, r)
+  Then c = color(1, 0.5, 0.25)
+
+Scenario: A diffuse sphere in a white environment reflects its albedo
+  Given w ← world()
+    And w.background ← solid(color(1, 1, 1))
+    And shape ← sphere()
+    And shape.material.color ← color(1, 0.5, 1)
+    And shape.material.diffuse ← 0.8
+    And shape.material.ambient ← 0
+    And shape.material.specular ← 0
+    And shape is added to w
+    And pt ← path_tracer(5)
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← path_color_at(pt, w, r)
+  Then c = color(0.8, 0.4, 0.8)
+
+Scenario: A diffuse sphere lit by a white environment map reflects its albedo
+  Given w ← world()
+    And w.environment ← canvas(16, 8) filled with color(1, 1, 1) with 64 samples
+    And shape ← sphere()
+    And shape.material.diffuse ← 0.5
+    And shape.material.ambient ← 0
+    And shape.material.specular ← 0
+    And shape is added to w
+    And pt ← path_tracer(5)
+    And r ← ray(point(0, 0, -5), vector(0, 0, 1))
+  When c ← the average of 400 paths(pt, w, r)
+  Then c is close to color(0.5, 0.5, 0.5)
+
+Scenario Outline: Light bounces between diffuse surfaces until the maximum depth
+  Given w ← world()
+    And shape ← sphere()
+    And shape.transform ← scaling(10, 10, 10)
+    And shape.material.diffuse ← 0.5
+    And shape.material.ambient ← 0
+    And shape.material.specular ← 0
+    And shape.material.emission ← color(1, 1, 1)
+    And shape is added to w
+    And pt ← path_tracer(<depth>)
+    And r ← ray(point(0, 0, 0), vector(0, 0, 1))
+  When c ← path_color_at(pt, w, r)
+  Then c = color(<value>, <value>, <value>)
+
+  Examples:
+    | depth | value |
+    | 0     | 1     |
+    | 1     | 1.5   |
+    | 2     | 1.75  |
+
+Scenario Outline: Russian roulette keeps the expected brightness of long paths
+  Given w ← world()
+    And shape ← sphere()
+    And shape.transform ← scaling(10, 10, 10)
+    And shape.material.diffuse ← 0.5
+    And shape.material.ambient ← 0
+    And shape.material.specular ← 0
+    And shape.material.emission ← color(1, 1, 1)
+    And shape is added to w
+    And pt ← path_tracer(40)
+    And pt.roulette_depth ← <roulette_depth>
+    And r ← ray(point(0, 0, 0), vector(0, 0, 1))
+  When c ← the average of 4000 paths(pt, w, r)
+  Then c is close to color(2, 2, 2)
+
+  Examples:
+    | roulette_depth |
+    | 0              |
+    | 3              |
+
+Scenario Outline: Purely specular scenes look the same as with the whitted tracer
+  Given w ← world()
+    And w.background ← solid(color(0.2, 0.3, 0.4))
+    And w.light ← point_light(point(-10, 10, -10), color(1, 1, 1))
+    And shape ← plane()
+    And shape.transform ← translation(0, -1, 0)
+    And shape.material.diffuse ← 0
+    And shape.material.ambient ← 0
+    And shape.material.reflective ← 0.8
+    And shape is added to w
+    And shape ← glass_sphere()
+    And shape.material.diffuse ← 0
+    And shape.material.ambient ← 0
+    And shape.material.reflective ← 0.9
+    And shape is added to w
+    And shape ← sphere()
+    And shape.transform ← translation(2, 0, 2)
+    And shape.material.diffuse ← 0
+    And shape.material.ambient ← 0
+    And shape.material.reflective ← 0.5
+    And shape is added to w
+    And pt ← path_tracer(5)
+    And r ← ray(point(0, 0, -5), vector(<x>, <y>, 1))
+  When c ← path_color_at(pt, w, r)
+    And expected ← color_at(w, r)
+  Then c = expected
+
+  Examples:
+    | x     | y    |
+    | 0     | 0    |
+    | 0.3   | -0.2 |
+    | -0.4  | 0.5  |
+    | 0.45  | -0.1 |
+    | 0.1   | -0.6 |
+
+Scenario: The integrator is selected in the render settings
+  Given w ← world()
+    And w.background ← solid(color(1, 1, 1))
+    And shape ← sphere()
+    And shape.material.diffuse ← 0.8
+    And shape.material.ambient ← 0
+    And shape.material.specular ← 0
+    And shape is added to w
+    And c ← camera(11, 11, 0.5)
+    And c.transform ← view_transform(point(0, 0, -5), point(0, 0, 0), vector(0, 1, 0))
+    And settings ← render_settings(jittered, 2)
+    And settings.integrator ← path_tracing(5)
+  When image ← render(c, w, settings)
+  Then pixel_at(image, 5, 5) = color(0.8, 0.8, 0.8)
+    And pixel_at(image, 0, 0) = color(1, 1, 1)
+
+Scenario: Path traced renders do not depend on the number of threads
+  Given w ← world()
+    And shape ← sphere()
+    And shape.transform ← scaling(10, 10, 10)
+    And shape.material.diffuse ← 0.5
+    And shape.material.ambient ← 0
+    And shape.material.emission ← color(1, 1, 1)
+    And shape is added to w
+    And c ← camera(8, 8, 1.5708)
+    And c.transform ← view_transform(point(0, 0, 0), point(0, 0, 1), vector(0, 1, 0))
+    And settings ← render_settings(jittered, 2)
+    And settings.integrator ← path_tracing(5)
+  When image ← render_with_threads(c, w, settings, 1)
+    And expected ← render_with_threads(c, w, settings, 3)
+  Then image = expected
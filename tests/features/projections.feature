@@ -0,0 +1,94 @@
+Feature: Projections
+
+Scenario: Cameras use the perspective projection by default
+  Given c ← camera(160, 120, 1.5708)
+  Then c.projection = perspective
+
+Scenario Outline: Orthographic rays are parallel and start on the image
+  Given c ← camera(201, 101, 1.5708)
+    And c.projection ← orthographic(10)
+  When r ← ray_for_pixel(c, <x>, <y>)
+  Then r.origin = <origin>
+    And r.direction = vector(0, 0, -1)
+
+  Examples:
+    | x   | y  | origin                      |
+    | 100 | 50 | point(0, 0, 0)              |
+    | 0   | 0  | point(4.97512, 2.48756, 0)  |
+    | 200 | 100 | point(-4.97512, -2.48756, 0) |
+
+Scenario: A transformed orthographic camera
+  Given c ← camera(201, 101, 1.5708)
+    And c.projection ← orthographic(10)
+    And c.transform ← rotation_y(π/4) * translation(0, -2, 5)
+  When r ← ray_for_pixel(c, 100, 50)
+  Then r.origin = point(0, 2, -5)
+    And r.direction = vector(0.70711, 0, -0.70711)
+
+Scenario Outline: The angle of fisheye rays grows with the distance from the center
+  Given c ← camera(201, 201, 3.14159)
+    And c.projection ← fisheye
+  When r ← ray_for_pixel_offset(c, <x>, <y>, <dx>, <dy>)
+  Then r.origin = point(0, 0, 0)
+    And r.direction = <direction>
+
+  Examples:
+    | x   | y   | dx  | dy  | direction                          |
+    | 100 | 100 | 0.5 | 0.5 | vector(0, 0, -1)                   |
+    | 0   | 100 | 0   | 0.5 | vector(1, 0, 0)                    |
+    | 100 | 200 | 0.5 | 1   | vector(0, -1, 0)                   |
+    | 50  | 100 | 0.25 | 0.5 | vector(0.70711, 0, -0.70711)      |
+    | 0   | 0   | 0   | 0   | vector(0.56259, 0.56259, 0.60570)  |
+
+Scenario Outline: Equirectangular panoramas see all around the camera
+  Given c ← camera(200, 100, 1.5708)
+    And c.projection ← equirectangular
+  When r ← ray_for_pixel_offset(c, <x>, <y>, 0, 0)
+  Then r.origin = point(0, 0, 0)
+    And r.direction = <direction>
+
+  Examples:
+    | x   | y  | direction          |
+    | 100 | 50 | vector(0, 0, -1)   |
+    | 50  | 50 | vector(1, 0, 0)    |
+    | 150 | 50 | vector(-1, 0, 0)   |
+    | 0   | 50 | vector(0, 0, 1)    |
+    | 100 | 0  | vector(0, 1, 0)    |
+    | 100 | 25 | vector(0, 0.70711, -0.70711) |
+
+Scenario: The view transform places a panorama camera
+  Given c ← camera(200, 100, 1.5708)
+    And c.projection ← equirectangular
+    And from ← point(0, 0, -5)
+    And to ← point(0, 0, 0)
+    And up ← vector(0, 1, 0)
+    And c.transform ← view_transform(from, to, up)
+  When r ← ray_for_pixel_offset(c, 100, 50, 0, 0)
+  Then r.origin = point(0, 0, -5)
+    And r.direction = vector(0, 0, 1)
+
+Scenario: Only the perspective projection has a lens
+  Given c ← camera(201, 101, 1.5708)
+    And c.projection ← orthographic(10)
+    And c.aperture_radius ← 0.5
+    And c.focal_distance ← 5
+  When r ← ray_for_pixel_lens(c, 100, 50, 0.5, 0.5, 1, 0)
+  Then r.origin = point(0, 0, 0)
+    And r.direction = vector(0, 0, -1)
+
+Scenario Outline: Orthographic images don't shrink with distance
+  Given w ← world()
+    And the emissive sphere at point(0, 0, <z>)
+    And c ← camera(21, 21, 0.5)
+    And c.projection ← <projection>
+  When image ← render(c, w)
+  Then pixel_at(image, 10, 10) is white
+    And pixel_at(image, 10, 14) is <inside>
+    And pixel_at(image, 10, 16) is black
+
+  Examples:
+    | projection      | z   | inside |
+    | orthographic(4) | -10 | white  |
+    | orthographic(4) | -50 | white  |
+    | perspective     | -10 | white  |
+    | perspective     | -50 | black  |
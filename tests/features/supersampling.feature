@@ -0,0 +1,71 @@
+Feature: Supersampling
+
+Background:
+  Given w ← default_world()
+    And c ← camera(11, 11, 1.5708)
+    And from ← point(0, 0, -5)
+    And to ← point(0, 0, 0)
+    And up ← vector(0, 1, 0)
+    And c.transform ← view_transform(from, to, up)
+
+Scenario: The default settings shoot a single ray through the pixel center
+  Given settings ← render_settings()
+  Then settings.sampling = center
+  When (color, rays) ← sample_pixel(c, w, settings, 5, 5)
+  Then color = color(0.38066, 0.47583, 0.2855)
+    And rays = 1
+
+Scenario: Grid sampling places the samples at the centers of a regular grid
+  Given settings ← render_settings(grid, 2)
+  When offsets ← sample_offsets(settings, 3, 4)
+  Then offsets = (0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)
+
+Scenario: Jittered sampling places one sample in every cell of the grid
+  Given settings ← render_settings(jittered, 4)
+  When offsets ← sample_offsets(settings, 3, 4)
+  Then offsets has 16 entries
+    And every offset lies in its own cell of a 4x4 grid
+    And offsets are not on the cell centers
+
+Scenario: Jittered sampling is reproducible
+  Given settings ← render_settings(jittered, 3)
+  When offsets ← sample_offsets(settings, 7, 2)
+    And offsets2 ← sample_offsets(settings, 7, 2)
+  Then offsets = offsets2
+
+Scenario: Grid sampling averages all samples of a pixel
+  Given settings ← render_settings(grid, 3)
+  When (color, rays) ← sample_pixel(c, w, settings, 5, 5)
+  Then rays = 9
+    And color is the average of the grid samples of pixel 5, 5
+
+Scenario: Adaptive sampling stays with the corner samples where the image is flat
+  Given settings ← render_settings(adaptive, 4)
+  When (color, rays) ← sample_pixel(c, w, settings, 0, 0)
+  Then color = color(0, 0, 0)
+    And rays = 4
+
+Scenario: Adaptive sampling adds rays where the corner samples differ
+  Given settings ← render_settings(adaptive, 4)
+  When (color, rays) ← sample_pixel(c, w, settings, 4, 5)
+  Then rays = 20
+    And color is the average of the corner and grid samples of pixel 4, 5
+
+Scenario: Supersampling smooths the edge of a sphere
+  Given settings ← render_settings(grid, 4)
+  When image ← render(c, w, settings)
+    And expected ← render(c, w, render_settings())
+  Then pixel_at(image, 0, 0) = pixel_at(expected, 0, 0)
+    And pixel_at(image, 4, 5) is darker than pixel_at(expected, 4, 5)
+
+Scenario Outline: Supersampled renders do not depend on the number of threads
+  Given settings ← render_settings(<sampling>, 3)
+  When image ← render_with_threads(c, w, settings, 1)
+    And expected ← render_with_threads(c, w, settings, 4)
+  Then every pixel of image is identical to expected
+
+  Examples:
+    | sampling |
+    | grid     |
+    | jittered |
+    | adaptive |
@@ -0,0 +1,87 @@
+Feature: Shadows of transparent objects
+
+Scenario: Light passes freely when nothing is in the way
+  Given w ← default_world()
+    And light ← point_light(point(-10, 10, -10), color(1, 1, 1))
+    And pt ← point(0, 10, 0)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(1, 1, 1)
+    And is_shadowed(w, pt, light) is false
+
+Scenario: An opaque object blocks all of the light
+  Given w ← default_world()
+    And light ← point_light(point(-10, 10, -10), color(1, 1, 1))
+    And pt ← point(10, -10, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(0, 0, 0)
+    And is_shadowed(w, pt, light) is true
+
+Scenario: A clear glass sphere lets all of the light through
+  Given w ← world()
+    And shape ← glass_sphere()
+    And shape is added to w
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And pt ← point(0, 0, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(1, 1, 1)
+    And is_shadowed(w, pt, light) is false
+
+Scenario: A tinted transparent sphere filters the light at both of its surfaces
+  Given w ← world()
+    And shape ← sphere()
+    And shape.material.transparency ← 0.5
+    And shape.material.color ← color(1, 0.5, 0)
+    And shape is added to w
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And pt ← point(0, 0, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(0.25, 0.0625, 0)
+    And is_shadowed(w, pt, light) is false
+
+Scenario: An opaque object behind a transparent one still blocks the light
+  Given w ← world()
+    And shape ← glass_sphere()
+    And shape is added to w
+    And shape ← sphere()
+    And shape.transform ← translation(0, 0, 4)
+    And shape is added to w
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And pt ← point(0, 0, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(0, 0, 0)
+
+Scenario: Objects that do not cast shadows are ignored
+  Given w ← world()
+    And shape ← sphere()
+    And shape.cast_shadows ← false
+    And shape is added to w
+    And light ← point_light(point(0, 0, -10), color(1, 1, 1))
+    And pt ← point(0, 0, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(1, 1, 1)
+
+Scenario: Transparent objects behind the light do not tint it
+  Given w ← world()
+    And shape ← sphere()
+    And shape.material.transparency ← 0.5
+    And shape.material.color ← color(1, 0.5, 0)
+    And shape is added to w
+    And light ← point_light(point(0, 0, 5), color(1, 1, 1))
+    And pt ← point(0, 0, 10)
+  When transmission ← intensity_at(w, pt, light)
+  Then transmission = color(1, 1, 1)
+
+Scenario: A tinted sphere casts a colored shadow on the floor
+  Given w ← world()
+    And shape ← plane()
+    And shape is added to w
+    And shape ← sphere()
+    And shape.transform ← translation(0, 3, 0)
+    And shape.material.transparency ← 0.5
+    And shape.material.color ← color(1, 0, 0)
+    And shape is added to w
+    And light ← point_light(point(0, 10, 0), color(1, 1, 1))
+    And light is added to w
+    And r ← ray(point(0, 0.5, 0), vector(0, -1, 0))
+  When c ← color_at(w, r)
+  Then c = color(0.55, 0.1, 0.1)
@@ -0,0 +1,67 @@
+Feature: Triangles
+
+Scenario: Constructing a triangle
+  Given p1 ← point(0, 1, 0)
+    And p2 ← point(-1, 0, 0)
+    And p3 ← point(1, 0, 0)
+    And t ← triangle(p1, p2, p3)
+  Then t.p1 = p1
+    And t.p2 = p2
+    And t.p3 = p3
+    And t.e1 = vector(-1, -1, 0)
+    And t.e2 = vector(1, -1, 0)
+    And t.normal = vector(0, 0, -1)
+
+Scenario: Finding the normal on a triangle
+  Given p1 ← point(0, 1, 0)
+    And p2 ← point(-1, 0, 0)
+    And p3 ← point(1, 0, 0)
+    And t ← triangle(p1, p2, p3)
+    And q1 ← point(0, 0.5, 0)
+    And q2 ← point(-0.5, 0.75, 0)
+    And q3 ← point(0.5, 0.25, 0)
+  When n1 ← local_normal_at(t, q1)
+    And n2 ← local_normal_at(t, q2)
+    And n3 ← local_normal_at(t, q3)
+  Then n1 = t.normal
+    And n2 = t.normal
+    And n3 = t.normal
+
+Scenario Outline: A ray misses a triangle
+  Given p1 ← point(0, 1, 0)
+    And p2 ← point(-1, 0, 0)
+    And p3 ← point(1, 0, 0)
+    And t ← triangle(p1, p2, p3)
+    And rp ← <origin>
+    And rv ← <direction>
+    And r ← ray(rp, rv)
+  When xs ← local_intersect(t, r)
+  Then xs.count = 0
+
+  Examples:
+    | origin            | direction       |
+    | point(0, -1, -2)  | vector(0, 1, 0) |
+    | point(1, 1, -2)   | vector(0, 0, 1) |
+    | point(-1, 1, -2)  | vector(0, 0, 1) |
+    | point(0, -1, -2)  | vector(0, 0, 1) |
+
+Scenario: A ray strikes a triangle
+  Given p1 ← point(0, 1, 0)
+    And p2 ← point(-1, 0, 0)
+    And p3 ← point(1, 0, 0)
+    And t ← triangle(p1, p2, p3)
+    And rp ← point(0, 0.5, -2)
+    And rv ← vector(0, 0, 1)
+    And r ← ray(rp, rv)
+  When xs ← local_intersect(t, r)
+  Then xs.count = 1
+    And xs[0].t = 2
+
+Scenario: An intersection can encapsulate u and v
+  Given p1 ← point(0, 1, 0)
+    And p2 ← point(-1, 0, 0)
+    And p3 ← point(1, 0, 0)
+    And t ← triangle(p1, p2, p3)
+  When i ← intersection_with_uv(3.5, t, 0.2, 0.4)
+  Then i.u = 0.2
+    And i.v = 0.4
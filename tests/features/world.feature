@@ -282,7 +282,7 @@ Scenario: shade_hit() with a transparent material
     And xs ← intersections(i0)
   When comps ← prepare_computations(i0, r, xs)
     And color ← shade_hit(w, comps, 5)
-  Then color = color(0.93642, 0.68642, 0.68642)
+  Then color = color(1.12547, 0.68642, 0.68642)
 
 Scenario: shade_hit() with a reflective, transparent material
   Given w ← default_world()
@@ -304,4 +304,4 @@ Scenario: shade_hit() with a reflective, transparent material
     And xs ← intersections(i0)
   When comps ← prepare_computations(i0, r, xs)
     And color ← shade_hit(w, comps, 5)
-  Then color = color(0.93391, 0.69643, 0.69243)
+  Then color = color(1.11500, 0.69643, 0.69243)
@@ -0,0 +1,255 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &GlossyWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_ray(world: &GlossyWorld, input: &str) -> Ray {
+    let rx = cucumber::codegen::Regex::new(r"^ray\((.+\)), (.+\))\)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    Ray::new(parse_tuple(world, &m[1]), parse_tuple(world, &m[2]))
+}
+
+// "white" or "between gray a and gray b"
+fn check_brightness(c: &Tuples, input: &str) {
+    if input == "white" {
+        assert!(c.is_equal(&Tuples::color(1.0, 1.0, 1.0)), "{:?}", c);
+        return;
+    }
+    let rx = cucumber::codegen::Regex::new(r"^between gray (.+) and gray (.+)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    let (low, high) = (m[1].parse::<f64>().unwrap(), m[2].parse::<f64>().unwrap());
+    assert!(c.x > low && c.x < high, "{:?}", c);
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut GlossyWorld) {
+    world.material = Some(Material::material());
+}
+
+#[then(regex = r"^m\.roughness = (.+)$")]
+fn check_roughness(world: &mut GlossyWorld, matches: &[String]) {
+    assert!(is_equal_f64(world.material.as_ref().unwrap().roughness, matches[0].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^m\.glossy_samples = (\d+)$")]
+fn check_glossy_samples(world: &mut GlossyWorld, matches: &[String]) {
+    assert_eq!(world.material.as_ref().unwrap().glossy_samples, matches[0].parse::<usize>().unwrap());
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut GlossyWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut GlossyWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+fn add_emissive_sphere(world: &mut GlossyWorld, y: f64) {
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&Matrix::translate(0.0, y, 0.0));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+#[given("the emissive sphere above the mirror")]
+fn given_sphere_above(world: &mut GlossyWorld) {
+    add_emissive_sphere(world, 3.0);
+}
+
+#[given("the emissive sphere below the pane")]
+fn given_sphere_below(world: &mut GlossyWorld) {
+    add_emissive_sphere(world, -3.0);
+}
+
+#[given(regex = r"^(shape|mirror) ← plane\(\)$")]
+fn given_plane(world: &mut GlossyWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = Plane::new();
+    world.shape.insert(matches[0].clone(), shape);
+}
+
+#[given("pane ← glass_pane()")]
+fn given_pane(world: &mut GlossyWorld) {
+    let pane: Arc<RwLock<dyn Shape>> = Cube::new();
+    {
+        let mut p = pane.write().unwrap();
+        p.set_transform(&Matrix::scale(10.0, 0.1, 10.0));
+        let material = p.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+    }
+    world.shape.insert("pane".to_string(), pane);
+}
+
+#[given(regex = r"^(\w+)\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut GlossyWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[1]);
+    world.shape.get(&matches[0]).unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given(regex = r"^(\w+)\.material\.(\w+) ← ([-\d.]+)$")]
+#[when(regex = r"^(\w+)\.material\.(\w+) ← ([-\d.]+)$")]
+fn given_material_value(world: &mut GlossyWorld, matches: &[String]) {
+    let value = matches[2].parse::<f64>().unwrap();
+    let mut shape = world.shape.get(&matches[0]).unwrap().write().unwrap();
+    let material = shape.get_mut_material();
+    match matches[1].as_str() {
+        "ambient" => material.ambient = value,
+        "diffuse" => material.diffuse = value,
+        "reflective" => material.reflective = value,
+        "roughness" => material.roughness = value,
+        "glossy_samples" => material.glossy_samples = value as usize,
+        f => panic!("unknown material field {f}"),
+    }
+}
+
+#[given(regex = r"^(\w+) is added to w$")]
+fn given_shape_added(world: &mut GlossyWorld, matches: &[String]) {
+    let shape = world.shape.get(&matches[0]).unwrap().clone();
+    world.world.add_object(shape);
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut GlossyWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^r ← (ray\(.+\))$")]
+fn given_ray(world: &mut GlossyWorld, matches: &[String]) {
+    world.ray = Some(parse_ray(world, &matches[0]));
+}
+
+#[given(regex = r"^pt ← path_tracer\((\d+)\)$")]
+fn given_path_tracer(world: &mut GlossyWorld, matches: &[String]) {
+    world.tracer = PathTracer::new(matches[0].parse::<usize>().unwrap());
+}
+
+#[when(regex = r"^directions ← (\d+) perturbations of d on side with roughness (.+)$")]
+fn when_perturb(world: &mut GlossyWorld, matches: &[String]) {
+    let d = world.tuple.get("d").unwrap().clone().normalize();
+    let side = *world.tuple.get("side").unwrap();
+    let roughness = matches[1].parse::<f64>().unwrap();
+    let mut rng = Rng::new(0);
+    world.directions = (0..matches[0].parse::<usize>().unwrap())
+        .map(|_| rtxch_lib::World::perturb(&d, &side, roughness, &mut rng))
+        .collect();
+}
+
+#[then("every direction is normalized")]
+fn check_normalized(world: &mut GlossyWorld) {
+    assert!(world.directions.iter().all(|d| is_equal_f64(d.clone().magnitude(), 1.0)));
+}
+
+#[then("every direction is on the side of side")]
+fn check_side(world: &mut GlossyWorld) {
+    let side = world.tuple.get("side").unwrap();
+    assert!(world.directions.iter().all(|d| Tuples::dot(d, side) >= 0.0));
+}
+
+#[then(regex = r"^every direction is within (.+) of d$")]
+fn check_lobe(world: &mut GlossyWorld, matches: &[String]) {
+    let d = world.tuple.get("d").unwrap().clone().normalize();
+    let min_cos = matches[0].parse::<f64>().unwrap();
+    for direction in &world.directions {
+        assert!(Tuples::dot(direction, &d) >= min_cos - 1e-9, "{:?}", direction);
+    }
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut GlossyWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^c ← the average of (\d+) paths along (ray\(.+\))$")]
+fn when_average_paths(world: &mut GlossyWorld, matches: &[String]) {
+    let n = matches[0].parse::<usize>().unwrap();
+    let ray = parse_ray(world, &matches[1]);
+    let mut rng = Rng::new(0);
+    let mut sum = Tuples::color(0.0, 0.0, 0.0);
+    for _ in 0..n {
+        sum.add(&world.tracer.color_at(&world.world, &ray, &mut rng));
+    }
+    world.tuple.insert("c".to_string(), sum.scale(1.0 / n as f64));
+}
+
+// largest difference of the red channel between rays shifted a little along x
+#[when(regex = r"^(spread|spread2) ← the spread of (\d+) rays next to (ray\(.+\))(?: with max depth (\d+))?$")]
+fn when_spread(world: &mut GlossyWorld, matches: &[String]) {
+    let ray = parse_ray(world, &matches[2]);
+    let depth = matches[3].parse::<i32>().unwrap_or(MAX_ITERATIONS);
+    let reds: Vec<f64> = (0..matches[1].parse::<usize>().unwrap()).map(|i| {
+        let origin = ray.origin().clone().add(&Tuples::vector(i as f64 * 0.001, 0.0, 0.0));
+        rtxch_lib::World::color_at(&world.world, &Ray::new(origin, *ray.direction()), depth).x
+    }).collect();
+    let spread = reds.iter().cloned().fold(-f64::INFINITY, f64::max) - reds.iter().cloned().fold(f64::INFINITY, f64::min);
+    world.value.insert(matches[0].clone(), spread);
+}
+
+#[then("spread2 < spread / 2")]
+fn check_spread(world: &mut GlossyWorld) {
+    let spread = world.value.get("spread").unwrap();
+    let spread2 = world.value.get("spread2").unwrap();
+    assert!(*spread2 < spread / 2.0, "{spread2} {spread}");
+}
+
+#[then(regex = r"^c = (.+)$")]
+fn check_color(world: &mut GlossyWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^c is (.+)$")]
+fn check_c_brightness(world: &mut GlossyWorld, matches: &[String]) {
+    check_brightness(world.tuple.get("c").unwrap(), &matches[0]);
+}
+
+#[derive(Debug, Default, World)]
+struct GlossyWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    value: HashMap<String, f64>,
+    material: Option<Material>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    directions: Vec<Tuples>,
+    tracer: PathTracer,
+    ray: Option<Ray>,
+}
+
+fn main() {
+    futures::executor::block_on(GlossyWorld::run(
+        "tests/features/glossy.feature",
+    ));
+}
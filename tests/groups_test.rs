@@ -0,0 +1,204 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+#[given(regex = r"(.+) ← (group|sphere|point|vector|ray|translation|scaling|rotation_y|test_pattern)\((.*)\)")]
+#[when(regex = r"(.+) ← (point|vector|ray|local_intersect|intersect|world_to_object|normal_to_world|normal_at|color_at_object)\((.*)\)")]
+fn given_item(world: &mut GroupsWorld, matches: &[String]) {
+    create_item(world, matches);
+}
+
+fn create_item(world: &mut GroupsWorld, matches: &[String]) {
+    let t = matches[0].clone();
+    let func = matches[1].as_str();
+    match func {
+        "group" => {
+            let g = Group::new();
+            world.shape.insert(t.clone(), g.clone());
+            world.group.insert(t, g);
+        },
+        "sphere" => {
+            world.shape.insert(t, Sphere::new());
+        },
+        "point" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::point(v[0], v[1], v[2]));
+        },
+        "vector" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::vector(v[0], v[1], v[2]));
+        },
+        "ray" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let o = world.tuple.get(v[0]).unwrap();
+            let d = world.tuple.get(v[1]).unwrap();
+            world.ray.insert(t, Ray::new(*o, *d));
+        },
+        "translation" => {
+            let v = parse_values_f64(&matches[2]);
+            world.matrix.insert(t, Matrix::translate(v[0], v[1], v[2]));
+        },
+        "scaling" => {
+            let v = parse_values_f64(&matches[2]);
+            world.matrix.insert(t, Matrix::scale(v[0], v[1], v[2]));
+        },
+        "rotation_y" => {
+            let divisor = matches[2].trim_start_matches("π/").parse::<f64>().unwrap();
+            world.matrix.insert(t, Matrix::rotate_y(PI / divisor));
+        },
+        "test_pattern" => {
+            world.pattern.insert(t, TestPattern::new());
+        },
+        "local_intersect" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            let xs = s.read().unwrap().intersections_local(r, s);
+            world.inter.insert(t, xs);
+        },
+        "intersect" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            world.inter.insert(t, <dyn Shape>::intersect(s, r));
+        },
+        "world_to_object" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.shape.get(v[0]).unwrap();
+            let p = world.tuple.get(v[1]).unwrap();
+            world.tuple.insert(t, <dyn Shape>::world_to_object(s, p));
+        },
+        "normal_to_world" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.shape.get(v[0]).unwrap();
+            let n = world.tuple.get(v[1]).unwrap();
+            world.tuple.insert(t, <dyn Shape>::normal_to_world(s, n));
+        },
+        "normal_at" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.shape.get(v[0]).unwrap();
+            let p = world.tuple.get(v[1]).unwrap();
+            world.tuple.insert(t, <dyn Shape>::normal_at(s, p));
+        },
+        "color_at_object" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let pattern = world.pattern.get(v[0]).unwrap();
+            let s = world.shape.get(v[1]).unwrap();
+            let p = world.tuple.get(v[2]).unwrap();
+            let c = pattern.read().unwrap().color_at_object(s, p);
+            world.tuple.insert(t, c);
+        },
+        _ => panic!("{func} not implemented")
+    }
+}
+
+fn set_transform(world: &mut GroupsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    let m = world.matrix.get(&matches[1]).unwrap();
+    s.write().unwrap().set_transform(m);
+}
+
+#[given(regex = r"set_transform\((.+), (.+)\)")]
+fn given_set_transform(world: &mut GroupsWorld, matches: &[String]) {
+    set_transform(world, matches);
+}
+
+fn add_child(world: &mut GroupsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let s = world.shape.get(&matches[1]).unwrap();
+    Group::add_child(g, s.clone());
+}
+
+#[given(regex = r"add_child\((.+), (.+)\)")]
+fn given_add_child(world: &mut GroupsWorld, matches: &[String]) {
+    add_child(world, matches);
+}
+
+#[when(regex = r"add_child\((.+), (.+)\)")]
+fn when_add_child(world: &mut GroupsWorld, matches: &[String]) {
+    add_child(world, matches);
+}
+
+#[then(regex = r"(.+)\.transform = identity_matrix")]
+fn check_identity(world: &mut GroupsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    assert!(s.read().unwrap().get_transform().is_equal(&Matrix::new(4)));
+}
+
+#[then(regex = r"(.+) is (not )?empty")]
+fn check_empty(world: &mut GroupsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let expected = matches[1].is_empty();
+    assert_eq!(g.read().unwrap().is_empty(), expected);
+}
+
+#[then(regex = r"(.+) includes (.+)")]
+fn check_includes(world: &mut GroupsWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let s = world.shape.get(&matches[1]).unwrap();
+    assert!(g.read().unwrap().contains(s));
+}
+
+#[then(regex = r"(.+)\.parent is nothing")]
+fn check_no_parent(world: &mut GroupsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    assert!(s.read().unwrap().get_parent().is_none());
+}
+
+#[then(regex = r"(.+)\.parent = (.+)")]
+fn check_parent(world: &mut GroupsWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    let g = world.shape.get(&matches[1]).unwrap();
+    let parent = s.read().unwrap().get_parent().unwrap();
+    assert!(Arc::ptr_eq(&parent, g));
+}
+
+#[then(regex = r"(.+)\.count = (.+)")]
+fn check_count(world: &mut GroupsWorld, matches: &[String]) {
+    let xs = world.inter.get(&matches[0]).unwrap();
+    let count = matches[1].parse::<usize>().unwrap();
+    assert_eq!(xs.count(), count);
+}
+
+#[then(regex = r"(.+)\[(.+)\]\.object = (.+)")]
+fn check_object(world: &mut GroupsWorld, matches: &[String]) {
+    let xs = world.inter.get(&matches[0]).unwrap();
+    let idx = matches[1].parse::<usize>().unwrap();
+    let target = world.shape.get(&matches[2]).unwrap();
+    assert!(Arc::ptr_eq(xs.xs()[idx].object(), target));
+}
+
+#[then(regex = r"(.+) = (point|vector|color)\((.+)\)")]
+fn check_tuple(world: &mut GroupsWorld, matches: &[String]) {
+    let r = world.tuple.get(&matches[0]).unwrap();
+    let v = parse_values_f64(&matches[2]);
+    let target = match matches[1].as_str() {
+        "point" => Tuples::point(v[0], v[1], v[2]),
+        "vector" => Tuples::vector(v[0], v[1], v[2]),
+        _ => Tuples::color(v[0], v[1], v[2]),
+    };
+    assert!(r.is_equal(&target), "{:?} {:?}", r, target);
+}
+
+#[derive(Debug, Default, World)]
+struct GroupsWorld {
+    ray: HashMap<String, Ray>,
+    tuple: HashMap<String, Tuples>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    group: HashMap<String, Arc<RwLock<Group>>>,
+    pattern: HashMap<String, Arc<RwLock<dyn Pattern>>>,
+    inter: HashMap<String, IntersectionList>,
+    matrix: HashMap<String, Matrix>,
+}
+
+fn main() {
+    futures::executor::block_on(GroupsWorld::run(
+        "tests/features/groups.feature",
+    ));
+}
@@ -0,0 +1,286 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &ImageLightsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn hdr_header(width: usize, height: usize) -> Vec<u8> {
+    format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {height} +X {width}\n").into_bytes()
+}
+
+fn parse_rgbe(input: &str) -> Vec<u8> {
+    parse_values_f64(&input.to_string()).iter().map(|v| *v as u8).collect()
+}
+
+#[given(regex = r"^data ← an hdr file of (\d+)x(\d+) pixels with rgbe\((.+)\)$")]
+fn given_flat_hdr(world: &mut ImageLightsWorld, matches: &[String]) {
+    let (width, height) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let mut data = hdr_header(width, height);
+    let rgbe = parse_rgbe(&matches[2]);
+    for _ in 0..width * height {
+        data.extend_from_slice(&rgbe);
+    }
+    world.data = data;
+}
+
+#[given(regex = r"^data ← a run length encoded hdr file of (\d+)x(\d+) pixels with rgbe\((.+)\)$")]
+fn given_rle_hdr(world: &mut ImageLightsWorld, matches: &[String]) {
+    let (width, height) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let mut data = hdr_header(width, height);
+    let rgbe = parse_rgbe(&matches[2]);
+    for _ in 0..height {
+        data.extend_from_slice(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8]);
+        // a literal run of one byte followed by a repeated run for the rest of the row
+        for value in &rgbe {
+            data.extend_from_slice(&[1, *value, (128 + width - 1) as u8, *value]);
+        }
+    }
+    world.data = data;
+}
+
+#[given("data ← a file without hdr header")]
+fn given_no_header(world: &mut ImageLightsWorld) {
+    world.data = b"P3\n1 1\n255\n0 0 0\n".to_vec();
+}
+
+#[given(regex = r"^data ← a truncated hdr file of (\d+)x(\d+) pixels$")]
+fn given_truncated(world: &mut ImageLightsWorld, matches: &[String]) {
+    let (width, height) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let mut data = hdr_header(width, height);
+    data.extend_from_slice(&[128, 128, 128, 128]);
+    world.data = data;
+}
+
+#[when("image ← canvas_from_hdr(data)")]
+fn when_canvas_from_hdr(world: &mut ImageLightsWorld) {
+    world.image = Some(Canvas::canvas_from_hdr(&world.data));
+}
+
+#[then(regex = r"^image\.(width|height) = (\d+)$")]
+fn check_size(world: &mut ImageLightsWorld, matches: &[String]) {
+    let image = world.image.as_ref().unwrap().as_ref().unwrap();
+    let size = if matches[0] == "width" { image.width } else { image.height };
+    assert_eq!(size, matches[1].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) = (.+)$")]
+fn check_pixel(world: &mut ImageLightsWorld, matches: &[String]) {
+    let image = world.image.as_ref().unwrap().as_ref().unwrap();
+    let target = parse_tuple(world, &matches[2]);
+    let c = image.pixel_at(matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then("reading the image fails")]
+fn check_fails(world: &mut ImageLightsWorld) {
+    assert!(world.image.as_ref().unwrap().is_err());
+}
+
+#[when(regex = r"^d ← uv_to_direction\((.+), (.+)\)$")]
+fn when_uv_to_direction(world: &mut ImageLightsWorld, matches: &[String]) {
+    let d = ImageBackground::uv_to_direction(matches[0].parse::<f64>().unwrap(), matches[1].parse::<f64>().unwrap());
+    world.tuple.insert("d".to_string(), d);
+}
+
+#[then(regex = r"^direction_to_uv\(d\) = \((.+), (.+)\)$")]
+fn check_direction_to_uv(world: &mut ImageLightsWorld, matches: &[String]) {
+    let (u, v) = ImageBackground::direction_to_uv(world.tuple.get("d").unwrap());
+    assert!(is_equal_f64(u, matches[0].parse::<f64>().unwrap()), "{u}");
+    assert!(is_equal_f64(v, matches[1].parse::<f64>().unwrap()), "{v}");
+}
+
+#[given(regex = r"^map ← canvas\((\d+), (\d+)\) filled with (.+)$")]
+fn given_map(world: &mut ImageLightsWorld, matches: &[String]) {
+    let mut map = Canvas::new(matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    map.clear(&parse_tuple(world, &matches[2]));
+    world.map = Some(map);
+}
+
+#[given(regex = r"^pixel \((\d+), (\d+)\) of map ← (.+)$")]
+fn given_map_pixel(world: &mut ImageLightsWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[2]);
+    world.map.as_mut().unwrap().write_pixel(matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap(), &color);
+}
+
+#[given(regex = r"^the top row of map is (.+)$")]
+fn given_top_row(world: &mut ImageLightsWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    let map = world.map.as_mut().unwrap();
+    for x in 0..map.width {
+        map.write_pixel(x, 0, &color);
+    }
+}
+
+#[given(regex = r"^light ← image_light\(map, (\d+)\)$")]
+fn given_image_light(world: &mut ImageLightsWorld, matches: &[String]) {
+    world.light = Some(ImageLight::new(world.map.clone().unwrap(), matches[0].parse::<usize>().unwrap()));
+}
+
+#[then(regex = r"^light\.intensity = (.+)$")]
+fn check_intensity(world: &mut ImageLightsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let intensity = world.light.as_ref().unwrap().intensity();
+    assert!(intensity.is_equal(&target), "{:?}", intensity);
+}
+
+#[then(regex = r"^light has (\d+) samples at (.+)$")]
+fn check_sample_count(world: &mut ImageLightsWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[1]);
+    assert_eq!(world.light.as_ref().unwrap().samples(&point).len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^every sample of light at (.+) is infinitely far away$")]
+fn check_sample_distance(world: &mut ImageLightsWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[0]);
+    assert!(world.light.as_ref().unwrap().samples(&point).iter().all(|s| s.distance.is_infinite()));
+}
+
+#[then(regex = r"^every sample of light at (.+) comes from pixel \((\d+), (\d+)\)$")]
+fn check_sample_pixel(world: &mut ImageLightsWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[0]);
+    let light = world.light.as_ref().unwrap();
+    let (x, y) = (matches[1].parse::<f64>().unwrap(), matches[2].parse::<f64>().unwrap());
+    let (width, height) = (light.image().width as f64, light.image().height as f64);
+    for sample in light.samples(&point) {
+        let (u, v) = ImageBackground::direction_to_uv(&sample.direction);
+        assert_eq!(((u * width).floor(), (v * height).floor()), (x, y));
+    }
+}
+
+#[then(regex = r"^direction_to_light\(light, (.+\))\) = (.+)$")]
+fn check_direction_to_light(world: &mut ImageLightsWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[0]);
+    let target = parse_tuple(world, &matches[1]);
+    let direction = world.light.as_ref().unwrap().direction_to_light(&point);
+    assert!(direction.is_equal(&target), "{:?}", direction);
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut ImageLightsWorld) {
+    world.material = Some(Material::material());
+}
+
+#[given(regex = r"^m\.specular ← (.+)$")]
+fn given_specular(world: &mut ImageLightsWorld, matches: &[String]) {
+    world.material.as_mut().unwrap().specular = matches[0].parse::<f64>().unwrap();
+}
+
+#[when("result ← lighting(m, light, position, eyev, normalv)")]
+fn when_lighting(world: &mut ImageLightsWorld) {
+    let object: Arc<RwLock<dyn Shape>> = Sphere::new();
+    let result = render::lighting(world.material.as_ref().unwrap(), &object, world.light.as_ref().unwrap(),
+        world.tuple.get("position").unwrap(), world.tuple.get("eyev").unwrap(), world.tuple.get("normalv").unwrap(),
+        &Tuples::color(1.0, 1.0, 1.0));
+    world.tuple.insert("result".to_string(), result);
+}
+
+// importance sampling only converges on the exact value
+#[then(regex = r"^result is close to (.+)$")]
+fn check_result_close(world: &mut ImageLightsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let result = world.tuple.get("result").unwrap();
+    assert!((result.x - target.x).abs() < 0.1 && (result.y - target.y).abs() < 0.1 && (result.z - target.z).abs() < 0.1, "{:?}", result);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut ImageLightsWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^w\.environment ← map with (\d+) samples$")]
+fn given_environment(world: &mut ImageLightsWorld, matches: &[String]) {
+    world.world.set_environment_map(world.map.clone().unwrap(), matches[0].parse::<usize>().unwrap());
+}
+
+#[given("shape ← plane()")]
+fn given_plane(world: &mut ImageLightsWorld) {
+    world.shape = Some(Plane::new());
+}
+
+#[given(regex = r"^shape\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut ImageLightsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut ImageLightsWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut ImageLightsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut ImageLightsWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("transmission ← intensity_at(w, pt, light)")]
+fn when_intensity_at(world: &mut ImageLightsWorld) {
+    let pt = world.tuple.get("pt").unwrap();
+    let light = world.world.get_lights()[0].as_ref();
+    let transmission = rtxch_lib::World::intensity_at(&world.world, pt, light);
+    world.tuple.insert("transmission".to_string(), transmission);
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut ImageLightsWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[then(regex = r"^(transmission|c|d) = (.+)$")]
+fn check_tuple(world: &mut ImageLightsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^w has (\d+) lights?$")]
+fn check_light_count(world: &mut ImageLightsWorld, matches: &[String]) {
+    assert_eq!(world.world.get_lights().len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[derive(Debug, Default, World)]
+struct ImageLightsWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    data: Vec<u8>,
+    image: Option<Result<Canvas, CanvasError>>,
+    map: Option<Canvas>,
+    light: Option<ImageLight>,
+    material: Option<Material>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    ray: Option<Ray>,
+}
+
+fn main() {
+    futures::executor::block_on(ImageLightsWorld::run(
+        "tests/features/image_lights.feature",
+    ));
+}
@@ -0,0 +1,213 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::render::render_with_settings;
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &IntegratorsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_integrator(input: &str) -> Arc<dyn Integrator> {
+    let rx = cucumber::codegen::Regex::new(r"^(\w+)\((.*)\)$").unwrap();
+    let m = rx.captures(input).unwrap();
+    match &m[1] {
+        "whitted" => Arc::new(Whitted::default()),
+        "normal_view" => Arc::new(NormalView),
+        "depth_view" => {
+            let v = parse_values_f64(&m[2].to_string());
+            Arc::new(DepthView::new(v[0], v[1]))
+        },
+        "albedo_view" => Arc::new(AlbedoView),
+        "uv_view" => Arc::new(UvView),
+        "object_id_view" => Arc::new(ObjectIdView),
+        "bounce_view" => Arc::new(BounceView::default()),
+        s => panic!("unknown integrator {s}"),
+    }
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut IntegratorsWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut IntegratorsWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given(regex = r"^w\.background ← solid\((.+)\)$")]
+fn given_solid(world: &mut IntegratorsWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.world.set_background(SolidBackground::new(color));
+}
+
+#[given(regex = r"^shape ← (sphere|plane)\(\)$")]
+fn given_shape(world: &mut IntegratorsWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = if matches[0] == "sphere" { Sphere::new() } else { Plane::new() };
+    world.shape = Some(shape);
+}
+
+#[given(regex = r"^shape ← triangle\((point\(.+\)), (point\(.+\)), (point\(.+\))\)$")]
+fn given_triangle(world: &mut IntegratorsWorld, matches: &[String]) {
+    let p1 = parse_tuple(world, &matches[0]);
+    let p2 = parse_tuple(world, &matches[1]);
+    let p3 = parse_tuple(world, &matches[2]);
+    world.shape = Some(Triangle::new(p1, p2, p3));
+}
+
+#[given(regex = r"^shape\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut IntegratorsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given(regex = r"^shape\.material\.reflective ← (.+)$")]
+fn given_reflective(world: &mut IntegratorsWorld, matches: &[String]) {
+    let shape = world.shape.as_ref().unwrap();
+    shape.write().unwrap().get_mut_material().reflective = matches[0].parse::<f64>().unwrap();
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut IntegratorsWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given("g ← group() containing shape")]
+fn given_group(world: &mut IntegratorsWorld) {
+    let g = Group::new();
+    Group::add_child(&g, world.shape.clone().unwrap());
+    world.group = Some(g);
+}
+
+#[given("g is added to w")]
+fn given_group_added(world: &mut IntegratorsWorld) {
+    world.world.add_object(world.group.clone().unwrap());
+}
+
+#[given(regex = r"^integrator ← (.+)$")]
+fn given_integrator(world: &mut IntegratorsWorld, matches: &[String]) {
+    world.integrator = Some(parse_integrator(&matches[0]));
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut IntegratorsWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("c ← integrator_color_at(integrator, w, r)")]
+fn when_color_at(world: &mut IntegratorsWorld) {
+    let mut rng = Rng::new(0);
+    let c = world.integrator.as_ref().unwrap().color_at(&world.world, world.ray.as_ref().unwrap(), &mut rng);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[then(regex = r"^c = id_color\((\d+)\)$")]
+fn check_id_color(world: &mut IntegratorsWorld, matches: &[String]) {
+    let target = ObjectIdView::id_color(matches[0].parse::<usize>().unwrap());
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^c = (color\(.+\))$")]
+fn check_color(world: &mut IntegratorsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^id_color\((\d+)\) = (.+)$")]
+fn check_palette(world: &mut IntegratorsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let c = ObjectIdView::id_color(matches[0].parse::<usize>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^the id colors of (\d+) to (\d+) are all different$")]
+fn check_palette_distinct(_world: &mut IntegratorsWorld, matches: &[String]) {
+    let colors: Vec<Tuples> = (matches[0].parse::<usize>().unwrap()..=matches[1].parse::<usize>().unwrap())
+        .map(ObjectIdView::id_color).collect();
+    for (i, a) in colors.iter().enumerate() {
+        for b in colors.iter().skip(i + 1) {
+            assert!(!a.is_equal(b), "{:?}", a);
+        }
+    }
+}
+
+#[given(regex = r"^settings ← render_settings\(\)$")]
+fn given_settings(world: &mut IntegratorsWorld) {
+    world.settings = RenderSettings::default();
+}
+
+#[given(regex = r"^settings\.integrator ← (.+)$")]
+fn given_settings_integrator(world: &mut IntegratorsWorld, matches: &[String]) {
+    world.settings.integrator = parse_integrator(&matches[0]);
+}
+
+#[then(regex = r"^settings\.integrator is (\w+)$")]
+fn check_settings_integrator(world: &mut IntegratorsWorld, matches: &[String]) {
+    assert!(format!("{:?}", world.settings.integrator).starts_with(&matches[0]));
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut IntegratorsWorld, matches: &[String]) {
+    let val = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(val[0] as usize, val[1] as usize, val[2]);
+}
+
+#[given(regex = r"^c\.transform ← view_transform\((point\(.+\)), (point\(.+\)), (vector\(.+\))\)$")]
+fn given_view_transform(world: &mut IntegratorsWorld, matches: &[String]) {
+    let from = parse_tuple(world, &matches[0]);
+    let to = parse_tuple(world, &matches[1]);
+    let up = parse_tuple(world, &matches[2]);
+    world.camera.transform = Matrix::view_transform(&from, &to, &up);
+}
+
+#[when("image ← render(c, w, settings)")]
+fn when_render(world: &mut IntegratorsWorld) {
+    world.image = Some(render_with_settings(&world.camera, &world.world, &world.settings));
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) = (.+)$")]
+fn check_pixel(world: &mut IntegratorsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[2]);
+    let c = world.image.as_ref().unwrap().pixel_at(matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct IntegratorsWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    group: Option<Arc<RwLock<Group>>>,
+    integrator: Option<Arc<dyn Integrator>>,
+    ray: Option<Ray>,
+    camera: Camera,
+    settings: RenderSettings,
+    image: Option<Canvas>,
+}
+
+fn main() {
+    futures::executor::block_on(IntegratorsWorld::run(
+        "tests/features/integrators.feature",
+    ));
+}
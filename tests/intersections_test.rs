@@ -6,8 +6,8 @@ use rtxch_lib::utils::parse_values_f64;
 use rtxch_lib::utils::is_equal_f64;
 use rtxch_lib::*;
 use utils::EPSILON;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 use rtxch_lib::Computations;
 
 
@@ -117,7 +117,7 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
         "object" => {
             let i = world.inter_sphere.get(&matches[0]).unwrap();
             let target = world.shape.get(&matches[2]).unwrap();
-            assert!(Rc::ptr_eq(i.object(), &target));
+            assert!(Arc::ptr_eq(i.object(), &target));
         },
         "count" => {
             let i = world.interlist_sphere.get(&matches[0]).unwrap();
@@ -148,7 +148,7 @@ fn check_prop_comps(world: &mut RaysWorld, matches: &[String]) {
         },
         "object" => {
             let i = world.inter_sphere.get(&"i".to_string()).unwrap();
-            assert!(Rc::ptr_eq(i.object(), &comps.object));
+            assert!(Arc::ptr_eq(i.object(), &comps.object));
         },
         "point" => {
             let target = world.tuple.get(&matches[2]).unwrap();
@@ -182,24 +182,24 @@ fn check_prop_comps(world: &mut RaysWorld, matches: &[String]) {
 #[given(regex = r"A ← glass_sphere\(\).+")]
 fn given_scenario_outline_a(world: &mut RaysWorld, _: &[String]) {
     let s = Sphere::glass_sphere();
-    s.borrow_mut().set_transform(&Matrix::scale(2.0,2.0,2.0));
-    s.borrow_mut().get_mut_material().refractive_index = 1.5;
+    s.write().unwrap().set_transform(&Matrix::scale(2.0,2.0,2.0));
+    s.write().unwrap().get_mut_material().refractive_index = 1.5;
     world.shape.insert("A".to_string(), s);
 }
 
 #[given(regex = r"B ← glass_sphere\(\).+")]
 fn given_scenario_outline_b(world: &mut RaysWorld, _: &[String]) {
     let s = Sphere::glass_sphere();
-    s.borrow_mut().set_transform(&Matrix::translate(0.0,0.0,-0.25));
-    s.borrow_mut().get_mut_material().refractive_index = 2.0;
+    s.write().unwrap().set_transform(&Matrix::translate(0.0,0.0,-0.25));
+    s.write().unwrap().get_mut_material().refractive_index = 2.0;
     world.shape.insert("B".to_string(), s);
 }
 
 #[given(regex = r"C ← glass_sphere\(\).+")]
 fn given_scenario_outline_c(world: &mut RaysWorld, _: &[String]) {
     let s = Sphere::glass_sphere();
-    s.borrow_mut().set_transform(&Matrix::translate(0.0,0.0,0.25));
-    s.borrow_mut().get_mut_material().refractive_index = 2.5;
+    s.write().unwrap().set_transform(&Matrix::translate(0.0,0.0,0.25));
+    s.write().unwrap().get_mut_material().refractive_index = 2.5;
     world.shape.insert("C".to_string(), s);
 }
 
@@ -233,7 +233,7 @@ fn check_prop_less_than_comps(world: &mut RaysWorld, matches: &[String]) {
 fn sphere2_alter(world: &mut RaysWorld) {
     let sphere = Sphere::new();
     let transform = Matrix::translate(0.0,0.0,1.0);
-    sphere.borrow_mut().set_transform(&transform);
+    sphere.write().unwrap().set_transform(&transform);
 
     world.shape.insert("shape".to_string(), sphere);
 }
@@ -242,7 +242,7 @@ fn sphere2_alter(world: &mut RaysWorld) {
 fn sphere2_alter_glass(world: &mut RaysWorld) {
     let sphere = Sphere::glass_sphere();
     let transform = Matrix::translate(0.0,0.0,1.0);
-    sphere.borrow_mut().set_transform(&transform);
+    sphere.write().unwrap().set_transform(&transform);
 
     world.shape.insert("shape".to_string(), sphere);
 }
@@ -261,7 +261,7 @@ fn check_sub_prop(world: &mut RaysWorld, matches: &[String]) {
         },
         "object" => {
             let target = world.shape.get(&matches[3]).unwrap();
-            assert!(Rc::ptr_eq(obj.object(), &target));
+            assert!(Arc::ptr_eq(obj.object(), &target));
         },
         _ => panic!()
     }
@@ -309,7 +309,7 @@ fn check_pos(world: &mut RaysWorld, matches: &[String]) {
 struct RaysWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    shape: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter_sphere:  HashMap<String, Intersection>,
     interlist_sphere: HashMap<String, IntersectionList>,
     hit: HashMap<String, Option<Intersection>>,
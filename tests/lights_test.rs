@@ -2,10 +2,10 @@ extern crate rtxch_lib;
 
 use std::collections::HashMap;
 use cucumber::{given, when, then, World};
-use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
 use rtxch_lib::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 use rtxch_lib::Matrix;
 
 #[given(regex = r"(.+) ← (point|vector|ray|sphere|intersect|translation|scaling|normal_at|rotation_z|color)\((.*)\)")]
@@ -87,40 +87,217 @@ fn when_item(world: &mut LightsWorld, matches: &[String]) {
 #[then(regex = r"([^\[\]]+)\.(origin|direction|t|object|count|position|intensity) = (.+)")]
 fn check_prop(world: &mut LightsWorld, matches: &[String]) {
     let prop = matches[1].as_str();
-    
+    let target = parse_tuple(world, &matches[2]);
+
     match prop {
         "origin" => {
             let r = world.ray.get(&matches[0]).unwrap();
-            let target = world.tuple.get(&matches[2]).unwrap();
-            assert!(r.origin().is_equal(target));
+            assert!(r.origin().is_equal(&target));
         },
         "direction" => {
-            let r = world.ray.get(&matches[0]).unwrap();
-            let target = world.tuple.get(&matches[2]).unwrap();
-            assert!(r.direction().is_equal(target));
+            let direction = match world.ray.get(&matches[0]) {
+                Some(r) => r.direction(),
+                None => match world.spot.get(&matches[0]) {
+                    Some(l) => l.direction(),
+                    None => world.dlight.get(&matches[0]).unwrap().direction(),
+                },
+            };
+            assert!(direction.is_equal(&target));
         },
         "position" => {
-            let i = world.plight.get(&matches[0]).unwrap();
-            let target = world.tuple.get(&matches[2]).unwrap();
-            assert!(i.position().is_equal(target));
+            let position = match world.plight.get(&matches[0]) {
+                Some(l) => l.position(),
+                None => world.spot.get(&matches[0]).unwrap().position(),
+            };
+            assert!(position.is_equal(&target));
         },
         "intensity" => {
-            let i = world.plight.get(&matches[0]).unwrap();
-            let target = world.tuple.get(&matches[2]).unwrap();
-            assert!(i.intensity().is_equal(target));
+            let i = world.get_light(&matches[0]);
+            assert!(i.intensity().is_equal(&target));
         },
         _ => panic!()
     }
 }
 
+// literal point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &LightsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_attenuation(input: &str) -> Attenuation {
+    match input {
+        "" | "none" => Attenuation::None,
+        "linear" => Attenuation::Linear,
+        "inverse_square" => Attenuation::InverseSquare,
+        _ => {
+            let v = parse_values_f64(&input.trim_start_matches("coefficients(").trim_end_matches(')').to_string());
+            Attenuation::Coefficients { constant: v[0], linear: v[1], quadratic: v[2] }
+        },
+    }
+}
+
+#[given(regex = r"^(\w+) ← point_light\((\w+\(.+\)|\w+), (\w+\(.+\)|\w+)\)(?: with (\w+) attenuation)?$")]
+fn create_point_light(world: &mut LightsWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[1]);
+    let intensity = parse_tuple(world, &matches[2]);
+    let light = lights::point_light(&position, &intensity).with_attenuation(parse_attenuation(&matches[3]));
+    world.plight.insert(matches[0].clone(), light);
+}
+
+#[given(regex = r"^(\w+) ← spot_light\((\w+\(.+\)|\w+), (\w+\(.+\)|\w+), (.+), (.+), (\w+\(.+\)|\w+)\)(?: with (\w+) attenuation)?$")]
+#[when(regex = r"^(\w+) ← spot_light\((\w+\(.+\)|\w+), (\w+\(.+\)|\w+), (.+), (.+), (\w+\(.+\)|\w+)\)(?: with (\w+) attenuation)?$")]
+fn create_spot_light(world: &mut LightsWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[1]);
+    let direction = parse_tuple(world, &matches[2]);
+    let inner = matches[3].parse::<f64>().unwrap();
+    let outer = matches[4].parse::<f64>().unwrap();
+    let intensity = parse_tuple(world, &matches[5]);
+    let light = lights::spot_light(&position, &direction, inner, outer, &intensity).with_attenuation(parse_attenuation(&matches[6]));
+    world.spot.insert(matches[0].clone(), light);
+}
+
+#[given(regex = r"^(\w+) ← directional_light\((\w+\(.+\)|\w+), (\w+\(.+\)|\w+)\)$")]
+fn create_directional_light(world: &mut LightsWorld, matches: &[String]) {
+    let direction = parse_tuple(world, &matches[1]);
+    let intensity = parse_tuple(world, &matches[2]);
+    world.dlight.insert(matches[0].clone(), lights::directional_light(&direction, &intensity));
+}
+
+#[given(regex = r"^(\w+) ← material\(\)$")]
+fn create_material(world: &mut LightsWorld, matches: &[String]) {
+    world.material.insert(matches[0].clone(), Material::material());
+}
+
+#[given("w ← default_world()")]
+fn create_world(world: &mut LightsWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given(regex = r"^w has only (\w+)$")]
+fn world_only_light(world: &mut LightsWorld, matches: &[String]) {
+    world.world.remove_lights();
+    if let Some(l) = world.spot.get(&matches[0]) {
+        world.world.add_light(l.clone());
+    } else {
+        world.world.add_light(world.dlight.get(&matches[0]).unwrap().clone());
+    }
+}
+
+#[when(regex = r"^(\w+) ← lighting\((\w+), (\w+), (\w+), (\w+), (\w+)\)$")]
+fn when_lighting(world: &mut LightsWorld, matches: &[String]) {
+    let m = world.material.get(&matches[1]).unwrap();
+    let light = world.get_light(&matches[2]);
+    let position = world.tuple.get(&matches[3]).unwrap();
+    let eyev = world.tuple.get(&matches[4]).unwrap();
+    let normalv = world.tuple.get(&matches[5]).unwrap();
+    let object: Arc<RwLock<dyn Shape>> = Sphere::new();
+    let result = lighting(m, &object, light, position, eyev, normalv, &Tuples::color(1.0, 1.0, 1.0));
+    world.tuple.insert(matches[0].clone(), result);
+}
+
+#[when(regex = r"^(\w+) ← color_at\(w, (\w+)\)$")]
+fn when_color_at(world: &mut LightsWorld, matches: &[String]) {
+    let r = world.ray.get(&matches[1]).unwrap();
+    let c = rtxch_lib::World::color_at(&world.world, r, MAX_ITERATIONS);
+    world.tuple.insert(matches[0].clone(), c);
+}
+
+#[then(regex = r"^(\w+) = (color\(.+\))$")]
+fn check_tuple(world: &mut LightsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^(\w+)\.(inner_angle|outer_angle) = (.+)$")]
+fn check_angle(world: &mut LightsWorld, matches: &[String]) {
+    let l = world.spot.get(&matches[0]).unwrap();
+    let angle = if matches[1] == "inner_angle" { l.inner_angle() } else { l.outer_angle() };
+    assert!(is_equal_f64(angle, matches[2].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^direction_to_light\((\w+), (\w+)\) = (.+)$")]
+fn check_direction_to_light(world: &mut LightsWorld, matches: &[String]) {
+    let p = world.tuple.get(&matches[1]).unwrap();
+    let target = parse_tuple(world, &matches[2]);
+    assert!(world.get_light(&matches[0]).direction_to_light(p).is_equal(&target));
+}
+
+#[then(regex = r"^distance_to_light\((\w+), (\w+)\) = (.+)$")]
+fn check_distance_to_light(world: &mut LightsWorld, matches: &[String]) {
+    let p = world.tuple.get(&matches[1]).unwrap();
+    let distance = world.get_light(&matches[0]).distance_to_light(p);
+    if matches[2] == "infinity" {
+        assert!(distance == f64::INFINITY);
+    } else {
+        assert!(is_equal_f64(distance, matches[2].parse::<f64>().unwrap()), "{distance}");
+    }
+}
+
+#[then(regex = r"^intensity_at\((\w+), (\w+)\) = (.+)$")]
+fn check_intensity_at(world: &mut LightsWorld, matches: &[String]) {
+    let p = world.tuple.get(&matches[1]).unwrap();
+    let target = parse_tuple(world, &matches[2]);
+    let intensity = world.get_light(&matches[0]).intensity_at(p);
+    assert!(intensity.is_equal(&target), "{:?}", intensity);
+}
+
+#[then(regex = r"^is_shadowed\(w, (\w+), (\w+)\) is (true|false)$")]
+fn check_shadowed(world: &mut LightsWorld, matches: &[String]) {
+    let p = world.tuple.get(&matches[0]).unwrap();
+    let light = world.get_light(&matches[1]);
+    let target = matches[2] == "true";
+    assert_eq!(rtxch_lib::World::is_shadowed(&world.world, p, light), target);
+}
+
+#[then(regex = r"^attenuation\((.+), (.+)\) = (.+)$")]
+fn check_attenuation_factor(_world: &mut LightsWorld, matches: &[String]) {
+    let attenuation = parse_attenuation(&matches[0]);
+    let distance = if matches[1] == "infinity" { f64::INFINITY } else { matches[1].parse::<f64>().unwrap() };
+    let factor = attenuation.factor(distance);
+    assert!(is_equal_f64(factor, matches[2].parse::<f64>().unwrap()), "{factor}");
+}
+
+#[then(regex = r"^(\w+)\.attenuation = (.+)$")]
+fn check_attenuation(world: &mut LightsWorld, matches: &[String]) {
+    assert_eq!(world.get_light(&matches[0]).attenuation(), parse_attenuation(&matches[1]));
+}
+
+impl LightsWorld {
+    fn get_light(&self, name: &String) -> &dyn Light {
+        if let Some(l) = self.plight.get(name) {
+            return l;
+        }
+        if let Some(l) = self.spot.get(name) {
+            return l;
+        }
+        self.dlight.get(name).unwrap()
+    }
+}
+
 #[derive(Debug, Default, World)]
 struct LightsWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    sphere: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    sphere: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter:  HashMap<String, IntersectionList>,
     matrix: HashMap<String, Matrix>,
     plight: HashMap<String, PointLight>,
+    spot: HashMap<String, SpotLight>,
+    dlight: HashMap<String, DirectionalLight>,
+    material: HashMap<String, Material>,
+    world: rtxch_lib::World,
 }
 
 fn main() {
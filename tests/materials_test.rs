@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use cucumber::{given, when, then, World};
 use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
 use rtxch_lib::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 #[given(regex = r"(.+) ← (point|stripe_pattern|vector|ray|sphere|intersect|translation|scaling|normal_at|rotation_z|material|color|point_light)\((.*)\)")]
 fn given_item(world: &mut MaterialsWorld, matches: &[String]) {
@@ -99,8 +99,9 @@ fn create_item(world: &mut MaterialsWorld, matches: &[String]) {
             let position = world.tuple.get(&v[2].to_string()).unwrap();
             let eyev = world.tuple.get(&v[3].to_string()).unwrap();
             let normalv = world.tuple.get(&v[4].to_string()).unwrap();
-            let obj: Rc<RefCell<dyn Shape>> = Sphere::new();
-            world.tuple.insert(t, lighting(m, &obj, pl, position, eyev, normalv, world.in_shadow));
+            let obj: Arc<RwLock<dyn Shape>> = Sphere::new();
+            let transmission = if world.in_shadow { Tuples::color(0.0, 0.0, 0.0) } else { Tuples::color(1.0, 1.0, 1.0) };
+            world.tuple.insert(t, lighting(m, &obj, pl, position, eyev, normalv, &transmission));
         },
         _ => panic!("{func} not implemented")
     }
@@ -137,7 +138,7 @@ fn set_ambient(world: &mut MaterialsWorld, matches: &[String]) {
         },
         "pattern" => {
             let v = world.patterns.get(&matches[1].to_string()).unwrap();
-            mat.pattern = Rc::clone(v);
+            mat.pattern = Arc::clone(v);
         },
         _ => panic!(),
     }
@@ -172,7 +173,7 @@ fn check_prop(world: &mut MaterialsWorld, matches: &[String]) {
         "color" => {
             let i = world.material.get(&matches[0]).unwrap();
             let target = world.tuple.get(&matches[2]).unwrap();
-            assert!(i.pattern.borrow().color_a().is_equal(target));
+            assert!(i.pattern.read().unwrap().color_a().is_equal(target));
         },
         "ambient" => {
             let i = world.material.get(&matches[0]).unwrap();
@@ -217,13 +218,13 @@ fn check_prop(world: &mut MaterialsWorld, matches: &[String]) {
 struct MaterialsWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    sphere: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    sphere: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter:  HashMap<String, IntersectionList>,
     matrix: HashMap<String, Matrix>,
     material: HashMap<String, Material>,
     plight: HashMap<String, PointLight>,
     in_shadow: bool,
-    patterns: HashMap<String, Rc<RefCell<dyn Pattern>>>,
+    patterns: HashMap<String, Arc<RwLock<dyn Pattern>>>,
 }
 
 fn main() {
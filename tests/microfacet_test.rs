@@ -0,0 +1,194 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &MicrofacetWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn parse_shading(input: &str) -> ShadingModel {
+    match input {
+        "phong" => ShadingModel::Phong,
+        "microfacet" => ShadingModel::Microfacet,
+        s => panic!("unknown shading model {s}"),
+    }
+}
+
+// sets a field of a material from the text of a step
+fn set_material_field(material: &mut Material, field: &str, value: &str, world: &MicrofacetWorld) {
+    match field {
+        "shading" => material.shading = parse_shading(value),
+        "color" => material.pattern = SingleColorPattern::new(parse_tuple(world, value)),
+        "roughness" => material.roughness = value.parse::<f64>().unwrap(),
+        "metallic" => material.metallic = value.parse::<f64>().unwrap(),
+        "reflective" => material.reflective = value.parse::<f64>().unwrap(),
+        "refractive_index" => material.refractive_index = value.parse::<f64>().unwrap(),
+        f => panic!("unknown material field {f}"),
+    }
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut MicrofacetWorld) {
+    world.material = Some(Material::material());
+}
+
+#[given(regex = r"^m\.(\w+) ← (.+)$")]
+#[when(regex = r"^m\.(\w+) ← (.+)$")]
+fn given_material_field(world: &mut MicrofacetWorld, matches: &[String]) {
+    let mut material = world.material.take().unwrap();
+    set_material_field(&mut material, &matches[0], &matches[1], world);
+    world.material = Some(material);
+}
+
+#[then(regex = r"^m\.shading = (\w+)$")]
+fn check_shading(world: &mut MicrofacetWorld, matches: &[String]) {
+    assert_eq!(world.material.as_ref().unwrap().shading, parse_shading(&matches[0]));
+}
+
+#[then(regex = r"^m\.(roughness|metallic) = (.+)$")]
+fn check_material_value(world: &mut MicrofacetWorld, matches: &[String]) {
+    let material = world.material.as_ref().unwrap();
+    let value = if matches[0] == "roughness" { material.roughness } else { material.metallic };
+    assert!(is_equal_f64(value, matches[1].parse::<f64>().unwrap()));
+}
+
+#[then(regex = r"^distribution\((.+), (.+)\) = (.+)$")]
+fn check_distribution(_world: &mut MicrofacetWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches.join(", "));
+    let d = microfacet::distribution(v[0], v[1]);
+    assert!(is_equal_f64(d, v[2]), "{d}");
+}
+
+#[then(regex = r"^geometry\((.+), (.+), (.+)\) = (.+)$")]
+fn check_geometry(_world: &mut MicrofacetWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches.join(", "));
+    let g = microfacet::geometry(v[0], v[1], v[2]);
+    assert!(is_equal_f64(g, v[3]), "{g}");
+}
+
+#[then(regex = r"^f0\(m, (color\(.+\))\) = (.+)$")]
+fn check_f0(world: &mut MicrofacetWorld, matches: &[String]) {
+    let base = parse_tuple(world, &matches[0]);
+    let target = parse_tuple(world, &matches[1]);
+    let f0 = microfacet::f0(world.material.as_ref().unwrap(), &base);
+    assert!(f0.is_equal(&target), "{:?}", f0);
+}
+
+#[then(regex = r"^fresnel\((color\(.+\)), (.+)\) = (.+)$")]
+fn check_fresnel(world: &mut MicrofacetWorld, matches: &[String]) {
+    let f0 = parse_tuple(world, &matches[0]);
+    let target = parse_tuple(world, &matches[2]);
+    let f = microfacet::fresnel(&f0, matches[1].parse::<f64>().unwrap());
+    assert!(f.is_equal(&target), "{:?}", f);
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut MicrofacetWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut MicrofacetWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.light = Some(lights::point_light(&position, &intensity));
+}
+
+#[when(regex = r"^(result|result2) ← lighting\(m, light, position, eyev, normalv\)$")]
+fn when_lighting(world: &mut MicrofacetWorld, matches: &[String]) {
+    let object: Arc<RwLock<dyn Shape>> = Sphere::new();
+    let result = render::lighting(world.material.as_ref().unwrap(), &object, world.light.as_ref().unwrap(),
+        world.tuple.get("position").unwrap(), world.tuple.get("eyev").unwrap(), world.tuple.get("normalv").unwrap(),
+        &Tuples::color(1.0, 1.0, 1.0));
+    world.tuple.insert(matches[0].clone(), result);
+}
+
+#[then(regex = r"^(result|c) = (.+)$")]
+fn check_tuple(world: &mut MicrofacetWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^(result|result2) is brighter than (result|result2)$")]
+fn check_brighter(world: &mut MicrofacetWorld, matches: &[String]) {
+    let a = world.tuple.get(&matches[0]).unwrap();
+    let b = world.tuple.get(&matches[1]).unwrap();
+    assert!(a.x > b.x, "{:?} {:?}", a, b);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut MicrofacetWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^w\.background ← solid\((.+)\)$")]
+fn given_solid(world: &mut MicrofacetWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.world.set_background(SolidBackground::new(color));
+}
+
+#[given("shape ← plane()")]
+fn given_plane(world: &mut MicrofacetWorld) {
+    world.shape = Some(Plane::new());
+}
+
+#[given(regex = r"^shape\.material\.(\w+) ← (.+)$")]
+fn given_shape_material(world: &mut MicrofacetWorld, matches: &[String]) {
+    let shape = world.shape.clone().unwrap();
+    let mut material = shape.read().unwrap().get_material().clone();
+    set_material_field(&mut material, &matches[0], &matches[1], world);
+    shape.write().unwrap().set_material(&material);
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut MicrofacetWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut MicrofacetWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut MicrofacetWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[derive(Debug, Default, World)]
+struct MicrofacetWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    material: Option<Material>,
+    light: Option<PointLight>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    ray: Option<Ray>,
+}
+
+fn main() {
+    futures::executor::block_on(MicrofacetWorld::run(
+        "tests/features/microfacet.feature",
+    ));
+}
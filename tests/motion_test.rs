@@ -0,0 +1,334 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::{Arc, RwLock};
+use cucumber::{given, when, then, World};
+use render::render_with_settings;
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &MotionWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+// 0, π/4, -π/2 ...
+fn parse_angle(input: &str) -> f64 {
+    let (sign, input) = match input.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, input),
+    };
+    let angle = match input.strip_prefix("π") {
+        Some("") => PI,
+        Some(divisor) => PI / divisor.trim_start_matches('/').parse::<f64>().unwrap(),
+        None => input.parse::<f64>().unwrap(),
+    };
+    sign * angle
+}
+
+// a product of translation(..), scaling(..), rotation_x/y/z(..) and identity_matrix
+fn parse_matrix(input: &str) -> Matrix {
+    let rx = cucumber::codegen::Regex::new(r"^(translation|scaling|rotation_x|rotation_y|rotation_z)\((.+)\)$").unwrap();
+    let mut out = Matrix::new(4);
+    for factor in input.split(" * ") {
+        let factor = factor.trim();
+        if factor == "identity_matrix" {
+            continue;
+        }
+        let m = rx.captures(factor).unwrap();
+        let matrix = match &m[1] {
+            "translation" => {
+                let v = parse_values_f64(&m[2].to_string());
+                Matrix::translate(v[0], v[1], v[2])
+            },
+            "scaling" => {
+                let v = parse_values_f64(&m[2].to_string());
+                Matrix::scale(v[0], v[1], v[2])
+            },
+            "rotation_x" => Matrix::rotate_x(parse_angle(&m[2])),
+            "rotation_y" => Matrix::rotate_y(parse_angle(&m[2])),
+            _ => Matrix::rotate_z(parse_angle(&m[2])),
+        };
+        out = &out * &matrix;
+    }
+    out
+}
+
+fn emissive_sphere() -> Arc<RwLock<Sphere>> {
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    sphere
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (vector\(.+\))\)$")]
+fn given_ray(world: &mut MotionWorld, matches: &[String]) {
+    world.ray = Some(Ray::new(parse_tuple(world, &matches[0]), parse_tuple(world, &matches[1])));
+}
+
+#[given(regex = r"^r ← ray_at\((.+\)), (vector\(.+\)), (.+)\)$")]
+fn given_ray_at(world: &mut MotionWorld, matches: &[String]) {
+    let time = matches[2].parse::<f64>().unwrap();
+    world.ray = Some(Ray::new_at(parse_tuple(world, &matches[0]), parse_tuple(world, &matches[1]), time));
+}
+
+#[when(regex = r"^r2 ← transform\(r, (.+)\)$")]
+fn when_transform(world: &mut MotionWorld, matches: &[String]) {
+    world.ray2 = Some(Ray::transform(world.ray.as_ref().unwrap(), &parse_matrix(&matches[0])));
+}
+
+#[then(regex = r"^(r|r2)\.time = (.+)$")]
+fn check_time(world: &mut MotionWorld, matches: &[String]) {
+    let r = if matches[0] == "r" { world.ray.as_ref() } else { world.ray2.as_ref() };
+    assert_eq!(r.unwrap().time(), matches[1].parse::<f64>().unwrap());
+}
+
+#[then(regex = r"^r2\.origin = (.+)$")]
+fn check_origin(world: &mut MotionWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let origin = world.ray2.as_ref().unwrap().origin();
+    assert!(origin.is_equal(&target), "{:?}", origin);
+}
+
+#[given(regex = r"^m ← motion\((.+\)|identity_matrix), (.+)\)$")]
+fn given_motion(world: &mut MotionWorld, matches: &[String]) {
+    world.motion = Some(Motion::new(&parse_matrix(&matches[0]), &parse_matrix(&matches[1])));
+}
+
+#[given(regex = r"^m ← keyframes\((.+)\)$")]
+fn given_keyframes(world: &mut MotionWorld, matches: &[String]) {
+    let rx = cucumber::codegen::Regex::new(r"([-\d.]+): (\w+\([^)]*\))").unwrap();
+    let keyframes: Vec<(f64, Matrix)> = rx.captures_iter(&matches[0])
+        .map(|m| (m[1].parse::<f64>().unwrap(), parse_matrix(&m[2])))
+        .collect();
+    world.motion = Some(Motion::from_keyframes(&keyframes));
+}
+
+#[then(regex = r"^transform_at\(m, (.+?)\) = (.+)$")]
+fn check_transform_at(world: &mut MotionWorld, matches: &[String]) {
+    let m = world.motion.as_ref().unwrap().transform_at(matches[0].parse::<f64>().unwrap());
+    let target = parse_matrix(&matches[1]);
+    assert!(m.is_equal(&target), "{:?}", m);
+}
+
+#[given(regex = r"^s ← (sphere|cube)\(\)$")]
+fn given_shape(world: &mut MotionWorld, matches: &[String]) {
+    world.shape = Some(match matches[0].as_str() {
+        "sphere" => Sphere::new(),
+        _ => Cube::new(),
+    });
+}
+
+#[given(regex = r"^s moves from (.+) to (.+)$")]
+fn given_shape_moves(world: &mut MotionWorld, matches: &[String]) {
+    let motion = Motion::new(&parse_matrix(&matches[0]), &parse_matrix(&matches[1]));
+    world.shape.as_ref().unwrap().write().unwrap().set_motion(&motion);
+}
+
+#[given("s has a black and white stripe pattern")]
+fn given_stripes(world: &mut MotionWorld) {
+    let mut material = Material::material();
+    material.pattern = StripePattern::new(Tuples::color(1.0, 1.0, 1.0), Tuples::color(0.0, 0.0, 0.0));
+    world.shape.as_ref().unwrap().write().unwrap().set_material(&material);
+}
+
+#[when("xs ← intersect(s, r)")]
+fn when_intersect(world: &mut MotionWorld) {
+    world.xs = Some(<dyn Shape>::intersect(world.shape.as_ref().unwrap(), world.ray.as_ref().unwrap()));
+}
+
+#[then(regex = r"^xs\.count = (\d+)$")]
+fn check_count(world: &mut MotionWorld, matches: &[String]) {
+    assert_eq!(world.xs.as_ref().unwrap().xs().len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^xs is (.+)$")]
+fn check_xs(world: &mut MotionWorld, matches: &[String]) {
+    let ts: Vec<f64> = world.xs.as_ref().unwrap().xs().iter().map(|i| i.t()).collect();
+    if matches[0] == "empty" {
+        assert!(ts.is_empty(), "{:?}", ts);
+        return;
+    }
+    let target = parse_values_f64(&matches[0]);
+    assert_eq!(ts.len(), target.len());
+    for (t, expected) in ts.iter().zip(target.iter()) {
+        assert!((t - expected).abs() < 0.0001, "{:?}", ts);
+    }
+}
+
+#[when(regex = r"^n ← normal_at_time\(s, (point\(.+\)), (.+)\)$")]
+fn when_normal_at_time(world: &mut MotionWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    let n = <dyn Shape>::normal_at_time(world.shape.as_ref().unwrap(), &p, 0.0, 0.0, matches[1].parse::<f64>().unwrap());
+    world.tuple.insert("n".to_string(), n);
+}
+
+#[then(regex = r"^n = (.+)$")]
+fn check_normal(world: &mut MotionWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let n = world.tuple.get("n").unwrap();
+    assert!(n.is_equal(&target), "{:?}", n);
+}
+
+#[when("box ← parent_space_bounds(s)")]
+fn when_bounds(world: &mut MotionWorld) {
+    world.bounds = <dyn Shape>::parent_space_bounds(world.shape.as_ref().unwrap());
+}
+
+#[then(regex = r"^box\.(min|max) = (.+)$")]
+fn check_bounds(world: &mut MotionWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let value = if matches[0] == "min" { world.bounds.min } else { world.bounds.max };
+    assert!(value.is_equal(&target), "{:?}", value);
+}
+
+#[then(regex = r"^box\.(min|max) is within (.+) of (.+)$")]
+fn check_bounds_near(world: &mut MotionWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[2]);
+    let mut offset = if matches[0] == "min" { world.bounds.min } else { world.bounds.max };
+    let distance = offset.subtract(&target).magnitude();
+    assert!(distance <= matches[1].parse::<f64>().unwrap(), "{:?}", world.bounds);
+}
+
+#[then(regex = r"^box contains (.+)$")]
+fn check_bounds_contain(world: &mut MotionWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    assert!(world.bounds.contains_point(&p), "{:?}", world.bounds);
+}
+
+#[then(regex = r"^color_at_object_at\(s, (point\(.+\)), (.+)\) = (.+)$")]
+fn check_pattern(world: &mut MotionWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    let target = parse_tuple(world, &matches[2]);
+    let s = world.shape.as_ref().unwrap();
+    let pattern = s.read().unwrap().get_material().pattern.clone();
+    let c = pattern.read().unwrap().color_at_object_at(s, &p, matches[1].parse::<f64>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut MotionWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("s is added to w")]
+fn given_added(world: &mut MotionWorld) {
+    world.world.add_object(world.shape.as_ref().unwrap().clone());
+}
+
+#[then(regex = r"^transmission_towards_at\(w, (point\(.+\)), (vector\(.+\)), (.+), (.+)\) = (.+)$")]
+fn check_transmission(world: &mut MotionWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[0]);
+    let sample = LightSample {
+        direction: parse_tuple(world, &matches[1]),
+        distance: matches[2].parse::<f64>().unwrap(),
+        intensity: Tuples::color(1.0, 1.0, 1.0),
+    };
+    let target = parse_tuple(world, &matches[4]);
+    let c = rtxch_lib::World::transmission_towards_at(&world.world, &point, &sample, matches[3].parse::<f64>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then(regex = r"^intensity_at_time\(w, (point\(.+?\)), point_light\((point\(.+?\)), (color\(.+?\))\), (.+)\) = (.+)$")]
+fn check_intensity(world: &mut MotionWorld, matches: &[String]) {
+    let point = parse_tuple(world, &matches[0]);
+    let light = lights::point_light(&parse_tuple(world, &matches[1]), &parse_tuple(world, &matches[2]));
+    let target = parse_tuple(world, &matches[4]);
+    let c = rtxch_lib::World::intensity_at_time(&world.world, &point, &light, matches[3].parse::<f64>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut MotionWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(v[0] as usize, v[1] as usize, v[2]);
+}
+
+#[given(regex = r"^c\.shutter_(open|close) ← (.+)$")]
+fn given_shutter(world: &mut MotionWorld, matches: &[String]) {
+    let value = matches[1].parse::<f64>().unwrap();
+    match matches[0].as_str() {
+        "open" => world.camera.shutter_open = value,
+        _ => world.camera.shutter_close = value,
+    }
+}
+
+#[then(regex = r"^c\.shutter_(open|close) = (.+)$")]
+fn check_shutter(world: &mut MotionWorld, matches: &[String]) {
+    let value = if matches[0] == "open" { world.camera.shutter_open } else { world.camera.shutter_close };
+    assert_eq!(value, matches[1].parse::<f64>().unwrap());
+}
+
+#[then(regex = r"^shutter_time\(c, (.+)\) = (.+)$")]
+fn check_shutter_time(world: &mut MotionWorld, matches: &[String]) {
+    let time = Camera::shutter_time(&world.camera, matches[0].parse::<f64>().unwrap());
+    assert!((time - matches[1].parse::<f64>().unwrap()).abs() < 0.0001, "{time}");
+}
+
+#[given("c.projection ← orthographic(4)")]
+fn given_orthographic(world: &mut MotionWorld) {
+    world.camera.projection = Projection::Orthographic { size: 4.0 };
+}
+
+#[given(regex = r"^the emissive sphere moves from (.+) to (.+)$")]
+fn given_moving_emissive_sphere(world: &mut MotionWorld, matches: &[String]) {
+    let sphere = emissive_sphere();
+    let motion = Motion::new(&parse_matrix(&matches[0]), &parse_matrix(&matches[1]));
+    sphere.write().unwrap().set_motion(&motion);
+    world.world.add_object(sphere);
+}
+
+#[when(regex = r"^image ← render_with_settings\(c, w, jittered, (\d+)\)$")]
+fn when_render(world: &mut MotionWorld, matches: &[String]) {
+    let settings = RenderSettings::new(Sampling::Jittered, matches[0].parse::<usize>().unwrap());
+    world.image = render_with_settings(&world.camera, &world.world, &settings);
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) is (white|black|gray)$")]
+fn check_pixel(world: &mut MotionWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let c = world.image.pixel_at(x, y);
+    match matches[2].as_str() {
+        "white" => assert!(c.is_equal(&Tuples::color(1.0, 1.0, 1.0)), "{:?}", c),
+        "black" => assert!(c.is_equal(&Tuples::color(0.0, 0.0, 0.0)), "{:?}", c),
+        _ => assert!((0..3).all(|i| c.get_at_idx(i) > 0.1 && c.get_at_idx(i) < 0.9), "{:?}", c),
+    }
+}
+
+#[derive(Debug, Default, World)]
+struct MotionWorld {
+    ray: Option<Ray>,
+    ray2: Option<Ray>,
+    motion: Option<Motion>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    xs: Option<IntersectionList>,
+    bounds: Bounds,
+    tuple: HashMap<String, Tuples>,
+    world: rtxch_lib::World,
+    camera: Camera,
+    image: Canvas,
+}
+
+fn main() {
+    futures::executor::block_on(MotionWorld::run(
+        "tests/features/motion.feature",
+    ));
+}
@@ -0,0 +1,195 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &NestedWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+fn clear_material(refractive_index: f64, priority: u32) -> Material {
+    let mut material = Material::material();
+    material.ambient = 0.0;
+    material.diffuse = 0.0;
+    material.specular = 0.0;
+    material.transparency = 1.0;
+    material.refractive_index = refractive_index;
+    material.medium_priority = priority;
+    material
+}
+
+#[given("m ← material()")]
+fn given_material(world: &mut NestedWorld) {
+    world.material = Some(Material::material());
+}
+
+#[then(regex = r"^m\.medium_priority = (\d+)$")]
+fn check_medium_priority(world: &mut NestedWorld, matches: &[String]) {
+    assert_eq!(world.material.as_ref().unwrap().medium_priority, matches[0].parse::<u32>().unwrap());
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut NestedWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("the emissive sphere above the glass")]
+fn given_emissive_sphere(world: &mut NestedWorld) {
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&Matrix::translate(0.0, 5.0, 0.0));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+// a glass with a bottom from y = -2 to -1.5 and walls from 1.5 to 2, filled with water up to
+// y = 1. The water reaches 0.1 into the bottom and 0.05 into the walls of the glass
+#[given(regex = r"^the glass of water with priorities (\d+) and (\d+)$")]
+fn given_glass_of_water(world: &mut NestedWorld, matches: &[String]) {
+    let glass_material = clear_material(1.5, matches[0].parse::<u32>().unwrap());
+    let outside: Arc<RwLock<dyn Shape>> = Cube::new();
+    outside.write().unwrap().set_transform(&Matrix::scale(2.0, 2.0, 2.0));
+    outside.write().unwrap().set_material(&glass_material);
+    let inside: Arc<RwLock<dyn Shape>> = Cube::new();
+    inside.write().unwrap().set_transform(&(&Matrix::translate(0.0, 1.0, 0.0) * &Matrix::scale(1.5, 2.5, 1.5)));
+    inside.write().unwrap().set_material(&glass_material);
+    let glass: Arc<RwLock<dyn Shape>> = Csg::new(CsgOperation::Difference, outside, inside);
+
+    let water: Arc<RwLock<dyn Shape>> = Cube::new();
+    water.write().unwrap().set_transform(&(&Matrix::translate(0.0, -0.3, 0.0) * &Matrix::scale(1.55, 1.3, 1.55)));
+    water.write().unwrap().set_material(&clear_material(1.33, matches[1].parse::<u32>().unwrap()));
+
+    world.world.add_object(glass.clone());
+    world.world.add_object(water.clone());
+    world.shape.insert("glass".to_string(), glass);
+    world.shape.insert("water".to_string(), water);
+}
+
+// a small opaque sphere in the middle of the water
+#[given("the opaque spoon in the water")]
+fn given_spoon(world: &mut NestedWorld) {
+    let spoon: Arc<RwLock<dyn Shape>> = Sphere::new();
+    spoon.write().unwrap().set_transform(&Matrix::scale(0.2, 0.2, 0.2));
+    world.world.add_object(spoon.clone());
+    world.shape.insert("spoon".to_string(), spoon);
+}
+
+#[given(regex = r"^the water absorbs with density (.+)$")]
+fn given_water_absorbs(world: &mut NestedWorld, matches: &[String]) {
+    let mut water = world.shape.get("water").unwrap().write().unwrap();
+    let material = water.get_mut_material();
+    material.absorption = Tuples::color(0.5, 0.5, 0.5);
+    material.absorption_density = matches[0].parse::<f64>().unwrap();
+}
+
+#[given(regex = r"^light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut NestedWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.light = Some(lights::point_light(&position, &intensity));
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut NestedWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[given("xs ← intersect_world(w, r)")]
+fn given_intersect_world(world: &mut NestedWorld) {
+    world.xs = Some(rtxch_lib::World::intersect_world(&world.world, world.ray.as_ref().unwrap()));
+}
+
+#[when(regex = r"^comps ← prepare_computations\(xs\[(\d+)\], r, xs\)$")]
+fn when_prepare_computations(world: &mut NestedWorld, matches: &[String]) {
+    let xs = world.xs.as_ref().unwrap();
+    let i = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    world.comps = Some(Intersection::prep_computations(i, world.ray.as_ref().unwrap(), xs));
+}
+
+#[when("i ← hit(xs)")]
+fn when_hit(world: &mut NestedWorld) {
+    world.hit = IntersectionList::hit(world.xs.as_ref().unwrap()).cloned();
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut NestedWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^t ← transmission_towards\(w, (point\(.+\)), light\)$")]
+fn when_transmission(world: &mut NestedWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    let sample = world.light.as_ref().unwrap().samples(&p)[0];
+    let t = rtxch_lib::World::transmission_towards(&world.world, &p, &sample);
+    world.tuple.insert("t".to_string(), t);
+}
+
+#[then(regex = r"^comps\.(n1|n2) = (.+)$")]
+fn check_n(world: &mut NestedWorld, matches: &[String]) {
+    let comps = world.comps.as_ref().unwrap();
+    let n = if matches[0] == "n1" { comps.n1 } else { comps.n2 };
+    assert!(is_equal_f64(n, matches[1].parse::<f64>().unwrap()), "{n}");
+}
+
+#[then(regex = r"^i\.t = (.+)$")]
+fn check_hit_t(world: &mut NestedWorld, matches: &[String]) {
+    let t = world.hit.as_ref().unwrap().t();
+    assert!(is_equal_f64(t, matches[0].parse::<f64>().unwrap()), "{t}");
+}
+
+#[then(regex = r"^i\.object is (\w+)$")]
+fn check_hit_object(world: &mut NestedWorld, matches: &[String]) {
+    let object = world.hit.as_ref().unwrap().object();
+    assert!(<dyn Shape>::includes(world.shape.get(&matches[0]).unwrap(), object));
+}
+
+#[then(regex = r"^(c|t) = (color\(.+\))$")]
+fn check_color(world: &mut NestedWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let c = world.tuple.get(&matches[0]).unwrap();
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct NestedWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    material: Option<Material>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    light: Option<PointLight>,
+    ray: Option<Ray>,
+    xs: Option<IntersectionList>,
+    hit: Option<Intersection>,
+    comps: Option<Computations>,
+}
+
+fn main() {
+    futures::executor::block_on(NestedWorld::run(
+        "tests/features/nested_dielectrics.feature",
+    ));
+}
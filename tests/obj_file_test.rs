@@ -0,0 +1,144 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World, gherkin::Step};
+use rtxch_lib::utils::{parse_values_f64, parse_values_usize, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+#[given(regex = r"^(.+) ← a file containing:$")]
+fn file_containing(world: &mut ObjWorld, step: &Step, matches: &[String]) {
+    let content = step.docstring.as_ref().unwrap();
+    // the docstring starts with the line break after the opening quotes
+    world.file.insert(matches[0].clone(), content.strip_prefix('\n').unwrap_or(content).to_string());
+}
+
+#[when(regex = r"^parser ← parse_obj_file\((.+)\)$")]
+fn parse_file(world: &mut ObjWorld, matches: &[String]) {
+    let content = world.file.get(&matches[0]).unwrap();
+    world.parser = Some(ObjParser::parse_obj(content));
+}
+
+#[when(regex = r"^(.+) ← parser.default_group$")]
+fn default_group(world: &mut ObjWorld, matches: &[String]) {
+    let parser = world.parser();
+    let g = parser.default_group().clone();
+    world.group.insert(matches[0].clone(), g);
+}
+
+#[when(regex = r#"^(.+) ← parser group "(.+)"$"#)]
+fn named_group(world: &mut ObjWorld, matches: &[String]) {
+    let parser = world.parser();
+    let g = parser.group(&matches[1]).unwrap().clone();
+    world.group.insert(matches[0].clone(), g);
+}
+
+#[when(regex = r"^(.+) ← obj_to_group\(parser\)$")]
+fn obj_to_group(world: &mut ObjWorld, matches: &[String]) {
+    let g = world.parser().to_group();
+    world.group.insert(matches[0].clone(), g);
+}
+
+#[then(regex = r"^parser should have ignored (\d+) lines$")]
+fn check_ignored(world: &mut ObjWorld, matches: &[String]) {
+    let count = matches[0].parse::<usize>().unwrap();
+    assert_eq!(world.parser().ignored, count);
+}
+
+#[then(regex = r"^parser should fail on line (\d+)$")]
+fn check_error(world: &mut ObjWorld, matches: &[String]) {
+    let line = matches[0].parse::<usize>().unwrap();
+    match world.parser.as_ref().unwrap() {
+        Ok(_) => panic!("expected an error on line {line}"),
+        Err(e) => assert_eq!(e.line, line, "{e}"),
+    }
+}
+
+#[then(regex = r"^parser\.(vertices|normals)\[(\d+)\] = (point|vector)\((.+)\)$")]
+fn check_record(world: &mut ObjWorld, matches: &[String]) {
+    let parser = world.parser();
+    let idx = matches[1].parse::<usize>().unwrap();
+    let v = parse_values_f64(&matches[3]);
+    let (actual, target) = match matches[0].as_str() {
+        "vertices" => (parser.vertex(idx).unwrap(), Tuples::point(v[0], v[1], v[2])),
+        _ => (parser.normal(idx).unwrap(), Tuples::vector(v[0], v[1], v[2])),
+    };
+    assert!(actual.is_equal(&target), "{:?} {:?}", actual, target);
+}
+
+#[then(regex = r"^(.+) has (\d+) children$")]
+fn check_children(world: &mut ObjWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let count = matches[1].parse::<usize>().unwrap();
+    assert_eq!(g.read().unwrap().get_children().len(), count);
+}
+
+#[then(regex = r#"^(.+) includes "(.+)" from parser$"#)]
+fn check_includes(world: &mut ObjWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let named: Arc<RwLock<dyn Shape>> = world.parser().group(&matches[1]).unwrap().clone();
+    assert!(g.read().unwrap().contains(&named));
+}
+
+// aims a ray at a known barycentric position of the expected corners,
+// the reported u/v only match if the triangle has the same corners in the same order
+fn assert_corners(child: &Arc<RwLock<dyn Shape>>, p1: &Tuples, p2: &Tuples, p3: &Tuples) {
+    let e1 = p2.clone().subtract(p1);
+    let e2 = p3.clone().subtract(p1);
+    let normal = Tuples::cross(&e2, &e1).normalize();
+    let target = p1.clone().add(&e1.clone().scale(0.2)).add(&e2.clone().scale(0.3));
+    let r = Ray::new(target.clone().subtract(&normal), normal);
+    let xs = child.read().unwrap().intersections_local(&r, child);
+    assert_eq!(xs.count(), 1);
+    let hit = &xs.xs()[0];
+    assert!(is_equal_f64(hit.u(), 0.2) && is_equal_f64(hit.v(), 0.3), "u: {} v: {}", hit.u(), hit.v());
+}
+
+#[then(regex = r"^child (\d+) of (.+) has points (.+)$")]
+fn check_points(world: &mut ObjWorld, matches: &[String]) {
+    let idx = matches[0].parse::<usize>().unwrap();
+    let g = world.group.get(&matches[1]).unwrap();
+    let child = g.read().unwrap().get_children()[idx].clone();
+    let v = parse_values_usize(&matches[2]);
+    let parser = world.parser();
+    let (p1, p2, p3) = (parser.vertex(v[0]).unwrap(), parser.vertex(v[1]).unwrap(), parser.vertex(v[2]).unwrap());
+    assert_corners(&child, p1, p2, p3);
+}
+
+#[then(regex = r"^child (\d+) of (.+) is a smooth triangle with normals (.+)$")]
+fn check_normals(world: &mut ObjWorld, matches: &[String]) {
+    let idx = matches[0].parse::<usize>().unwrap();
+    let g = world.group.get(&matches[1]).unwrap();
+    let child = g.read().unwrap().get_children()[idx].clone();
+    assert_eq!(child.read().unwrap().get_type(), "SmoothTriangle");
+    let v = parse_values_usize(&matches[2]);
+    let parser = world.parser();
+    let p = Tuples::point(0.0, 0.0, 0.0);
+    // the interpolated normal equals n1 at u = v = 0, n2 at u = 1 and n3 at v = 1
+    let n1 = child.read().unwrap().normal_at_local_uv(&p, 0.0, 0.0);
+    let n2 = child.read().unwrap().normal_at_local_uv(&p, 1.0, 0.0);
+    let n3 = child.read().unwrap().normal_at_local_uv(&p, 0.0, 1.0);
+    assert!(n1.is_equal(parser.normal(v[0]).unwrap()));
+    assert!(n2.is_equal(parser.normal(v[1]).unwrap()));
+    assert!(n3.is_equal(parser.normal(v[2]).unwrap()));
+}
+
+impl ObjWorld {
+    fn parser(&self) -> &ObjParser {
+        self.parser.as_ref().unwrap().as_ref().unwrap()
+    }
+}
+
+#[derive(Debug, Default, World)]
+struct ObjWorld {
+    file: HashMap<String, String>,
+    parser: Option<Result<ObjParser, ObjError>>,
+    group: HashMap<String, Arc<RwLock<Group>>>,
+}
+
+fn main() {
+    futures::executor::block_on(ObjWorld::run(
+        "tests/features/obj_file.feature",
+    ));
+}
@@ -0,0 +1,256 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::path_tracer::cosine_hemisphere;
+use rtxch_lib::render::render_with_settings;
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &PathTracingWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut PathTracingWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[when(regex = r"^d ← cosine_hemisphere\(n, (.+), (.+)\)$")]
+fn when_cosine_hemisphere(world: &mut PathTracingWorld, matches: &[String]) {
+    let n = world.tuple.get("n").unwrap().clone().normalize();
+    let d = cosine_hemisphere(&n, matches[0].parse::<f64>().unwrap(), matches[1].parse::<f64>().unwrap());
+    world.tuple.insert("d".to_string(), d);
+}
+
+#[then("d is normalized")]
+fn check_normalized(world: &mut PathTracingWorld) {
+    let d = world.tuple.get("d").unwrap();
+    assert!(is_equal_f64(d.clone().magnitude(), 1.0), "{:?}", d);
+}
+
+#[then("dot(d, n) >= 0")]
+fn check_hemisphere(world: &mut PathTracingWorld) {
+    let d = world.tuple.get("d").unwrap();
+    let n = world.tuple.get("n").unwrap();
+    assert!(Tuples::dot(d, n) >= 0.0, "{:?}", d);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut PathTracingWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^w\.background ← solid\((.+)\)$")]
+fn given_solid(world: &mut PathTracingWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    world.world.set_background(SolidBackground::new(color));
+}
+
+#[given(regex = r"^w\.environment ← canvas\((\d+), (\d+)\) filled with (.+) with (\d+) samples$")]
+fn given_environment(world: &mut PathTracingWorld, matches: &[String]) {
+    let (width, height) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let color = parse_tuple(world, &matches[2]);
+    let mut map = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            map.write_pixel(x, y, &color);
+        }
+    }
+    world.world.set_environment_map(map, matches[3].parse::<usize>().unwrap());
+}
+
+#[given(regex = r"^w\.light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut PathTracingWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.world.add_light(lights::point_light(&position, &intensity));
+}
+
+#[given(regex = r"^shape ← (sphere|glass_sphere|plane)\(\)$")]
+fn given_shape(world: &mut PathTracingWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = match matches[0].as_str() {
+        "sphere" => Sphere::new(),
+        "glass_sphere" => Sphere::glass_sphere(),
+        _ => Plane::new(),
+    };
+    world.shape = Some(shape);
+}
+
+#[given(regex = r"^shape\.transform ← (translation|scaling)\((.+)\)$")]
+fn given_transform(world: &mut PathTracingWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[1]);
+    let transform = if matches[0] == "translation" { Matrix::translate(v[0], v[1], v[2]) } else { Matrix::scale(v[0], v[1], v[2]) };
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&transform);
+}
+
+#[given(regex = r"^shape\.material\.(color|emission) ← (.+)$")]
+fn given_material_color(world: &mut PathTracingWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[1]);
+    let mut shape = world.shape.as_ref().unwrap().write().unwrap();
+    let material = shape.get_mut_material();
+    if matches[0] == "color" {
+        material.pattern = SingleColorPattern::new(color);
+    } else {
+        material.emission = color;
+    }
+}
+
+#[given(regex = r"^shape\.material\.(diffuse|ambient|specular|reflective) ← ([-\d.]+)$")]
+fn given_material_value(world: &mut PathTracingWorld, matches: &[String]) {
+    let value = matches[1].parse::<f64>().unwrap();
+    let mut shape = world.shape.as_ref().unwrap().write().unwrap();
+    let material = shape.get_mut_material();
+    match matches[0].as_str() {
+        "diffuse" => material.diffuse = value,
+        "ambient" => material.ambient = value,
+        "specular" => material.specular = value,
+        _ => material.reflective = value,
+    }
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut PathTracingWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^pt ← path_tracer\((\d+)\)$")]
+fn given_path_tracer(world: &mut PathTracingWorld, matches: &[String]) {
+    world.tracer = PathTracer::new(matches[0].parse::<usize>().unwrap());
+}
+
+#[given(regex = r"^pt\.roulette_depth ← (\d+)$")]
+fn given_roulette_depth(world: &mut PathTracingWorld, matches: &[String]) {
+    world.tracer.roulette_depth = matches[0].parse::<usize>().unwrap();
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut PathTracingWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("c ← path_color_at(pt, w, r)")]
+fn when_path_color_at(world: &mut PathTracingWorld) {
+    let mut rng = Rng::new(0);
+    let c = world.tracer.color_at(&world.world, world.ray.as_ref().unwrap(), &mut rng);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[when(regex = r"^c ← the average of (\d+) paths\(pt, w, r\)$")]
+fn when_average_paths(world: &mut PathTracingWorld, matches: &[String]) {
+    let n = matches[0].parse::<usize>().unwrap();
+    let mut rng = Rng::new(0);
+    let mut sum = Tuples::color(0.0, 0.0, 0.0);
+    for _ in 0..n {
+        sum.add(&world.tracer.color_at(&world.world, world.ray.as_ref().unwrap(), &mut rng));
+    }
+    world.tuple.insert("c".to_string(), sum.scale(1.0 / n as f64));
+}
+
+#[when("expected ← color_at(w, r)")]
+fn when_color_at(world: &mut PathTracingWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("expected".to_string(), c);
+}
+
+#[then(regex = r"^(c|d) = (.+)$")]
+fn check_tuple(world: &mut PathTracingWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?} != {:?}", t, target);
+}
+
+// monte carlo estimates only get close to the exact value
+#[then(regex = r"^c is close to (.+)$")]
+fn check_close(world: &mut PathTracingWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[0]);
+    let c = world.tuple.get("c").unwrap();
+    assert!((0..3).all(|i| (c.get_at_idx(i) - target.get_at_idx(i)).abs() < 0.1), "{:?}", c);
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut PathTracingWorld, matches: &[String]) {
+    let val = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(val[0] as usize, val[1] as usize, val[2]);
+}
+
+#[given(regex = r"^c\.transform ← view_transform\((point\(.+\)), (point\(.+\)), (vector\(.+\))\)$")]
+fn given_view_transform(world: &mut PathTracingWorld, matches: &[String]) {
+    let from = parse_tuple(world, &matches[0]);
+    let to = parse_tuple(world, &matches[1]);
+    let up = parse_tuple(world, &matches[2]);
+    world.camera.transform = Matrix::view_transform(&from, &to, &up);
+}
+
+#[given(regex = r"^settings ← render_settings\(jittered, (\d+)\)$")]
+fn given_settings(world: &mut PathTracingWorld, matches: &[String]) {
+    world.settings = RenderSettings::new(Sampling::Jittered, matches[0].parse::<usize>().unwrap());
+}
+
+#[given(regex = r"^settings\.integrator ← path_tracing\((\d+)\)$")]
+fn given_integrator(world: &mut PathTracingWorld, matches: &[String]) {
+    world.settings.integrator = Arc::new(PathTracer::new(matches[0].parse::<usize>().unwrap()));
+}
+
+#[when("image ← render(c, w, settings)")]
+fn when_render(world: &mut PathTracingWorld) {
+    let image = render_with_settings(&world.camera, &world.world, &world.settings);
+    world.image.insert("image".to_string(), image);
+}
+
+#[when(regex = r"^(image|expected) ← render_with_threads\(c, w, settings, (\d+)\)$")]
+fn when_render_threads(world: &mut PathTracingWorld, matches: &[String]) {
+    let settings = RenderSettings { threads: matches[1].parse::<usize>().unwrap(), ..world.settings.clone() };
+    let image = render_with_settings(&world.camera, &world.world, &settings);
+    world.image.insert(matches[0].clone(), image);
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) = (.+)$")]
+fn check_pixel(world: &mut PathTracingWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[2]);
+    let image = world.image.get("image").unwrap();
+    let c = image.pixel_at(matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[then("image = expected")]
+fn check_images(world: &mut PathTracingWorld) {
+    let image = world.image.get("image").unwrap();
+    let expected = world.image.get("expected").unwrap();
+    assert_eq!(image.get_pixels(), expected.get_pixels());
+}
+
+#[derive(Debug, Default, World)]
+struct PathTracingWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    tracer: PathTracer,
+    ray: Option<Ray>,
+    camera: Camera,
+    settings: RenderSettings,
+    image: HashMap<String, Canvas>,
+}
+
+fn main() {
+    futures::executor::block_on(PathTracingWorld::run(
+        "tests/features/path_tracing.feature",
+    ));
+}
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use cucumber::{given, when, then, World};
 use rtxch_lib::utils::parse_values_f64;
 use rtxch_lib::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 #[given(regex = r"(.+) ← (point|vector|ray|test_pattern|stripe_pattern|checkers_pattern|gradient_pattern|ring_pattern|sphere|intersect|translation|scaling|normal_at|rotation_z|material|color|point_light)\((.*)\)")]
 fn given_item(world: &mut MaterialsWorld, matches: &[String]) {
@@ -120,8 +120,9 @@ fn create_item(world: &mut MaterialsWorld, matches: &[String]) {
             let position = world.tuple.get(&v[2].to_string()).unwrap();
             let eyev = world.tuple.get(&v[3].to_string()).unwrap();
             let normalv = world.tuple.get(&v[4].to_string()).unwrap();
-            let obj: Rc<RefCell<dyn Shape>> = Sphere::new();
-            world.tuple.insert(t, lighting(m, &obj, pl, position, eyev, normalv, world.in_shadow));
+            let obj: Arc<RwLock<dyn Shape>> = Sphere::new();
+            let transmission = if world.in_shadow { Tuples::color(0.0, 0.0, 0.0) } else { Tuples::color(1.0, 1.0, 1.0) };
+            world.tuple.insert(t, lighting(m, &obj, pl, position, eyev, normalv, &transmission));
         }
         _ => panic!("{func} not implemented")
     }
@@ -144,7 +145,7 @@ fn check_color_at(world: &mut MaterialsWorld, matches: &[String]) {
     let pattern = world.patterns.get(&matches[0]).unwrap();
     let val = parse_values_f64(&matches[1]);
     let point = Tuples::point(val[0],val[1],val[2]);
-    let bound_pattern = pattern.borrow();
+    let bound_pattern = pattern.read().unwrap();
     let result = bound_pattern.color_at(&point);
     let target = world.tuple.get(&matches[2]).unwrap();
     assert!(result.is_equal(target));
@@ -156,7 +157,7 @@ fn check_color_at_obj(world: &mut MaterialsWorld, matches: &[String]) {
     let pattern = world.patterns.get(&matches[0]).unwrap();
     let val = parse_values_f64(&matches[2]);
     let point = Tuples::point(val[0],val[1],val[2]);
-    let bound_pattern = pattern.borrow();
+    let bound_pattern = pattern.read().unwrap();
     let result = bound_pattern.color_at_object(obj, &point);
     let target = world.tuple.get(&matches[3]).unwrap();
     assert!(result.is_equal(target));
@@ -170,12 +171,12 @@ fn check_prop(world: &mut MaterialsWorld, matches: &[String]) {
     
     match prop {
         "a" => {
-            let prop = pattern.borrow().color_a().clone();
+            let prop = pattern.read().unwrap().color_a().clone();
             let target = world.tuple.get(&matches[2]).unwrap();
             assert!(prop.is_equal(target));
         },
         "b" => {
-            let prop = pattern.borrow().color_b().clone();
+            let prop = pattern.read().unwrap().color_b().clone();
             let target = world.tuple.get(&matches[2]).unwrap();
             assert!(prop.is_equal(target));
         },
@@ -188,7 +189,7 @@ fn check_prop(world: &mut MaterialsWorld, matches: &[String]) {
                     world.matrix.get(&matches[2].to_string()).unwrap().clone()
                 },
             };
-            let borrowed = pattern.borrow();
+            let borrowed = pattern.read().unwrap();
             let target = borrowed.get_transform();
             assert!(ident.is_equal(&target));
         },
@@ -200,7 +201,7 @@ fn set_transform(world: &mut MaterialsWorld, matches: &[String]) {
     let v: Vec<&str> = matches[0].split(", ").collect();
     let s = world.sphere.get(v[0]).unwrap();
     let t = world.matrix.get(v[1]).unwrap();
-    s.borrow_mut().set_transform(t);
+    s.write().unwrap().set_transform(t);
 }
 
 #[given(regex = r"set_transform\((.+)\)")]
@@ -214,7 +215,7 @@ fn given_set_pattern_transform(world: &mut MaterialsWorld, matches: &[String]) {
     let v: Vec<&str> = matches[0].split(", ").collect();
     let s = world.patterns.get(v[0]).unwrap();
     let t = world.matrix.get(v[1]).unwrap();
-    s.borrow_mut().set_transform(t.clone());
+    s.write().unwrap().set_transform(t.clone());
 }
 
 #[when(regex = r"set_transform\((.+)\)")]
@@ -226,13 +227,13 @@ fn when_set_transform(world: &mut MaterialsWorld, matches: &[String]) {
 struct MaterialsWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    sphere: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    sphere: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter:  HashMap<String, IntersectionList>,
     matrix: HashMap<String, Matrix>,
     material: HashMap<String, Material>,
     plight: HashMap<String, PointLight>,
     in_shadow: bool,
-    patterns: HashMap<String, Rc<RefCell<dyn Pattern>>>,
+    patterns: HashMap<String, Arc<RwLock<dyn Pattern>>>,
 }
 
 fn main() {
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use cucumber::{given, when, then, World};
 use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
 use rtxch_lib::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 
 #[given(regex = r"(.+) ← (point|vector|ray|plane|local_intersect|translation|scaling|local_normal_at|rotation_z|material)\((.*)\)")]
@@ -42,7 +42,7 @@ fn create_item(world: &mut RaysWorld, matches: &[String]) {
             let v: Vec<&str> = matches[2].split(", ").collect();
             let s = world.plane.get(&v[0].to_string()).unwrap();
             let r = world.ray.get(&v[1].to_string()).unwrap();
-            let result = s.borrow().intersect_local(r);
+            let result = s.read().unwrap().intersect_local(r);
             let il = IntersectionList::new(result, s);
             world.inter.insert(t, il);
         },
@@ -65,7 +65,7 @@ fn create_item(world: &mut RaysWorld, matches: &[String]) {
             let v: Vec<&str> = matches[2].split(", ").collect();
             let s = world.plane.get(&v[0].to_string()).unwrap();
             let p = world.tuple.get(&v[1].to_string()).unwrap();
-            let n = s.borrow().normal_at_local(p);
+            let n = s.read().unwrap().normal_at_local(p);
             world.tuple.insert(t, n);
         },
         "material" => {
@@ -85,7 +85,7 @@ fn set_ambient(world: &mut RaysWorld, _: &[String]) {
 fn set_mat(world: &mut RaysWorld, _: &[String]) {
     let s = world.plane.get(&"s".to_string()).unwrap();
     let mat = world.material.get_mut(&"m".to_string()).unwrap();
-    s.borrow_mut().set_material(&mat);
+    s.write().unwrap().set_material(&mat);
 }
 
 #[given(regex = r"m ← scale \* rot")]
@@ -99,7 +99,7 @@ fn set_transform(world: &mut RaysWorld, matches: &[String]) {
     let v: Vec<&str> = matches[0].split(", ").collect();
     let s = world.plane.get(v[0]).unwrap();
     let t = world.matrix.get(v[1]).unwrap();
-    s.borrow_mut().set_transform(t);
+    s.write().unwrap().set_transform(t);
 }
 
 #[given(regex = r"set_transform\((.+)\)")]
@@ -137,11 +137,11 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
             match matches[2].as_str() {
                 "identity_matrix" => {
                     let ident = Matrix::new(4);
-                    s.borrow().get_transform().is_equal(&ident);
+                    s.read().unwrap().get_transform().is_equal(&ident);
                 },
                 _ => {
                     let m = world.matrix.get(&matches[2]).unwrap();
-                    s.borrow().get_transform().is_equal(m);
+                    s.read().unwrap().get_transform().is_equal(m);
                 },
             }
         },
@@ -151,12 +151,12 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
                 "material()" => &Material::material(),
                 _ => world.material.get(&matches[2]).unwrap(),
             };
-            assert!(obj.borrow().get_material().is_equal(&target));
+            assert!(obj.read().unwrap().get_material().is_equal(&target));
         },
     "color" => {
         let i = world.material.get(&matches[0]).unwrap();
         let target = world.tuple.get(&matches[2]).unwrap();
-        assert!(i.pattern.borrow().color_a().is_equal(target));
+        assert!(i.pattern.read().unwrap().color_a().is_equal(target));
     },
     "ambient" => {
         let i = world.material.get(&matches[0]).unwrap();
@@ -200,7 +200,7 @@ fn check_sub_prop(world: &mut RaysWorld, matches: &[String]) {
             let i = world.inter.get(&matches[0]).unwrap();
             let obj = i.xs().get(idx).unwrap();
             let target = world.plane.get(&matches[3]).unwrap();
-            assert!(Rc::ptr_eq(obj.object(), &target));
+            assert!(Arc::ptr_eq(obj.object(), &target));
         },
         "material" => {
             let obj = world.plane.get(&matches[0]).unwrap();
@@ -208,12 +208,12 @@ fn check_sub_prop(world: &mut RaysWorld, matches: &[String]) {
                 "material()" => &Material::material(),
                 _ => world.material.get(&matches[3]).unwrap(),
             };
-            assert!(obj.borrow().get_material().is_equal(&target));
+            assert!(obj.read().unwrap().get_material().is_equal(&target));
         },
     "color" => {
         let i = world.material.get(&matches[0]).unwrap();
         let target = world.tuple.get(&matches[2]).unwrap();
-        assert!(i.pattern.borrow().color_a().is_equal(target));
+        assert!(i.pattern.read().unwrap().color_a().is_equal(target));
     },
     "ambient" => {
         let i = world.material.get(&matches[0]).unwrap();
@@ -279,7 +279,7 @@ fn check_norm(world: &mut RaysWorld, matches: &[String]) {
 struct RaysWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    plane: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    plane: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter:  HashMap<String, IntersectionList>,
     matrix: HashMap<String, Matrix>,
     material: HashMap<String, Material>,
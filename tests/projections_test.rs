@@ -0,0 +1,157 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use cucumber::{given, when, then, World};
+use render::render;
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &ProjectionsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+// perspective, orthographic(size), fisheye or equirectangular
+fn parse_projection(input: &str) -> Projection {
+    let rx = cucumber::codegen::Regex::new(r"^orthographic\((.+)\)$").unwrap();
+    if let Some(m) = rx.captures(input) {
+        return Projection::Orthographic { size: m[1].parse::<f64>().unwrap() };
+    }
+    match input {
+        "perspective" => Projection::Perspective,
+        "fisheye" => Projection::Fisheye,
+        "equirectangular" => Projection::Equirectangular,
+        p => panic!("unknown projection {p}"),
+    }
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut ProjectionsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(v[0] as usize, v[1] as usize, v[2]);
+}
+
+#[given(regex = r"^c\.projection ← (.+)$")]
+fn given_projection(world: &mut ProjectionsWorld, matches: &[String]) {
+    world.camera.projection = parse_projection(&matches[0]);
+}
+
+#[then(regex = r"^c\.projection = (.+)$")]
+fn check_projection(world: &mut ProjectionsWorld, matches: &[String]) {
+    assert_eq!(world.camera.projection, parse_projection(&matches[0]));
+}
+
+#[given("c.transform ← rotation_y(π/4) * translation(0, -2, 5)")]
+fn given_camera_transform(world: &mut ProjectionsWorld) {
+    world.camera.transform = Matrix::rotate_y(PI / 4.0) * Matrix::translate(0.0, -2.0, 5.0);
+}
+
+#[given("c.transform ← view_transform(from, to, up)")]
+fn given_view_transform(world: &mut ProjectionsWorld) {
+    let from = world.tuple.get("from").unwrap();
+    let to = world.tuple.get("to").unwrap();
+    let up = world.tuple.get("up").unwrap();
+    world.camera.transform = Matrix::view_transform(from, to, up);
+}
+
+#[given(regex = r"^c\.(aperture_radius|focal_distance) ← (.+)$")]
+fn given_lens(world: &mut ProjectionsWorld, matches: &[String]) {
+    let value = matches[1].parse::<f64>().unwrap();
+    match matches[0].as_str() {
+        "aperture_radius" => world.camera.aperture_radius = value,
+        _ => world.camera.focal_distance = value,
+    }
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut ProjectionsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[when(regex = r"^r ← ray_for_pixel\(c, (\d+), (\d+)\)$")]
+fn when_ray_for_pixel(world: &mut ProjectionsWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    world.ray = Some(Camera::ray_for_pixel(&world.camera, x, y));
+}
+
+#[when(regex = r"^r ← ray_for_pixel_offset\(c, (.+)\)$")]
+fn when_ray_for_pixel_offset(world: &mut ProjectionsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.ray = Some(Camera::ray_for_pixel_offset(&world.camera, v[0] as usize, v[1] as usize, v[2], v[3]));
+}
+
+#[when(regex = r"^r ← ray_for_pixel_lens\(c, (.+)\)$")]
+fn when_ray_for_pixel_lens(world: &mut ProjectionsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.ray = Some(Camera::ray_for_pixel_lens(&world.camera, v[0] as usize, v[1] as usize, v[2], v[3], v[4], v[5]));
+}
+
+#[then(regex = r"^r\.(origin|direction) = (.+)$")]
+fn check_ray(world: &mut ProjectionsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let r = world.ray.as_ref().unwrap();
+    let value = if matches[0] == "origin" { r.origin() } else { r.direction() };
+    assert!(value.is_equal(&target), "{:?}", value);
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut ProjectionsWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given(regex = r"^the emissive sphere at (point\(.+\))$")]
+fn given_emissive_sphere(world: &mut ProjectionsWorld, matches: &[String]) {
+    let p = parse_tuple(world, &matches[0]);
+    let sphere = Sphere::new();
+    {
+        let mut s = sphere.write().unwrap();
+        s.set_transform(&Matrix::translate(p.x, p.y, p.z));
+        let material = s.get_mut_material();
+        material.ambient = 0.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        material.emission = Tuples::color(1.0, 1.0, 1.0);
+    }
+    world.world.add_object(sphere);
+}
+
+#[when("image ← render(c, w)")]
+fn when_render(world: &mut ProjectionsWorld) {
+    world.image = render(&world.camera, &world.world);
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) is (white|black)$")]
+fn check_pixel(world: &mut ProjectionsWorld, matches: &[String]) {
+    let (x, y) = (matches[0].parse::<usize>().unwrap(), matches[1].parse::<usize>().unwrap());
+    let c = world.image.pixel_at(x, y);
+    let target = if matches[2] == "white" { Tuples::color(1.0, 1.0, 1.0) } else { Tuples::color(0.0, 0.0, 0.0) };
+    assert!(c.is_equal(&target), "{:?}", c);
+}
+
+#[derive(Debug, Default, World)]
+struct ProjectionsWorld {
+    camera: Camera,
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    ray: Option<Ray>,
+    image: Canvas,
+}
+
+fn main() {
+    futures::executor::block_on(ProjectionsWorld::run(
+        "tests/features/projections.feature",
+    ));
+}
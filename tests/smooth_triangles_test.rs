@@ -0,0 +1,172 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+#[given(regex = r"(.+) ← (point|vector|ray|smooth_triangle|group|scaling|intersection_with_uv|intersections)\((.*)\)")]
+#[when(regex = r"(.+) ← (local_intersect|intersect|normal_at|prepare_computations)\((.*)\)")]
+fn given_item(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    create_item(world, matches);
+}
+
+fn create_item(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let t = matches[0].clone();
+    let func = matches[1].as_str();
+    let v: Vec<&str> = matches[2].split(", ").collect();
+    match func {
+        "point" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::point(v[0], v[1], v[2]));
+        },
+        "vector" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::vector(v[0], v[1], v[2]));
+        },
+        "scaling" => {
+            let v = parse_values_f64(&matches[2]);
+            world.matrix.insert(t, Matrix::scale(v[0], v[1], v[2]));
+        },
+        "ray" => {
+            let o = world.tuple.get(v[0]).unwrap();
+            let d = world.tuple.get(v[1]).unwrap();
+            world.ray.insert(t, Ray::new(*o, *d));
+        },
+        "smooth_triangle" => {
+            let p: Vec<Tuples> = v.iter().map(|k| *world.tuple.get(*k).unwrap()).collect();
+            let tri = SmoothTriangle::new(p[0], p[1], p[2], p[3], p[4], p[5]);
+            world.shape.insert(t.clone(), tri.clone());
+            world.triangle.insert(t, tri);
+        },
+        "group" => {
+            let g = Group::new();
+            world.shape.insert(t.clone(), g.clone());
+            world.group.insert(t, g);
+        },
+        "intersection_with_uv" => {
+            let s = world.shape.get(v[1]).unwrap();
+            let time = v[0].parse::<f64>().unwrap();
+            let u = v[2].parse::<f64>().unwrap();
+            let w = v[3].parse::<f64>().unwrap();
+            world.intersection.insert(t, Intersection::new_with_uv(time, s, u, w));
+        },
+        "intersections" => {
+            let i: Vec<Intersection> = v.iter().map(|k| world.intersection.get(*k).unwrap().clone()).collect();
+            world.inter.insert(t, IntersectionList::intersections_from_vec(i));
+        },
+        "local_intersect" => {
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            let xs = s.read().unwrap().intersections_local(r, s);
+            world.inter.insert(t, xs);
+        },
+        "intersect" => {
+            let s = world.shape.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            world.inter.insert(t, <dyn Shape>::intersect(s, r));
+        },
+        "normal_at" => {
+            let s = world.shape.get(v[0]).unwrap();
+            let p = world.tuple.get(v[1]).unwrap();
+            let i = world.intersection.get(v[2]).unwrap();
+            world.tuple.insert(t, <dyn Shape>::normal_at_uv(s, p, i.u(), i.v()));
+        },
+        "prepare_computations" => {
+            let i = world.intersection.get(v[0]).unwrap();
+            let r = world.ray.get(v[1]).unwrap();
+            let xs = world.inter.get(v[2]).unwrap();
+            world.comps.insert(t, Intersection::prep_computations(i, r, xs));
+        },
+        _ => panic!("{func} not implemented")
+    }
+}
+
+#[given(regex = r"set_transform\((.+), (.+)\)")]
+fn set_transform(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let s = world.shape.get(&matches[0]).unwrap();
+    let m = world.matrix.get(&matches[1]).unwrap();
+    s.write().unwrap().set_transform(m);
+}
+
+#[given(regex = r"add_child\((.+), (.+)\)")]
+fn add_child(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let g = world.group.get(&matches[0]).unwrap();
+    let s = world.shape.get(&matches[1]).unwrap();
+    Group::add_child(g, s.clone());
+}
+
+#[then(regex = r"^tri\.(p1|p2|p3|n1|n2|n3) = (.+)$")]
+fn check_triangle_prop(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let tri = world.triangle.get("tri").unwrap().read().unwrap();
+    let prop = match matches[0].as_str() {
+        "p1" => tri.p1,
+        "p2" => tri.p2,
+        "p3" => tri.p3,
+        "n1" => tri.n1,
+        "n2" => tri.n2,
+        _ => tri.n3,
+    };
+    let target = world.tuple.get(&matches[1]).unwrap();
+    assert!(prop.is_equal(target));
+}
+
+#[then(regex = r"^xs\.count = (.+)$")]
+fn check_count(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let count = matches[0].parse::<usize>().unwrap();
+    assert_eq!(xs.count(), count);
+}
+
+#[then(regex = r"^xs\[(.+)\]\.(t|u|v) = (.+)$")]
+fn check_xs_prop(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let i = &xs.xs()[matches[0].parse::<usize>().unwrap()];
+    let val = match matches[1].as_str() {
+        "t" => i.t(),
+        "u" => i.u(),
+        _ => i.v(),
+    };
+    let target = matches[2].parse::<f64>().unwrap();
+    assert!(is_equal_f64(val, target), "{val} {target}");
+}
+
+#[then(regex = r"^comps\.(u|v) = (.+)$")]
+fn check_comps_uv(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let comps = world.comps.get("comps").unwrap();
+    let val = if matches[0] == "u" { comps.u } else { comps.v };
+    let target = matches[1].parse::<f64>().unwrap();
+    assert!(is_equal_f64(val, target));
+}
+
+#[then(regex = r"^(n|comps\.normalv) = vector\((.+)\)$")]
+fn check_normal(world: &mut SmoothTrianglesWorld, matches: &[String]) {
+    let n = match matches[0].as_str() {
+        "n" => *world.tuple.get("n").unwrap(),
+        _ => world.comps.get("comps").unwrap().normal_v,
+    };
+    let v = parse_values_f64(&matches[1]);
+    let target = Tuples::vector(v[0], v[1], v[2]);
+    assert!(n.is_equal(&target), "{:?} {:?}", n, target);
+}
+
+#[derive(Debug, Default, World)]
+struct SmoothTrianglesWorld {
+    ray: HashMap<String, Ray>,
+    tuple: HashMap<String, Tuples>,
+    matrix: HashMap<String, Matrix>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
+    triangle: HashMap<String, Arc<RwLock<SmoothTriangle>>>,
+    group: HashMap<String, Arc<RwLock<Group>>>,
+    inter: HashMap<String, IntersectionList>,
+    intersection: HashMap<String, Intersection>,
+    comps: HashMap<String, Computations>,
+}
+
+fn main() {
+    futures::executor::block_on(SmoothTrianglesWorld::run(
+        "tests/features/smooth_triangles.feature",
+    ));
+}
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use cucumber::{given, when, then, World};
 use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
 use rtxch_lib::*;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 
 #[given(regex = r"(.+) ← (glass_sphere|point|vector|ray|sphere|intersect|translation|scaling|normal_at|rotation_z|material)\((.*)\)")]
@@ -89,7 +89,7 @@ fn set_ambient(world: &mut RaysWorld, _: &[String]) {
 fn set_mat(world: &mut RaysWorld, _: &[String]) {
     let s = world.sphere.get(&"s".to_string()).unwrap();
     let mat = world.material.get_mut(&"m".to_string()).unwrap();
-    s.borrow_mut().set_material(&mat);
+    s.write().unwrap().set_material(&mat);
 }
 
 #[given(regex = r"m ← scale \* rot")]
@@ -103,7 +103,7 @@ fn set_transform(world: &mut RaysWorld, matches: &[String]) {
     let v: Vec<&str> = matches[0].split(", ").collect();
     let s = world.sphere.get(v[0]).unwrap();
     let t = world.matrix.get(v[1]).unwrap();
-    s.borrow_mut().set_transform(t);
+    s.write().unwrap().set_transform(t);
 }
 
 #[given(regex = r"set_transform\((.+)\)")]
@@ -123,13 +123,13 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
     match prop {
         "material.transparency" => {
             let r = world.sphere.get(&matches[0]).unwrap();
-            let prop = r.borrow().get_material().transparency;
+            let prop = r.read().unwrap().get_material().transparency;
             let t = &matches[2].parse::<f64>().unwrap();
             assert!(is_equal_f64(prop, *t));
         },
         "material.refractive_index" => {
             let r = world.sphere.get(&matches[0]).unwrap();
-            let prop = r.borrow().get_material().refractive_index;
+            let prop = r.read().unwrap().get_material().refractive_index;
             let t = &matches[2].parse::<f64>().unwrap();
             assert!(is_equal_f64(prop, *t));
         },
@@ -153,11 +153,11 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
             match matches[2].as_str() {
                 "identity_matrix" => {
                     let ident = Matrix::new(4);
-                    s.borrow().get_transform().is_equal(&ident);
+                    s.read().unwrap().get_transform().is_equal(&ident);
                 },
                 _ => {
                     let m = world.matrix.get(&matches[2]).unwrap();
-                    s.borrow().get_transform().is_equal(m);
+                    s.read().unwrap().get_transform().is_equal(m);
                 },
             }
         },
@@ -167,12 +167,12 @@ fn check_prop(world: &mut RaysWorld, matches: &[String]) {
                 "material()" => &Material::material(),
                 _ => world.material.get(&matches[2]).unwrap(),
             };
-            assert!(obj.borrow().get_material().is_equal(&target));
+            assert!(obj.read().unwrap().get_material().is_equal(&target));
         },
     "color" => {
         let i = world.material.get(&matches[0]).unwrap();
         let target = world.tuple.get(&matches[2]).unwrap();
-        assert!(i.pattern.borrow().color_a().is_equal(target));
+        assert!(i.pattern.read().unwrap().color_a().is_equal(target));
     },
     "ambient" => {
         let i = world.material.get(&matches[0]).unwrap();
@@ -216,7 +216,7 @@ fn check_sub_prop(world: &mut RaysWorld, matches: &[String]) {
             let i = world.inter.get(&matches[0]).unwrap();
             let obj = i.xs().get(idx).unwrap();
             let target = world.sphere.get(&matches[3]).unwrap();
-            assert!(Rc::ptr_eq(obj.object(), &target));
+            assert!(Arc::ptr_eq(obj.object(), &target));
         },
         "material" => {
             let obj = world.sphere.get(&matches[0]).unwrap();
@@ -224,12 +224,12 @@ fn check_sub_prop(world: &mut RaysWorld, matches: &[String]) {
                 "material()" => &Material::material(),
                 _ => world.material.get(&matches[3]).unwrap(),
             };
-            assert!(obj.borrow().get_material().is_equal(&target));
+            assert!(obj.read().unwrap().get_material().is_equal(&target));
         },
     "color" => {
         let i = world.material.get(&matches[0]).unwrap();
         let target = world.tuple.get(&matches[2]).unwrap();
-        assert!(i.pattern.borrow().color_a().is_equal(target));
+        assert!(i.pattern.read().unwrap().color_a().is_equal(target));
     },
     "ambient" => {
         let i = world.material.get(&matches[0]).unwrap();
@@ -295,7 +295,7 @@ fn check_norm(world: &mut RaysWorld, matches: &[String]) {
 struct RaysWorld {
     ray: HashMap<String, Ray>,
     tuple: HashMap<String, Tuples>,
-    sphere: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    sphere: HashMap<String, Arc<RwLock<dyn Shape>>>,
     inter:  HashMap<String, IntersectionList>,
     matrix: HashMap<String, Matrix>,
     material: HashMap<String, Material>,
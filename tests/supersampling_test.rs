@@ -0,0 +1,198 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use render::{render_with_settings, sample_offsets, sample_pixel};
+use rtxch_lib::*;
+use rtxch_lib::utils::*;
+
+fn parse_settings(args: &str) -> RenderSettings {
+    if args.is_empty() {
+        return RenderSettings::default();
+    }
+    let v: Vec<&str> = args.split(", ").collect();
+    let sampling = match v[0] {
+        "center" => Sampling::Center,
+        "grid" => Sampling::Grid,
+        "jittered" => Sampling::Jittered,
+        "adaptive" => Sampling::Adaptive,
+        s => panic!("unknown sampling {s}"),
+    };
+    RenderSettings::new(sampling, v[1].parse::<usize>().unwrap())
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut SupersamplingWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given(regex = r"^c ← camera\((.+)\)$")]
+fn given_camera(world: &mut SupersamplingWorld, matches: &[String]) {
+    let val = parse_values_f64(&matches[0]);
+    world.camera = Camera::new(val[0] as usize, val[1] as usize, val[2]);
+}
+
+#[given(regex = r"^(from|to|up) ← (point|vector)\((.+)\)$")]
+fn given_tuple(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[2]);
+    let t = if matches[1] == "point" { Tuples::point(v[0], v[1], v[2]) } else { Tuples::vector(v[0], v[1], v[2]) };
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given("c.transform ← view_transform(from, to, up)")]
+fn given_view_transform(world: &mut SupersamplingWorld) {
+    let from = world.tuple.get("from").unwrap();
+    let to = world.tuple.get("to").unwrap();
+    let up = world.tuple.get("up").unwrap();
+    world.camera.transform = Matrix::view_transform(from, to, up);
+}
+
+#[given(regex = r"^settings ← render_settings\((.*)\)$")]
+fn given_settings(world: &mut SupersamplingWorld, matches: &[String]) {
+    world.settings = parse_settings(&matches[0]);
+}
+
+#[then(regex = r"^settings\.sampling = (center|grid|jittered|adaptive)$")]
+fn check_sampling(world: &mut SupersamplingWorld, matches: &[String]) {
+    let expected = parse_settings(&format!("{}, 1", matches[0])).sampling;
+    assert_eq!(world.settings.sampling, expected);
+}
+
+#[when(regex = r"^\(color, rays\) ← sample_pixel\(c, w, settings, (\d+), (\d+)\)$")]
+fn when_sample_pixel(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v = parse_values_usize(&format!("{}, {}", matches[0], matches[1]));
+    let (color, rays) = sample_pixel(&world.camera, &world.world, &world.settings, v[0], v[1]);
+    world.color = color;
+    world.rays = rays;
+}
+
+#[when(regex = r"^(offsets|offsets2) ← sample_offsets\(settings, (\d+), (\d+)\)$")]
+fn when_sample_offsets(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v = parse_values_usize(&format!("{}, {}", matches[1], matches[2]));
+    world.offsets.insert(matches[0].clone(), sample_offsets(&world.settings, v[0], v[1]));
+}
+
+#[when(regex = r"^(image|expected) ← render\(c, w, (settings|render_settings\(\))\)$")]
+fn when_render(world: &mut SupersamplingWorld, matches: &[String]) {
+    let settings = if matches[1] == "settings" { world.settings.clone() } else { RenderSettings::default() };
+    let image = render_with_settings(&world.camera, &world.world, &settings);
+    world.image.insert(matches[0].clone(), image);
+}
+
+#[when(regex = r"^(image|expected) ← render_with_threads\(c, w, settings, (\d+)\)$")]
+fn when_render_threads(world: &mut SupersamplingWorld, matches: &[String]) {
+    let settings = RenderSettings { threads: matches[1].parse::<usize>().unwrap(), ..world.settings.clone() };
+    let image = render_with_settings(&world.camera, &world.world, &settings);
+    world.image.insert(matches[0].clone(), image);
+}
+
+#[then(regex = r"^color = color\((.+)\)$")]
+fn check_color(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    assert!(world.color.is_equal(&Tuples::color(v[0], v[1], v[2])), "{:?}", world.color);
+}
+
+#[then(regex = r"^rays = (\d+)$")]
+fn check_rays(world: &mut SupersamplingWorld, matches: &[String]) {
+    assert_eq!(world.rays, matches[0].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^offsets = (\(.+\))$")]
+fn check_offsets(world: &mut SupersamplingWorld, matches: &[String]) {
+    let expected: Vec<(f64, f64)> = matches[0].trim_matches(|c| c == '(' || c == ')').split("), (").map(|pair| {
+        let v = parse_values_f64(&pair.to_string());
+        (v[0], v[1])
+    }).collect();
+    let offsets = world.offsets.get("offsets").unwrap();
+    assert_eq!(offsets.len(), expected.len());
+    for (a, b) in offsets.iter().zip(expected.iter()) {
+        assert!(is_equal_f64(a.0, b.0) && is_equal_f64(a.1, b.1), "{:?} != {:?}", a, b);
+    }
+}
+
+#[then("offsets = offsets2")]
+fn check_offsets_equal(world: &mut SupersamplingWorld) {
+    assert_eq!(world.offsets.get("offsets").unwrap(), world.offsets.get("offsets2").unwrap());
+}
+
+#[then(regex = r"^offsets has (\d+) entries$")]
+fn check_offsets_count(world: &mut SupersamplingWorld, matches: &[String]) {
+    assert_eq!(world.offsets.get("offsets").unwrap().len(), matches[0].parse::<usize>().unwrap());
+}
+
+#[then(regex = r"^every offset lies in its own cell of a (\d+)x\d+ grid$")]
+fn check_offsets_cells(world: &mut SupersamplingWorld, matches: &[String]) {
+    let n = matches[0].parse::<usize>().unwrap();
+    let offsets = world.offsets.get("offsets").unwrap();
+    for (k, (dx, dy)) in offsets.iter().enumerate() {
+        let (i, j) = (k % n, k / n);
+        assert!((*dx * n as f64).floor() as usize == i && (*dy * n as f64).floor() as usize == j, "{dx}, {dy}");
+    }
+}
+
+#[then("offsets are not on the cell centers")]
+fn check_offsets_jittered(world: &mut SupersamplingWorld) {
+    let offsets = world.offsets.get("offsets").unwrap();
+    let n = (offsets.len() as f64).sqrt() as usize;
+    let centered = offsets.iter().enumerate().filter(|(k, (dx, _))| {
+        is_equal_f64(*dx, ((k % n) as f64 + 0.5) / n as f64)
+    }).count();
+    assert!(centered < offsets.len());
+}
+
+#[then(regex = r"^color is the average of the (grid|corner and grid) samples of pixel (\d+), (\d+)$")]
+fn check_average(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v = parse_values_usize(&format!("{}, {}", matches[1], matches[2]));
+    let mut offsets = sample_offsets(&world.settings, v[0], v[1]);
+    if matches[0] != "grid" {
+        offsets.extend([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]);
+    }
+    let mut sum = Tuples::color(0.0, 0.0, 0.0);
+    for (dx, dy) in offsets.iter() {
+        let r = Camera::ray_for_pixel_offset(&world.camera, v[0], v[1], *dx, *dy);
+        sum.add(&rtxch_lib::World::color_at(&world.world, &r, MAX_ITERATIONS));
+    }
+    let expected = sum.scale(1.0 / offsets.len() as f64);
+    assert!(world.color.is_equal(&expected));
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) = pixel_at\(expected, (\d+), (\d+)\)$")]
+fn check_pixels_equal(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v: Vec<usize> = matches.iter().map(|m| m.parse::<usize>().unwrap()).collect();
+    let a = world.image.get("image").unwrap().pixel_at(v[0], v[1]);
+    let b = world.image.get("expected").unwrap().pixel_at(v[2], v[3]);
+    assert!(a.is_equal(b));
+}
+
+#[then(regex = r"^pixel_at\(image, (\d+), (\d+)\) is darker than pixel_at\(expected, (\d+), (\d+)\)$")]
+fn check_pixel_darker(world: &mut SupersamplingWorld, matches: &[String]) {
+    let v: Vec<usize> = matches.iter().map(|m| m.parse::<usize>().unwrap()).collect();
+    let a = world.image.get("image").unwrap().pixel_at(v[0], v[1]);
+    let b = world.image.get("expected").unwrap().pixel_at(v[2], v[3]);
+    assert!(a.x + a.y + a.z < b.x + b.y + b.z, "{:?} {:?}", a, b);
+}
+
+#[then("every pixel of image is identical to expected")]
+fn check_identical(world: &mut SupersamplingWorld) {
+    let a = world.image.get("image").unwrap();
+    let b = world.image.get("expected").unwrap();
+    assert!(a.get_pixels() == b.get_pixels());
+}
+
+#[derive(Debug, Default, World)]
+struct SupersamplingWorld {
+    world: rtxch_lib::World,
+    camera: Camera,
+    tuple: HashMap<String, Tuples>,
+    settings: RenderSettings,
+    color: Tuples,
+    rays: usize,
+    offsets: HashMap<String, Vec<(f64, f64)>>,
+    image: HashMap<String, Canvas>,
+}
+
+fn main() {
+    futures::executor::block_on(SupersamplingWorld::run(
+        "tests/features/supersampling.feature",
+    ));
+}
@@ -0,0 +1,140 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::parse_values_f64;
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+// point(..), vector(..), color(..) or the name of a stored tuple
+fn parse_tuple(world: &TransparentShadowsWorld, input: &str) -> Tuples {
+    let rx = cucumber::codegen::Regex::new(r"^(point|vector|color)\((.+)\)$").unwrap();
+    match rx.captures(input) {
+        Some(m) => {
+            let v = parse_values_f64(&m[2].to_string());
+            match &m[1] {
+                "point" => Tuples::point(v[0], v[1], v[2]),
+                "vector" => Tuples::vector(v[0], v[1], v[2]),
+                _ => Tuples::color(v[0], v[1], v[2]),
+            }
+        },
+        None => *world.tuple.get(input).unwrap(),
+    }
+}
+
+#[given("w ← world()")]
+fn given_world(world: &mut TransparentShadowsWorld) {
+    world.world = rtxch_lib::World::new();
+}
+
+#[given("w ← default_world()")]
+fn given_default_world(world: &mut TransparentShadowsWorld) {
+    world.world = rtxch_lib::World::default_world();
+}
+
+#[given(regex = r"^shape ← (sphere|glass_sphere|plane)\(\)$")]
+fn given_shape(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let shape: Arc<RwLock<dyn Shape>> = match matches[0].as_str() {
+        "sphere" => Sphere::new(),
+        "glass_sphere" => Sphere::glass_sphere(),
+        _ => Plane::new(),
+    };
+    world.shape = Some(shape);
+}
+
+#[given(regex = r"^shape\.transform ← translation\((.+)\)$")]
+fn given_transform(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let v = parse_values_f64(&matches[0]);
+    world.shape.as_ref().unwrap().write().unwrap().set_transform(&Matrix::translate(v[0], v[1], v[2]));
+}
+
+#[given(regex = r"^shape\.material\.transparency ← (.+)$")]
+fn given_transparency(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let shape = world.shape.as_ref().unwrap();
+    shape.write().unwrap().get_mut_material().transparency = matches[0].parse::<f64>().unwrap();
+}
+
+#[given(regex = r"^shape\.material\.color ← (.+)$")]
+fn given_color(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let color = parse_tuple(world, &matches[0]);
+    let shape = world.shape.as_ref().unwrap();
+    shape.write().unwrap().get_mut_material().pattern = SingleColorPattern::new(color);
+}
+
+#[given("shape.cast_shadows ← false")]
+fn given_no_shadows(world: &mut TransparentShadowsWorld) {
+    world.shape.as_ref().unwrap().write().unwrap().set_cast_shadows(false);
+}
+
+#[given("shape is added to w")]
+fn given_shape_added(world: &mut TransparentShadowsWorld) {
+    world.world.add_object(world.shape.clone().unwrap());
+}
+
+#[given(regex = r"^light ← point_light\((.+\)), (.+\))\)$")]
+fn given_light(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let position = parse_tuple(world, &matches[0]);
+    let intensity = parse_tuple(world, &matches[1]);
+    world.light = Some(lights::point_light(&position, &intensity));
+}
+
+#[given("light is added to w")]
+fn given_light_added(world: &mut TransparentShadowsWorld) {
+    world.world.add_light(world.light.clone().unwrap());
+}
+
+#[given(regex = r"^(\w+) ← ((?:point|vector|color)\([-\d., ]+\))$")]
+fn given_tuple(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let t = parse_tuple(world, &matches[1]);
+    world.tuple.insert(matches[0].clone(), t);
+}
+
+#[given(regex = r"^r ← ray\((.+\)), (.+\))\)$")]
+fn given_ray(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let origin = parse_tuple(world, &matches[0]);
+    let direction = parse_tuple(world, &matches[1]);
+    world.ray = Some(Ray::new(origin, direction));
+}
+
+#[when("transmission ← intensity_at(w, pt, light)")]
+fn when_intensity_at(world: &mut TransparentShadowsWorld) {
+    let pt = world.tuple.get("pt").unwrap();
+    let transmission = rtxch_lib::World::intensity_at(&world.world, pt, world.light.as_ref().unwrap());
+    world.tuple.insert("transmission".to_string(), transmission);
+}
+
+#[when("c ← color_at(w, r)")]
+fn when_color_at(world: &mut TransparentShadowsWorld) {
+    let c = rtxch_lib::World::color_at(&world.world, world.ray.as_ref().unwrap(), MAX_ITERATIONS);
+    world.tuple.insert("c".to_string(), c);
+}
+
+#[then(regex = r"^(transmission|c) = (.+)$")]
+fn check_tuple(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let target = parse_tuple(world, &matches[1]);
+    let t = world.tuple.get(&matches[0]).unwrap();
+    assert!(t.is_equal(&target), "{:?}", t);
+}
+
+#[then(regex = r"^is_shadowed\(w, pt, light\) is (true|false)$")]
+fn check_shadowed(world: &mut TransparentShadowsWorld, matches: &[String]) {
+    let pt = world.tuple.get("pt").unwrap();
+    let shadowed = rtxch_lib::World::is_shadowed(&world.world, pt, world.light.as_ref().unwrap());
+    assert_eq!(shadowed, matches[0] == "true");
+}
+
+#[derive(Debug, Default, World)]
+struct TransparentShadowsWorld {
+    world: rtxch_lib::World,
+    tuple: HashMap<String, Tuples>,
+    shape: Option<Arc<RwLock<dyn Shape>>>,
+    light: Option<PointLight>,
+    ray: Option<Ray>,
+}
+
+fn main() {
+    futures::executor::block_on(TransparentShadowsWorld::run(
+        "tests/features/transparent_shadows.feature",
+    ));
+}
@@ -0,0 +1,128 @@
+extern crate rtxch_lib;
+
+use std::collections::HashMap;
+use cucumber::{given, when, then, World};
+use rtxch_lib::utils::{parse_values_f64, is_equal_f64};
+use rtxch_lib::*;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+#[given(regex = r"(.+) ← (point|vector|ray|triangle)\((.*)\)")]
+#[when(regex = r"(.+) ← (local_intersect|local_normal_at|intersection_with_uv)\((.*)\)")]
+fn given_item(world: &mut TrianglesWorld, matches: &[String]) {
+    create_item(world, matches);
+}
+
+fn create_item(world: &mut TrianglesWorld, matches: &[String]) {
+    let t = matches[0].clone();
+    let func = matches[1].as_str();
+    match func {
+        "point" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::point(v[0], v[1], v[2]));
+        },
+        "vector" => {
+            let v = parse_values_f64(&matches[2]);
+            world.tuple.insert(t, Tuples::vector(v[0], v[1], v[2]));
+        },
+        "ray" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let o = world.tuple.get(v[0]).unwrap();
+            let d = world.tuple.get(v[1]).unwrap();
+            world.ray.insert(t, Ray::new(*o, *d));
+        },
+        "triangle" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let p: Vec<Tuples> = v.iter().map(|k| *world.tuple.get(*k).unwrap()).collect();
+            world.triangle.insert(t, Triangle::new(p[0], p[1], p[2]));
+        },
+        "local_intersect" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s: Arc<RwLock<dyn Shape>> = world.triangle.get(v[0]).unwrap().clone();
+            let r = world.ray.get(v[1]).unwrap();
+            let xs = s.read().unwrap().intersections_local(r, &s);
+            world.inter.insert(t, xs);
+        },
+        "local_normal_at" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s = world.triangle.get(v[0]).unwrap();
+            let p = world.tuple.get(v[1]).unwrap();
+            let n = s.read().unwrap().normal_at_local(p);
+            world.tuple.insert(t, n);
+        },
+        "intersection_with_uv" => {
+            let v: Vec<&str> = matches[2].split(", ").collect();
+            let s: Arc<RwLock<dyn Shape>> = world.triangle.get(v[1]).unwrap().clone();
+            let time = v[0].parse::<f64>().unwrap();
+            let u = v[2].parse::<f64>().unwrap();
+            let w = v[3].parse::<f64>().unwrap();
+            world.intersection.insert(t, Intersection::new_with_uv(time, &s, u, w));
+        },
+        _ => panic!("{func} not implemented")
+    }
+}
+
+#[then(regex = r"^t\.(p1|p2|p3|e1|e2|normal) = (.+)$")]
+fn check_triangle_prop(world: &mut TrianglesWorld, matches: &[String]) {
+    let tri = world.triangle.get("t").unwrap().read().unwrap();
+    let prop = match matches[0].as_str() {
+        "p1" => tri.p1,
+        "p2" => tri.p2,
+        "p3" => tri.p3,
+        "e1" => tri.e1,
+        "e2" => tri.e2,
+        _ => tri.normal,
+    };
+    let target = if matches[1].starts_with("vector") {
+        let v = parse_values_f64(&matches[1].trim_start_matches("vector(").trim_end_matches(')').to_string());
+        Tuples::vector(v[0], v[1], v[2])
+    } else {
+        *world.tuple.get(&matches[1]).unwrap()
+    };
+    assert!(prop.is_equal(&target), "{:?} {:?}", prop, target);
+}
+
+#[then(regex = r"^(n1|n2|n3) = t\.normal$")]
+fn check_normal(world: &mut TrianglesWorld, matches: &[String]) {
+    let n = world.tuple.get(&matches[0]).unwrap();
+    let tri = world.triangle.get("t").unwrap().read().unwrap();
+    assert!(n.is_equal(&tri.normal));
+}
+
+#[then(regex = r"^xs\.count = (.+)$")]
+fn check_count(world: &mut TrianglesWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let count = matches[0].parse::<usize>().unwrap();
+    assert_eq!(xs.count(), count);
+}
+
+#[then(regex = r"^xs\[(.+)\]\.t = (.+)$")]
+fn check_t(world: &mut TrianglesWorld, matches: &[String]) {
+    let xs = world.inter.get("xs").unwrap();
+    let idx = matches[0].parse::<usize>().unwrap();
+    let target = matches[1].parse::<f64>().unwrap();
+    assert!(is_equal_f64(xs.xs()[idx].t(), target));
+}
+
+#[then(regex = r"^i\.(u|v) = (.+)$")]
+fn check_uv(world: &mut TrianglesWorld, matches: &[String]) {
+    let i = world.intersection.get("i").unwrap();
+    let target = matches[1].parse::<f64>().unwrap();
+    let val = if matches[0] == "u" { i.u() } else { i.v() };
+    assert!(is_equal_f64(val, target));
+}
+
+#[derive(Debug, Default, World)]
+struct TrianglesWorld {
+    ray: HashMap<String, Ray>,
+    tuple: HashMap<String, Tuples>,
+    triangle: HashMap<String, Arc<RwLock<Triangle>>>,
+    inter: HashMap<String, IntersectionList>,
+    intersection: HashMap<String, Intersection>,
+}
+
+fn main() {
+    futures::executor::block_on(TrianglesWorld::run(
+        "tests/features/triangles.feature",
+    ));
+}
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 use cucumber::{given, when, then, World, gherkin::Step};
 use rtxch_lib::*;
 use rtxch_lib::utils::parse_values_f64;
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 #[given(regex = r"w ← world()")]
 fn given_world(world: &mut WorldWorld, _: &[String]) {
@@ -20,25 +20,25 @@ fn given_default_world(world: &mut WorldWorld, _: &[String]) {
 #[given(regex = r"(shape) ← plane\(\).+$")]
 fn given_plane(world: &mut WorldWorld, matches: &[String]) {
     let plane = Plane::new();
-    plane.borrow_mut().set_transform(&Matrix::translate(0.0,-1.0,0.0));
-    plane.borrow_mut().get_mut_material().reflective = 0.5;
-    plane.borrow_mut().get_mut_material().refractive_index = 1.5;
+    plane.write().unwrap().set_transform(&Matrix::translate(0.0,-1.0,0.0));
+    plane.write().unwrap().get_mut_material().reflective = 0.5;
+    plane.write().unwrap().get_mut_material().refractive_index = 1.5;
     world.shape.insert(matches[0].to_string(), plane);
 }
 
 #[given(regex = r"(lower) ← plane\(\).+$")]
 fn given_lower_plane(world: &mut WorldWorld, matches: &[String]) {
     let plane = Plane::new();
-    plane.borrow_mut().set_transform(&Matrix::translate(0.0,-1.0,0.0));
-    plane.borrow_mut().get_mut_material().reflective = 1.0;
+    plane.write().unwrap().set_transform(&Matrix::translate(0.0,-1.0,0.0));
+    plane.write().unwrap().get_mut_material().reflective = 1.0;
     world.shape.insert(matches[0].to_string(), plane);
 }
 
 #[given(regex = r"(upper) ← plane\(\).+$")]
 fn given_upper_plane(world: &mut WorldWorld, matches: &[String]) {
     let plane = Plane::new();
-    plane.borrow_mut().set_transform(&Matrix::translate(0.0,1.0,0.0));
-    plane.borrow_mut().get_mut_material().reflective = 1.0;
+    plane.write().unwrap().set_transform(&Matrix::translate(0.0,1.0,0.0));
+    plane.write().unwrap().get_mut_material().reflective = 1.0;
     world.shape.insert(matches[0].to_string(), plane);
 }
 
@@ -51,7 +51,7 @@ fn given_item(world: &mut WorldWorld, matches: &[String]) {
 #[given(regex = r"^(floor|ball) ← (plane|sphere)\(\) with:$")]
 fn floor_plane(world: &mut WorldWorld, step: &Step, matches: &[String]) {
     let name = &matches[0];
-    let shape: Rc<RefCell<dyn Shape>> = match matches[1].as_str() {
+    let shape: Arc<RwLock<dyn Shape>> = match matches[1].as_str() {
         "plane" => Plane::new(),
         "sphere" => Sphere::new(),
         _ => panic!(),
@@ -75,22 +75,22 @@ fn floor_plane(world: &mut WorldWorld, step: &Step, matches: &[String]) {
                             },
                             _ => panic!("transform not implemented {fun}"),
                         };
-                        shape.borrow_mut().set_transform(&transform);
+                        shape.write().unwrap().set_transform(&transform);
                     } else {
                         panic!("failed to match transform: {:?}", row[1]);
                     }
                 },
-                "material.transparency" => shape.borrow_mut().get_mut_material().transparency = val.unwrap(),
-                "material.refractive_index" => shape.borrow_mut().get_mut_material().refractive_index = val.unwrap(),
+                "material.transparency" => shape.write().unwrap().get_mut_material().transparency = val.unwrap(),
+                "material.refractive_index" => shape.write().unwrap().get_mut_material().refractive_index = val.unwrap(),
                 "material.color" => {
                     let rx = cucumber::codegen::Regex::new(r"\((.+)\)").unwrap();
                     let m = rx.captures(&row[1]).unwrap();
                     let values = parse_values_f64(&m[1].to_string());
                     let color = Tuples::color(values[0], values[1], values[2]);
-                    shape.borrow_mut().get_mut_material().pattern = SingleColorPattern::new(color);
+                    shape.write().unwrap().get_mut_material().pattern = SingleColorPattern::new(color);
                 },
-                "material.ambient" => shape.borrow_mut().get_mut_material().ambient = val.unwrap(),
-                "material.reflective" => shape.borrow_mut().get_mut_material().reflective = val.unwrap(),
+                "material.ambient" => shape.write().unwrap().get_mut_material().ambient = val.unwrap(),
+                "material.reflective" => shape.write().unwrap().get_mut_material().reflective = val.unwrap(),
                 _ => panic!("Prop: {prop} not implemented"),
             }
         }
@@ -107,10 +107,10 @@ fn shape_has(world: &mut WorldWorld, step: &Step, matches: &[String]) {
             let prop = &row[0];
             let val = row[1].parse::<f64>();
             match prop.as_str() {
-                "material.transparency" => shape.borrow_mut().get_mut_material().transparency = val.unwrap(),
-                "material.refractive_index" => shape.borrow_mut().get_mut_material().refractive_index = val.unwrap(),
-                "material.pattern" => shape.borrow_mut().get_mut_material().pattern = TestPattern::new(),
-                "material.ambient" => shape.borrow_mut().get_mut_material().ambient = val.unwrap(),
+                "material.transparency" => shape.write().unwrap().get_mut_material().transparency = val.unwrap(),
+                "material.refractive_index" => shape.write().unwrap().get_mut_material().refractive_index = val.unwrap(),
+                "material.pattern" => shape.write().unwrap().get_mut_material().pattern = TestPattern::new(),
+                "material.ambient" => shape.write().unwrap().get_mut_material().ambient = val.unwrap(),
                 _ => panic!("Prop: {prop} not implemented"),
             }
         }
@@ -124,7 +124,7 @@ fn sphere1(world: &mut WorldWorld) {
     material.pattern = SingleColorPattern::new(Tuples::color(0.8,1.0,0.6));
     material.diffuse = 0.7;
     material.specular = 0.2;
-    sphere.borrow_mut().set_material(&material);
+    sphere.write().unwrap().set_material(&material);
 
     world.shape.insert("s1".to_string(), sphere);
 }
@@ -133,7 +133,7 @@ fn sphere1(world: &mut WorldWorld) {
 fn sphere2(world: &mut WorldWorld) {
     let sphere = Sphere::new();
     let transform = Matrix::scale(0.5, 0.5, 0.5);
-    sphere.borrow_mut().set_transform(&transform);
+    sphere.write().unwrap().set_transform(&transform);
 
     world.shape.insert("s2".to_string(), sphere);
 }
@@ -142,7 +142,7 @@ fn sphere2(world: &mut WorldWorld) {
 fn sphere2_alter(world: &mut WorldWorld) {
     let sphere = Sphere::new();
     let transform = Matrix::translate(0.0,0.0,10.0);
-    sphere.borrow_mut().set_transform(&transform);
+    sphere.write().unwrap().set_transform(&transform);
 
     world.shape.insert("s2".to_string(), sphere);
 }
@@ -164,14 +164,14 @@ fn create_item(world: &mut WorldWorld, matches: &[String]) {
             let w = &world.world;
             let comps = world.comps.get(&v[1].to_string()).unwrap();
             let remaining= if v.len() == 3 { v[2].parse::<i32>().unwrap() } else { MAX_ITERATIONS };
-            world.tuple.insert(t, rtxch_lib::World::reflected_color(w, comps, remaining));
+            world.tuple.insert(t, rtxch_lib::World::reflected_color(w, comps, remaining, MAX_ITERATIONS, None));
         },
         "refracted_color" => {
             let v: Vec<&str> = matches[2].split(", ").collect();
             let w = &world.world;
             let comps = world.comps.get(&v[1].to_string()).unwrap();
             let remaining= if v.len() == 3 { v[2].parse::<i32>().unwrap() } else { MAX_ITERATIONS };
-            world.tuple.insert(t, rtxch_lib::World::refracted_color(w, comps, remaining));
+            world.tuple.insert(t, rtxch_lib::World::refracted_color(w, comps, remaining, MAX_ITERATIONS, None));
         },
         "point" => {
             let v = parse_values_f64(&matches[2]);
@@ -235,7 +235,7 @@ fn create_item(world: &mut WorldWorld, matches: &[String]) {
         "shade_hit" => {
             let w = &world.world;
             let comps = world.comps.get(&"comps".to_string()).unwrap();
-            let hit = rtxch_lib::World::shade_hit(w, comps, MAX_ITERATIONS);
+            let hit = rtxch_lib::World::shade_hit(w, comps, MAX_ITERATIONS, MAX_ITERATIONS, None);
             world.tuple.insert(t, hit);
         },
         "color_at" => {
@@ -255,27 +255,27 @@ fn create_item(world: &mut WorldWorld, matches: &[String]) {
 #[given(regex = r"(s|s.|shape|plane|lower|upper|floor|ball) is added to w")]
 fn add_sphere(world: &mut WorldWorld, matches: &[String]) {
     let sphere = world.shape.get(&matches[0]).unwrap();
-    world.world.add_object(Rc::clone(sphere));
+    world.world.add_object(Arc::clone(sphere));
 }
 
 #[given(regex = r"(.+) ← the (first|second) object in w")]
 fn first(world: &mut WorldWorld, matches: &[String]) {
     let idx = if matches[1].as_str() == "first" { 0 } else { 1 };
-    let shape = Rc::clone(world.world.get_objects().get(idx).unwrap());
+    let shape = Arc::clone(world.world.get_objects().get(idx).unwrap());
     world.shape.insert(matches[0].clone(), shape);
 }
 
 #[given(regex = r"(.+).material.ambient ← 1")]
 fn set_ambient(world: &mut WorldWorld, matches: &[String]) {
     let sphere = world.shape.get(&matches[0]).unwrap();
-    sphere.borrow_mut().get_mut_material().ambient = 1.0;
+    sphere.write().unwrap().get_mut_material().ambient = 1.0;
 }
 
 #[given(regex = r"w.light ← light")]
 fn set_light(world: &mut WorldWorld, _: &[String]) {
     let light = world.plight.get(&"light".to_string()).unwrap();
     world.world.remove_lights();
-    world.world.add_point_light(light.clone());
+    world.world.add_light(light.clone());
 }
 
 
@@ -291,8 +291,9 @@ fn intershape(world: &mut WorldWorld, matches: &[String]) {
 #[then(regex = r"w.light = light")]
 fn then_light(world: &mut WorldWorld, _: &[String]) {
     let light = world.plight.get(&"light".to_string()).unwrap();
-    let world_light = world.world.get_point_lights().get(0).unwrap();
-    assert!(world_light.is_equal(&light));
+    let world_light = world.world.get_lights().get(0).unwrap();
+    assert!(world_light.get_type() == "PointLight");
+    assert!(world_light.intensity().is_equal(light.intensity()) && world_light.distance_to_light(light.position()) == 0.0);
 }
 
 #[then(regex = r"color_at\(w, r\) should terminate successfully")]
@@ -324,14 +325,14 @@ fn check_shadow(world: &mut WorldWorld, matches: &[String]) {
         "false" => false,
         _ => panic!(),
     };
-    assert!(rtxch_lib::World::is_shadowed(&world.world, p, world.world.get_point_lights().get(0).unwrap()) == target);
+    assert!(rtxch_lib::World::is_shadowed(&world.world, p, world.world.get_lights()[0].as_ref()) == target);
 }
 
 #[then(regex = r"c = inner.material.color")]
 fn check_inner_color(world: &mut WorldWorld, _: &[String]) {
     let sphere = world.shape.get(&"inner".to_string()).unwrap();
     let c = world.tuple.get(&"c".to_string()).unwrap();
-    assert!(sphere.borrow().get_material().pattern.borrow().color_a().is_equal(c));
+    assert!(sphere.read().unwrap().get_material().pattern.read().unwrap().color_a().is_equal(c));
 }
 
 #[then(regex = r"(comps)\.(t|object|point|eyev|normalv|inside) = (.+)")]
@@ -346,7 +347,7 @@ fn check_prop_comps(world: &mut WorldWorld, matches: &[String]) {
         },
         "object" => {
             let i = world.inter.get(&"i".to_string()).unwrap();
-            assert!(Rc::ptr_eq(i.object(), &comps.object));
+            assert!(Arc::ptr_eq(i.object(), &comps.object));
         },
         "point" => {
             let target = world.tuple.get(&matches[2]).unwrap();
@@ -380,7 +381,7 @@ fn no_obj(world: &mut WorldWorld, _: &[String]) {
 
 #[then(regex = r"w has no light source")]
 fn no_light(world: &mut WorldWorld, _: &[String]) {
-    assert!(world.world.get_point_lights().len() == 0);
+    assert!(world.world.get_lights().len() == 0);
 }
 
 #[then(regex = r"xs\.(count) = (.+)")]
@@ -417,7 +418,7 @@ struct WorldWorld {
     world: rtxch_lib::World,
     plight: HashMap<String, PointLight>,
     tuple: HashMap<String, Tuples>,
-    shape: HashMap<String, Rc<RefCell<dyn Shape>>>,
+    shape: HashMap<String, Arc<RwLock<dyn Shape>>>,
     ray: HashMap<String, Ray>,
     inter_list: HashMap<String, IntersectionList>,
     inter: HashMap<String, Intersection>,